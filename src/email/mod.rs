@@ -0,0 +1,106 @@
+//! Outgoing email
+//! Messages are built from templates and handed to a queue that delivers
+//! them in the background through a transport, retrying on failure
+
+pub mod templates;
+mod queue;
+mod transport;
+
+use std::sync::Arc;
+
+pub use queue::{EmailQueue, EmailStatus};
+pub use transport::{FileTransport, SmtpTransport, Transport};
+
+/// Email ready to be sent
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    /// Plain text body
+    pub text: String,
+    /// HTML body
+    pub html: String,
+}
+
+/// Public address of the site, used for links in emails
+/// Read from `SITE_URL`, defaults to the local development server
+pub fn site_url() -> String {
+    std::env::var("SITE_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| "http://127.0.0.1:3000".to_string())
+}
+
+/// Picks the transport from the environment
+/// Uses SMTP when `SMTP_HOST` is set, otherwise writes emails to the `MAIL_OUTBOX` directory
+pub fn transport_from_env() -> Arc<dyn Transport> {
+    let from = std::env::var("MAIL_FROM").unwrap_or_else(|_| "MyStore <shop@localhost>".to_string());
+
+    if let Ok(host) = std::env::var("SMTP_HOST") {
+        let port = std::env::var("SMTP_PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(587);
+        let credentials = std::env::var("SMTP_USERNAME")
+            .ok()
+            .map(|username| (username, std::env::var("SMTP_PASSWORD").unwrap_or_default()));
+        match SmtpTransport::new(&host, port, credentials, &from) {
+            Ok(transport) => return Arc::new(transport),
+            Err(error) => tracing::error!("Invalid SMTP settings, using the outbox instead: {}", error),
+        }
+    }
+
+    let dir = std::env::var("MAIL_OUTBOX").unwrap_or_else(|_| "outbox".to_string());
+    Arc::new(FileTransport::new(dir.into(), &from))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::models::{Currency, Order, SiteSettings};
+    use super::*;
+
+    /// Reads the messages written to an outbox, with quoted-printable line breaks joined
+    fn outbox_messages(dir: &std::path::Path) -> Vec<String> {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| std::fs::read_to_string(entry.ok()?.path()).ok())
+                    .map(|message| message.replace("=\r\n", ""))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn order_confirmation_is_written_to_the_outbox() {
+        let dir = std::env::temp_dir().join(format!("outbox-test-{}", uuid::Uuid::new_v4().simple()));
+        let queue = EmailQueue::start(Arc::new(FileTransport::new(dir.clone(), "Shop <shop@localhost>")));
+
+        let currency = Currency::new("USD".to_string(), "US Dollar".to_string(), "$".to_string(), 1.0, "en-US".to_string(), 2);
+        let mut order = Order::new(
+            1001,
+            "Ann <b>Lee</b>".to_string(),
+            "ann@example.com".to_string(),
+            "1 Main St\nSpringfield".to_string(),
+            "US-CA".to_string(),
+            "USD".to_string(),
+        );
+        order.total = 42.0;
+        queue.send(templates::order_confirmation(&SiteSettings::default(), &order, &currency));
+
+        for _ in 0..50 {
+            if queue.records()[0].status != EmailStatus::Queued {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let messages = outbox_messages(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(queue.records()[0].status, EmailStatus::Sent);
+        assert_eq!(messages.len(), 1);
+        let message = &messages[0];
+        assert!(message.contains("To: ann@example.com"));
+        assert!(message.contains("Subject: Order #1001 confirmed"));
+        assert!(message.contains("Ann &lt;b&gt;Lee&lt;/b&gt;"));
+        assert!(!message.contains("<p>Hi Ann <b>Lee</b>"));
+        assert!(message.contains("1 Main St<br>Springfield"));
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+use super::{Email, Transport};
+
+/// Attempts per email before it is marked as failed
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry, doubled after every failed attempt
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Number of delivery records kept for the admin panel
+const LOG_SIZE: usize = 200;
+
+/// Delivery state of a queued email
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmailStatus {
+    Queued,
+    Sent,
+    Failed,
+}
+
+impl EmailStatus {
+    /// Human readable name of the status
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Queued => "Queued",
+            Self::Sent => "Sent",
+            Self::Failed => "Failed",
+        }
+    }
+}
+
+/// Delivery record of an email
+#[derive(Debug, Clone)]
+pub struct EmailRecord {
+    pub id: String,
+    pub to: String,
+    pub subject: String,
+    pub status: EmailStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub queued_at: DateTime<Utc>,
+}
+
+/// Background send queue
+/// Queuing never fails, so a broken mail server can't break a request
+#[derive(Clone)]
+pub struct EmailQueue {
+    sender: mpsc::UnboundedSender<(String, Email)>,
+    log: Arc<RwLock<Vec<EmailRecord>>>,
+}
+
+impl EmailQueue {
+    /// Starts the delivery worker on the tokio runtime
+    pub fn start(transport: Arc<dyn Transport>) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<(String, Email)>();
+        let log: Arc<RwLock<Vec<EmailRecord>>> = Arc::new(RwLock::new(Vec::new()));
+
+        let worker_log = log.clone();
+        tokio::spawn(async move {
+            while let Some((id, email)) = receiver.recv().await {
+                // Each email retries on its own so one failing address doesn't hold up the rest
+                tokio::spawn(deliver(transport.clone(), worker_log.clone(), id, email));
+            }
+        });
+
+        Self { sender, log }
+    }
+
+    /// Queues an email for delivery
+    pub fn send(&self, email: Email) {
+        let id = uuid::Uuid::new_v4().to_string();
+        {
+            let mut log = self.log.write().unwrap();
+            log.push(EmailRecord {
+                id: id.clone(),
+                to: email.to.clone(),
+                subject: email.subject.clone(),
+                status: EmailStatus::Queued,
+                attempts: 0,
+                last_error: None,
+                queued_at: Utc::now(),
+            });
+            if log.len() > LOG_SIZE {
+                let excess = log.len() - LOG_SIZE;
+                log.drain(..excess);
+            }
+        }
+        if self.sender.send((id, email)).is_err() {
+            tracing::error!("Email queue worker has stopped");
+        }
+    }
+
+    /// Recent delivery records, newest first
+    pub fn records(&self) -> Vec<EmailRecord> {
+        self.log.read().unwrap().iter().rev().cloned().collect()
+    }
+}
+
+/// Sends one email, retrying with a growing delay
+async fn deliver(transport: Arc<dyn Transport>, log: Arc<RwLock<Vec<EmailRecord>>>, id: String, email: Email) {
+    let mut delay = RETRY_DELAY;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let (attempt_transport, attempt_email) = (transport.clone(), email.clone());
+        let result = tokio::task::spawn_blocking(move || attempt_transport.send(&attempt_email))
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+
+        let status = match &result {
+            Ok(()) => EmailStatus::Sent,
+            Err(_) if attempt == MAX_ATTEMPTS => EmailStatus::Failed,
+            Err(_) => EmailStatus::Queued,
+        };
+        if let Some(record) = log.write().unwrap().iter_mut().find(|r| r.id == id) {
+            record.status = status;
+            record.attempts = attempt;
+            record.last_error = result.as_ref().err().cloned();
+        }
+
+        match result {
+            Ok(()) => return,
+            Err(error) => {
+                tracing::warn!("Sending \"{}\" to {} failed (attempt {}): {}", email.subject, email.to, attempt, error);
+                if attempt < MAX_ATTEMPTS {
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+    }
+}
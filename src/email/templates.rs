@@ -0,0 +1,205 @@
+//! Email templates
+//! Every message has a plain text and an HTML version built from the same data
+
+use crate::models::{Comment, Currency, Customer, Order, Post, SiteSettings};
+use crate::html::escape_html;
+use super::{site_url, Email};
+
+/// Wraps HTML email content with the shared header and footer
+/// The footer names the site and its contact address
+fn html_layout(settings: &SiteSettings, title: &str, content: &str) -> String {
+    let contact = if settings.contact_email.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"<br>Questions? Write to <a href="mailto:{0}">{0}</a>"#,
+            escape_html(&settings.contact_email)
+        )
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>{}</title>
+</head>
+<body style="font-family: Arial, sans-serif; color: #2d3748; max-width: 600px; margin: 0 auto;">
+    <h1 style="color: #667eea;">{}</h1>
+    {}
+    <p style="color: #718096; font-size: 12px;">&copy; {}{}</p>
+</body>
+</html>"#,
+        escape_html(title),
+        escape_html(&settings.site_name),
+        content,
+        escape_html(&settings.site_name),
+        contact
+    )
+}
+
+/// Renders the items and totals of an order as (text, html)
+fn order_summary(order: &Order, currency: &Currency) -> (String, String) {
+    let mut text = String::new();
+    let mut rows = String::new();
+    for item in &order.items {
+        text.push_str(&format!("{} x {}  {}\n", item.quantity, item.name, currency.format(item.total)));
+        rows.push_str(&format!(
+            "<tr><td>{} &times; {}</td><td align=\"right\">{}</td></tr>",
+            item.quantity, escape_html(&item.name), currency.format(item.total)
+        ));
+    }
+
+    let mut totals = vec![("Subtotal".to_string(), currency.format(order.subtotal))];
+    if order.discount > 0.0 {
+        totals.push((
+            format!("Discount ({})", order.coupon_code.as_deref().unwrap_or("")),
+            format!("-{}", currency.format(order.discount)),
+        ));
+    }
+    if let Some(method) = &order.shipping_method {
+        totals.push((format!("Shipping ({})", method), currency.format(order.shipping)));
+    }
+    for tax_line in &order.tax_lines {
+        totals.push((tax_line.label(), currency.format(tax_line.amount)));
+    }
+    totals.push(("Total".to_string(), currency.format(order.total)));
+
+    for (label, amount) in totals {
+        text.push_str(&format!("{}: {}\n", label, amount));
+        rows.push_str(&format!("<tr><th align=\"left\">{}</th><td align=\"right\">{}</td></tr>", escape_html(&label), amount));
+    }
+
+    (text, format!(r#"<table width="100%" cellpadding="4">{}</table>"#, rows))
+}
+
+/// Confirmation sent when an order is placed
+pub fn order_confirmation(settings: &SiteSettings, order: &Order, currency: &Currency) -> Email {
+    let link = format!("{}/order/{}", site_url(), order.id);
+    let (summary_text, summary_html) = order_summary(order, currency);
+
+    Email {
+        to: order.email.clone(),
+        subject: format!("Order #{} confirmed", order.number),
+        text: format!(
+            "Hi {},\n\nThank you for your order! We'll let you know when it ships.\n\n{}\nShipping to:\n{}\n\nView your order: {}\n",
+            order.customer_name, summary_text, order.address, link
+        ),
+        html: html_layout(
+            settings,
+            &format!("Order #{} confirmed", order.number),
+            &format!(
+                r#"<p>Hi {},</p>
+    <p>Thank you for your order! We'll let you know when it ships.</p>
+    {}
+    <h3>Shipping to</h3>
+    <p>{}</p>
+    <p><a href="{}">View your order</a></p>"#,
+                escape_html(&order.customer_name), summary_html, escape_html(&order.address).replace('\n', "<br>"), link
+            ),
+        ),
+    }
+}
+
+/// Notification sent when an order is marked as shipped
+pub fn shipping_notification(settings: &SiteSettings, order: &Order) -> Email {
+    let link = format!("{}/order/{}", site_url(), order.id);
+    let method = order.shipping_method.as_deref().unwrap_or("our shipping partner");
+
+    Email {
+        to: order.email.clone(),
+        subject: format!("Order #{} has shipped", order.number),
+        text: format!(
+            "Hi {},\n\nGood news: your order #{} is on its way with {}.\n\nShipping to:\n{}\n\nView your order: {}\n",
+            order.customer_name, order.number, method, order.address, link
+        ),
+        html: html_layout(
+            settings,
+            &format!("Order #{} has shipped", order.number),
+            &format!(
+                r#"<p>Hi {},</p>
+    <p>Good news: your order #{} is on its way with {}.</p>
+    <h3>Shipping to</h3>
+    <p>{}</p>
+    <p><a href="{}">View your order</a></p>"#,
+                escape_html(&order.customer_name), order.number, escape_html(method),
+                escape_html(&order.address).replace('\n', "<br>"), link
+            ),
+        ),
+    }
+}
+
+/// Password reset link for a customer account
+pub fn password_reset(settings: &SiteSettings, customer: &Customer, token: &str) -> Email {
+    let link = format!("{}/account/reset/{}", site_url(), token);
+
+    Email {
+        to: customer.email.clone(),
+        subject: "Reset your password".to_string(),
+        text: format!(
+            "Hi {},\n\nUse this link to choose a new password. It is valid for one hour.\n\n{}\n\nIf you didn't ask for a new password, you can ignore this email.\n",
+            customer.name, link
+        ),
+        html: html_layout(
+            settings,
+            "Reset your password",
+            &format!(
+                r#"<p>Hi {},</p>
+    <p>Use the link below to choose a new password. It is valid for one hour.</p>
+    <p><a href="{}">Reset your password</a></p>
+    <p>If you didn't ask for a new password, you can ignore this email.</p>"#,
+                escape_html(&customer.name), link
+            ),
+        ),
+    }
+}
+
+/// Link confirming the email of a new customer account
+pub fn email_verification(settings: &SiteSettings, customer: &Customer, token: &str) -> Email {
+    let link = format!("{}/account/verify/{}", site_url(), token);
+
+    Email {
+        to: customer.email.clone(),
+        subject: "Confirm your email".to_string(),
+        text: format!(
+            "Hi {},\n\nPlease confirm your email with this link. It is valid for one day.\n\n{}\n\nOrders you placed as a guest with this email are added to your account once it is confirmed.\n",
+            customer.name, link
+        ),
+        html: html_layout(
+            settings,
+            "Confirm your email",
+            &format!(
+                r#"<p>Hi {},</p>
+    <p>Please confirm your email with the link below. It is valid for one day.</p>
+    <p><a href="{}">Confirm your email</a></p>
+    <p>Orders you placed as a guest with this email are added to your account once it is confirmed.</p>"#,
+                escape_html(&customer.name), link
+            ),
+        ),
+    }
+}
+
+/// Notification sent to a commenter when a reply to their comment is approved
+pub fn comment_reply(settings: &SiteSettings, parent: &Comment, reply: &Comment, post: &Post) -> Email {
+    let link = format!("{}/blog/{}#comment-{}", site_url(), post.id, reply.id);
+
+    Email {
+        to: parent.author_email.clone(),
+        subject: format!("New reply to your comment on \"{}\"", post.title),
+        text: format!(
+            "Hi {},\n\n{} replied to your comment on \"{}\":\n\n{}\n\nRead the conversation: {}\n",
+            parent.author_name, reply.author_name, post.title, reply.body, link
+        ),
+        html: html_layout(
+            settings,
+            "New reply to your comment",
+            &format!(
+                r#"<p>Hi {},</p>
+    <p>{} replied to your comment on &ldquo;{}&rdquo;:</p>
+    <blockquote style="border-left: 3px solid #667eea; margin: 0; padding-left: 12px;">{}</blockquote>
+    <p><a href="{}">Read the conversation</a></p>"#,
+                escape_html(&parent.author_name), escape_html(&reply.author_name), escape_html(&post.title),
+                escape_html(&reply.body).replace('\n', "<br>"), link
+            ),
+        ),
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+use lettre::message::{header::ContentType, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::Message;
+use super::Email;
+
+/// Delivers emails
+/// Sending is blocking, the queue runs it off the async runtime
+pub trait Transport: Send + Sync {
+    fn send(&self, email: &Email) -> Result<(), String>;
+}
+
+/// Builds a multipart plain text and HTML message
+fn build_message(email: &Email, from: &Mailbox) -> Result<Message, String> {
+    let to: Mailbox = email.to.parse().map_err(|e| format!("Invalid recipient {}: {}", email.to, e))?;
+    Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(email.subject.clone())
+        .multipart(
+            MultiPart::alternative()
+                .singlepart(SinglePart::builder().header(ContentType::TEXT_PLAIN).body(email.text.clone()))
+                .singlepart(SinglePart::builder().header(ContentType::TEXT_HTML).body(email.html.clone())),
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Sends emails through an SMTP server using STARTTLS
+pub struct SmtpTransport {
+    mailer: lettre::SmtpTransport,
+    from: Mailbox,
+}
+
+impl SmtpTransport {
+    /// Creates a transport for the server, with optional (username, password) credentials
+    pub fn new(host: &str, port: u16, credentials: Option<(String, String)>, from: &str) -> Result<Self, String> {
+        let mut builder = lettre::SmtpTransport::starttls_relay(host)
+            .map_err(|e| e.to_string())?
+            .port(port);
+        if let Some((username, password)) = credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+        Ok(Self {
+            mailer: builder.build(),
+            from: from.parse().map_err(|e| format!("Invalid sender {}: {}", from, e))?,
+        })
+    }
+}
+
+impl Transport for SmtpTransport {
+    fn send(&self, email: &Email) -> Result<(), String> {
+        let message = build_message(email, &self.from)?;
+        lettre::Transport::send(&self.mailer, &message)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Writes emails as `.eml` files into a local directory
+/// Meant for development, the files can be opened with any mail client
+pub struct FileTransport {
+    dir: PathBuf,
+    from: Mailbox,
+}
+
+impl FileTransport {
+    /// Creates a transport writing into `dir`, created on first send
+    pub fn new(dir: PathBuf, from: &str) -> Self {
+        Self {
+            dir,
+            from: from.parse().unwrap_or_else(|_| "shop@localhost".parse().expect("valid fallback address")),
+        }
+    }
+}
+
+impl Transport for FileTransport {
+    fn send(&self, email: &Email) -> Result<(), String> {
+        let message = build_message(email, &self.from)?;
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let name = format!(
+            "{}-{}.eml",
+            chrono::Utc::now().format("%Y%m%d%H%M%S"),
+            uuid::Uuid::new_v4().simple()
+        );
+        std::fs::write(self.dir.join(name), message.formatted()).map_err(|e| e.to_string())
+    }
+}
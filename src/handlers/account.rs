@@ -0,0 +1,460 @@
+//! Customer account pages
+//! Sign up, login, password reset, order history and the address book
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    response::{Html, IntoResponse, Redirect, Response},
+    Form,
+};
+use serde::Deserialize;
+use crate::store::Store;
+use crate::models::{Address, Customer};
+use super::layout::{escape_html, public_page};
+use super::error::{AppError, AppResult};
+use super::public::{cookie_value, nav_tools, order_details_html, selected_currency};
+
+/// Name of the cookie holding the customer session token
+const SESSION_COOKIE: &str = "session";
+
+/// Returns the logged in customer
+pub(crate) fn current_customer(store: &Store, headers: &HeaderMap) -> Option<Customer> {
+    cookie_value(headers, SESSION_COOKIE).and_then(|token| store.session_customer(&token))
+}
+
+/// Starts a session and redirects with the session cookie set
+fn log_in(store: &Store, customer: &Customer, to: &str) -> Response {
+    let token = store.create_session(&customer.id);
+    let cookie = format!("{}={}; Path=/; Max-Age=2592000; HttpOnly; SameSite=Lax", SESSION_COOKIE, token);
+    ([(header::SET_COOKIE, cookie)], Redirect::to(to)).into_response()
+}
+
+/// Wraps account page content with the public layout
+fn account_page(store: &Store, headers: &HeaderMap, title: &str, path: &str, content: &str) -> String {
+    let currency = selected_currency(store, headers);
+    public_page(
+        store,
+        &format!("{} - {}", title, store.site_name()),
+        "/account",
+        &nav_tools(store, headers, &currency, path),
+        &format!(
+            r#"<section class="page-header">
+            <h1>{}</h1>
+        </section>
+        <section class="account">
+            {}
+        </section>"#,
+            title, content
+        ),
+    )
+}
+
+/// Renders an error message paragraph
+fn error_html(error: Option<&str>) -> String {
+    error.map(|e| format!(r#"<p class="warning">{}</p>"#, e)).unwrap_or_default()
+}
+
+#[derive(Deserialize, Default)]
+pub struct SignupForm {
+    name: String,
+    email: String,
+    password: String,
+}
+
+/// Renders the sign up page
+fn signup_page(store: &Store, headers: &HeaderMap, form: &SignupForm, error: Option<&str>) -> String {
+    let content = format!(
+        r#"{}
+            <form method="post" action="/account/signup" class="account-form">
+                <label>Name
+                    <input type="text" name="name" value="{}" required>
+                </label>
+                <label>Email
+                    <input type="email" name="email" value="{}" required>
+                </label>
+                <label>Password
+                    <input type="password" name="password" minlength="8" required>
+                </label>
+                <button type="submit" class="btn btn-primary">Create Account</button>
+            </form>
+            <p>Already have an account? <a href="/account/login">Log in</a></p>
+            <p>Orders you placed as a guest with this email will show up in your account once you confirm the email.</p>"#,
+        error_html(error), escape_html(&form.name), escape_html(&form.email)
+    );
+    account_page(store, headers, "Create Account", "/account/signup", &content)
+}
+
+/// Sign up page handler
+pub async fn account_signup(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    Html(signup_page(&store, &headers, &SignupForm::default(), None))
+}
+
+/// Creates a customer account and logs the customer in
+pub async fn account_create(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<SignupForm>,
+) -> impl IntoResponse {
+    match store.register_customer(form.name.clone(), form.email.clone(), &form.password) {
+        Ok(customer) => log_in(&store, &customer, "/account"),
+        Err(error) => Html(signup_page(&store, &headers, &form, Some(&error))).into_response(),
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub struct LoginForm {
+    email: String,
+    password: String,
+}
+
+/// Renders the login page
+fn login_page(store: &Store, headers: &HeaderMap, email: &str, error: Option<&str>) -> String {
+    let content = format!(
+        r#"{}
+            <form method="post" action="/account/login" class="account-form">
+                <label>Email
+                    <input type="email" name="email" value="{}" required>
+                </label>
+                <label>Password
+                    <input type="password" name="password" required>
+                </label>
+                <button type="submit" class="btn btn-primary">Log In</button>
+            </form>
+            <p><a href="/account/forgot">Forgot your password?</a></p>
+            <p>New here? <a href="/account/signup">Create an account</a></p>"#,
+        error_html(error), escape_html(email)
+    );
+    account_page(store, headers, "Log In", "/account/login", &content)
+}
+
+/// Login page handler
+pub async fn account_login(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    Html(login_page(&store, &headers, "", None))
+}
+
+/// Checks the login details and starts a session
+pub async fn account_authenticate(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<LoginForm>,
+) -> impl IntoResponse {
+    match store.authenticate_customer(&form.email, &form.password) {
+        Some(customer) => log_in(&store, &customer, "/account"),
+        None => Html(login_page(&store, &headers, &form.email, Some("Wrong email or password"))).into_response(),
+    }
+}
+
+/// Ends the session and clears the cookie
+pub async fn account_logout(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    if let Some(token) = cookie_value(&headers, SESSION_COOKIE) {
+        store.end_session(&token);
+    }
+    let cookie = format!("{}=; Path=/; Max-Age=0; HttpOnly; SameSite=Lax", SESSION_COOKIE);
+    ([(header::SET_COOKIE, cookie)], Redirect::to("/"))
+}
+
+#[derive(Deserialize)]
+pub struct ForgotPasswordForm {
+    email: String,
+}
+
+/// Password reset request page handler
+pub async fn account_forgot_password(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    let content = r#"<form method="post" action="/account/forgot" class="account-form">
+                <label>Email
+                    <input type="email" name="email" required>
+                </label>
+                <button type="submit" class="btn btn-primary">Send Reset Link</button>
+            </form>"#;
+    Html(account_page(&store, &headers, "Reset Password", "/account/forgot", content))
+}
+
+/// Emails a password reset link for the account
+/// The response is the same whether or not the account exists
+pub async fn account_request_reset(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<ForgotPasswordForm>,
+) -> impl IntoResponse {
+    store.send_password_reset(&form.email);
+    let content = r#"<p>If an account exists for that email, we've sent a link to reset your password.
+            The link is valid for one hour.</p>"#;
+    Html(account_page(&store, &headers, "Reset Password", "/account/forgot", content))
+}
+
+/// Renders the new password form of a reset link
+fn reset_page(store: &Store, headers: &HeaderMap, token: &str, error: Option<&str>) -> String {
+    let content = format!(
+        r#"{}
+            <form method="post" action="/account/reset/{}" class="account-form">
+                <label>New Password
+                    <input type="password" name="password" minlength="8" required>
+                </label>
+                <button type="submit" class="btn btn-primary">Set Password</button>
+            </form>"#,
+        error_html(error), token
+    );
+    account_page(store, headers, "Choose a New Password", "/account/login", &content)
+}
+
+/// Password reset link handler
+pub async fn account_reset_form(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    if !store.password_reset_valid(&token) {
+        let content = r#"<p class="warning">This reset link is invalid or has expired.</p>
+            <p><a href="/account/forgot">Request a new link</a></p>"#;
+        return Html(account_page(&store, &headers, "Reset Password", "/account/forgot", content));
+    }
+    Html(reset_page(&store, &headers, &token, None))
+}
+
+#[derive(Deserialize)]
+pub struct ResetPasswordForm {
+    password: String,
+}
+
+/// Sets the new password of a reset link
+pub async fn account_reset_password(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(token): Path<String>,
+    Form(form): Form<ResetPasswordForm>,
+) -> impl IntoResponse {
+    match store.reset_password(&token, &form.password) {
+        Ok(()) => Redirect::to("/account/login").into_response(),
+        Err(error) => Html(reset_page(&store, &headers, &token, Some(&error))).into_response(),
+    }
+}
+
+/// Account overview with the order history
+pub async fn account_overview(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    let customer = match current_customer(&store, &headers) {
+        Some(customer) => customer,
+        None => return Redirect::to("/account/login").into_response(),
+    };
+
+    let mut rows = String::new();
+    for order in store.customer_orders(&customer.id) {
+        let currency = store.currency_or_base(Some(&order.currency_code));
+        rows.push_str(&format!(
+            r#"<tr>
+                    <td><a href="/account/orders/{}">#{}</a></td>
+                    <td>{}</td>
+                    <td>{}</td>
+                    <td>{}</td>
+                </tr>"#,
+            order.id, order.number, store.settings().local_time(order.created_at).format("%B %d, %Y"),
+            order.status.label(), currency.format(order.total)
+        ));
+    }
+    let orders_html = if rows.is_empty() {
+        r#"<p class="empty">You haven't placed any orders yet. <a href="/shop">Start shopping</a></p>"#.to_string()
+    } else {
+        format!(
+            r#"<table class="cart-table">
+                <thead>
+                    <tr>
+                        <th>Order</th>
+                        <th>Date</th>
+                        <th>Status</th>
+                        <th>Total</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {}
+                </tbody>
+            </table>"#,
+            rows
+        )
+    };
+
+    let verify_notice = if customer.email_verified {
+        ""
+    } else {
+        r#"<div class="account-notice">
+                <p>Please confirm your email with the link we sent you. Orders you placed as a guest show up here after that.</p>
+                <form method="post" action="/account/verify">
+                    <button type="submit" class="btn btn-small">Send the Link Again</button>
+                </form>
+            </div>"#
+    };
+
+    let content = format!(
+        r#"<p>Logged in as <strong>{}</strong> ({})</p>
+            <div class="account-links">
+                <a href="/account/addresses" class="btn btn-small">Address Book</a>
+                <form method="post" action="/account/logout">
+                    <button type="submit" class="btn btn-small">Log Out</button>
+                </form>
+            </div>
+            {}
+            <h2>Order History</h2>
+            {}"#,
+        escape_html(&customer.name), escape_html(&customer.email), verify_notice, orders_html
+    );
+    Html(account_page(&store, &headers, "My Account", "/account", &content)).into_response()
+}
+
+/// Email verification link handler
+/// Verifies the email and shows the account, where guest orders now appear
+pub async fn account_verify_email(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(token): Path<String>,
+) -> impl IntoResponse {
+    match store.verify_email(&token) {
+        Ok(_) => Redirect::to("/account").into_response(),
+        Err(error) => {
+            let content = format!(
+                r#"<p class="warning">{}</p>
+            <p><a href="/account">Go to your account</a> to request a new link.</p>"#,
+                error
+            );
+            Html(account_page(&store, &headers, "Confirm Email", "/account", &content)).into_response()
+        }
+    }
+}
+
+/// Sends a new verification link to the logged in customer
+pub async fn account_resend_verification(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    let customer = match current_customer(&store, &headers) {
+        Some(customer) => customer,
+        None => return Redirect::to("/account/login").into_response(),
+    };
+    if !customer.email_verified {
+        store.send_email_verification(&customer);
+    }
+    let content = format!(
+        r#"<p>We've sent a new confirmation link to {}. It is valid for one day.</p>
+            <p><a href="/account">Back to your account</a></p>"#,
+        escape_html(&customer.email)
+    );
+    Html(account_page(&store, &headers, "Confirm Email", "/account", &content)).into_response()
+}
+
+/// Order detail page of the account area
+/// Only shows orders belonging to the logged in customer, others are forbidden
+pub async fn account_order(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> AppResult<Response> {
+    let customer = match current_customer(&store, &headers) {
+        Some(customer) => customer,
+        None => return Ok(Redirect::to("/account/login").into_response()),
+    };
+    let order = store.orders.read()?.get(&id).cloned().ok_or(AppError::NotFound)?;
+    if order.customer_id.as_deref() != Some(customer.id.as_str()) {
+        return Err(AppError::Forbidden);
+    }
+
+    let content = format!(
+        r#"<p>Placed {} &middot; {}</p>
+            {}
+            <a href="/account" class="btn">&larr; Back to Account</a>"#,
+        store.settings().local_time(order.created_at).format("%B %d, %Y"), order.status.label(), order_details_html(&store, &order)
+    );
+    Ok(Html(account_page(
+        &store,
+        &headers,
+        &format!("Order #{}", order.number),
+        &format!("/account/orders/{}", order.id),
+        &content,
+    ))
+    .into_response())
+}
+
+/// Address book page
+pub async fn account_addresses(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    let customer = match current_customer(&store, &headers) {
+        Some(customer) => customer,
+        None => return Redirect::to("/account/login").into_response(),
+    };
+
+    let mut addresses_html = String::new();
+    for address in &customer.addresses {
+        addresses_html.push_str(&format!(
+            r#"<div class="address-card">
+                <h3>{}</h3>
+                <p class="address">{}<br>{}<br>{}</p>
+                <form method="post" action="/account/addresses/delete/{}">
+                    <button type="submit" class="btn btn-small">Delete</button>
+                </form>
+            </div>"#,
+            escape_html(&address.label), escape_html(&address.recipient), escape_html(&address.address).replace('\n', "<br>"),
+            escape_html(&address.region), address.id
+        ));
+    }
+
+    let mut region_options = String::new();
+    for (code, name) in store.regions() {
+        region_options.push_str(&format!(r#"<option value="{}">{}</option>"#, code, name));
+    }
+
+    let content = format!(
+        r#"<div class="address-book">{}</div>
+            <h2>Add an Address</h2>
+            <form method="post" action="/account/addresses/create" class="account-form">
+                <label>Label
+                    <input type="text" name="label" placeholder="Home" required>
+                </label>
+                <label>Recipient
+                    <input type="text" name="recipient" value="{}" required>
+                </label>
+                <label>Address
+                    <textarea name="address" rows="4" required></textarea>
+                </label>
+                <label>Region
+                    <select name="region">{}</select>
+                </label>
+                <button type="submit" class="btn btn-primary">Save Address</button>
+            </form>
+            <a href="/account">&larr; Back to Account</a>"#,
+        addresses_html, escape_html(&customer.name), region_options
+    );
+    Html(account_page(&store, &headers, "Address Book", "/account/addresses", &content)).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct AddressForm {
+    label: String,
+    recipient: String,
+    address: String,
+    region: String,
+}
+
+/// Adds an address to the address book
+pub async fn account_create_address(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<AddressForm>,
+) -> impl IntoResponse {
+    if let Some(customer) = current_customer(&store, &headers) {
+        if let Some(customer) = store.customers.write().unwrap().get_mut(&customer.id) {
+            customer.addresses.push(Address::new(
+                form.label.trim().to_string(),
+                form.recipient.trim().to_string(),
+                form.address.trim().to_string(),
+                form.region,
+            ));
+        }
+    }
+    Redirect::to("/account/addresses")
+}
+
+/// Removes an address from the address book
+pub async fn account_delete_address(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Some(customer) = current_customer(&store, &headers) {
+        if let Some(customer) = store.customers.write().unwrap().get_mut(&customer.id) {
+            customer.addresses.retain(|a| a.id != id);
+        }
+    }
+    Redirect::to("/account/addresses")
+}
@@ -1,79 +1,139 @@
 use axum::{
-    extract::{Path, State},
-    response::{Html, IntoResponse, Redirect},
+    extract::{Multipart, Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
     Form,
 };
+use std::collections::HashMap;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::Deserialize;
+use similar::{ChangeTag, TextDiff};
 use crate::store::Store;
-use crate::models::{Product, Category, Post, BlogCategory};
+use crate::email::EmailStatus;
+use crate::media::MAX_UPLOAD_SIZE;
+use super::layout::{admin_page, escape_html};
+use super::error::{AppError, AppResult};
+use super::seo::summary;
+use crate::models::{
+    Product, Category, Post, PostStatus, ReviewComment, Comment, CommentStatus, COMMENT_RATE_LIMIT, COMMENT_RATE_WINDOW_MINUTES, Author, BlogCategory, Currency, ProductOption,
+    AttributeDefinition, AttributeType, StockMovementKind, Coupon, DiscountType, OrderStatus,
+    TaxClass, TaxRate, ImageSize, MediaAsset, ProductImage, Dimensions, SeoMeta, PageStatus, MenuItem, MenuLink, MenuLocation, ShippingMethod, ShippingRateType, ShippingZone,
+    SiteSettings, timezone_choices, MAX_POSTS_PER_PAGE,
+};
+
+/// Parses the value of a `datetime-local` input, entered in the site timezone
+fn parse_datetime_local(value: &str, settings: &SiteSettings) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%dT%H:%M")
+        .ok()
+        .and_then(|naive| naive.and_local_timezone(settings.utc_offset()).single())
+        .map(|local| local.with_timezone(&Utc))
+}
+
+/// Formats a timestamp for a `datetime-local` input in the site timezone
+fn format_datetime_local(value: Option<DateTime<Utc>>, settings: &SiteSettings) -> String {
+    value.map(|v| settings.local_time(v).format("%Y-%m-%dT%H:%M").to_string()).unwrap_or_default()
+}
+
+/// Builds the options of a tax class select
+fn tax_class_options(selected: TaxClass) -> String {
+    let mut options = String::new();
+    for class in TaxClass::ALL {
+        let attr = if class == selected { " selected" } else { "" };
+        options.push_str(&format!(r#"<option value="{}"{}>{}</option>"#, class.label(), attr, class.label()));
+    }
+    options
+}
 
 /// Admin dashboard handler
 /// Shows overview of the system with statistics
-pub async fn admin_dashboard(State(store): State<Store>) -> impl IntoResponse {
-    let products_count = store.products.read().unwrap().len();
-    let categories_count = store.categories.read().unwrap().len();
-    let posts_count = store.posts.read().unwrap().len();
-    let blog_categories_count = store.blog_categories.read().unwrap().len();
-
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Admin Dashboard</title>
-    <link rel="stylesheet" href="/static/css/admin.css">
-</head>
-<body>
-    <nav class="admin-nav">
-        <h1>CMS Admin Panel</h1>
-        <ul>
-            <li><a href="/admin">Dashboard</a></li>
-            <li><a href="/admin/categories">Product Categories</a></li>
-            <li><a href="/admin/products">Products</a></li>
-            <li><a href="/admin/blog-categories">Blog Categories</a></li>
-            <li><a href="/admin/posts">Blog Posts</a></li>
-            <li><a href="/">View Site</a></li>
-        </ul>
-    </nav>
-    <div class="container">
-        <h2>Dashboard Overview</h2>
-        <div class="stats-grid">
-            <div class="stat-card">
-                <h3>Product Categories</h3>
-                <p class="stat-number">{}</p>
-                <a href="/admin/categories" class="btn">Manage</a>
-            </div>
-            <div class="stat-card">
-                <h3>Products</h3>
-                <p class="stat-number">{}</p>
-                <a href="/admin/products" class="btn">Manage</a>
-            </div>
-            <div class="stat-card">
-                <h3>Blog Categories</h3>
-                <p class="stat-number">{}</p>
-                <a href="/admin/blog-categories" class="btn">Manage</a>
-            </div>
-            <div class="stat-card">
-                <h3>Blog Posts</h3>
-                <p class="stat-number">{}</p>
-                <a href="/admin/posts" class="btn">Manage</a>
-            </div>
+pub async fn admin_dashboard(State(store): State<Store>) -> AppResult<Html<String>> {
+    let products_count = store.products.read()?.len();
+    let categories_count = store.categories.read()?.len();
+    let posts_count = store.posts.read()?.len();
+    let blog_categories_count = store.blog_categories.read()?.len();
+    let pending_comments_count = store
+        .comments
+        .read()?
+        .values()
+        .filter(|c| c.status == CommentStatus::Pending)
+        .count();
+
+    let low_stock = store.low_stock_items();
+    let mut low_stock_rows = String::new();
+    for item in &low_stock {
+        low_stock_rows.push_str(&format!(
+            r#"<tr>
+                <td><a href="/admin/inventory/{}">{}</a></td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+            </tr>"#,
+            item.product_id, item.name, item.sku.as_deref().unwrap_or("-"), item.stock, item.threshold
+        ));
+    }
+    let low_stock_html = if low_stock.is_empty() {
+        "<p>All products are above their low stock threshold.</p>".to_string()
+    } else {
+        format!(
+            r#"<table>
+        <thead>
+            <tr>
+                <th>Product</th>
+                <th>SKU</th>
+                <th>Stock</th>
+                <th>Threshold</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+            low_stock_rows
+        )
+    };
+
+    let content = format!(
+        r#"<h2>Dashboard Overview</h2>
+    <div class="stats-grid">
+        <div class="stat-card">
+            <h3>Product Categories</h3>
+            <p class="stat-number">{}</p>
+            <a href="/admin/categories" class="btn">Manage</a>
+        </div>
+        <div class="stat-card">
+            <h3>Products</h3>
+            <p class="stat-number">{}</p>
+            <a href="/admin/products" class="btn">Manage</a>
+        </div>
+        <div class="stat-card">
+            <h3>Blog Categories</h3>
+            <p class="stat-number">{}</p>
+            <a href="/admin/blog-categories" class="btn">Manage</a>
+        </div>
+        <div class="stat-card">
+            <h3>Blog Posts</h3>
+            <p class="stat-number">{}</p>
+            <a href="/admin/posts" class="btn">Manage</a>
+        </div>
+        <div class="stat-card">
+            <h3>Pending Comments</h3>
+            <p class="stat-number">{}</p>
+            <a href="/admin/comments" class="btn">Moderate</a>
         </div>
     </div>
-</body>
-</html>"#,
-        categories_count, products_count, blog_categories_count, posts_count
+    <h2>Low Stock ({})</h2>
+    {}"#,
+        categories_count, products_count, blog_categories_count, posts_count, pending_comments_count,
+        low_stock.len(), low_stock_html
     );
 
-    Html(html)
+    Ok(Html(admin_page("Admin Dashboard", "/admin", &content)))
 }
 
 /// Lists all product categories in admin panel
-pub async fn admin_list_categories(State(store): State<Store>) -> impl IntoResponse {
-    let categories = store.categories.read().unwrap();
+pub async fn admin_list_categories(State(store): State<Store>) -> AppResult<Html<String>> {
+    let categories = store.categories.read()?;
     let mut categories_vec: Vec<_> = categories.values().collect();
-    categories_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    categories_vec.sort_by_key(|c| std::cmp::Reverse(c.created_at));
 
     let mut rows = String::new();
     for cat in categories_vec {
@@ -82,64 +142,43 @@ pub async fn admin_list_categories(State(store): State<Store>) -> impl IntoRespo
                 <td>{}</td>
                 <td>{}</td>
                 <td>{}</td>
+                <td>{}</td>
                 <td>
+                    <a href="/admin/categories/edit/{}" class="btn btn-sm">Attributes</a>
                     <form method="post" action="/admin/categories/delete/{}" style="display:inline;">
                         <button type="submit" class="btn btn-danger">Delete</button>
                     </form>
                 </td>
             </tr>"#,
-            cat.name, cat.description, cat.created_at.format("%Y-%m-%d"), cat.id
+            cat.name, cat.description, cat.created_at.format("%Y-%m-%d"), cat.attributes.len(), cat.id, cat.id
         ));
     }
 
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Product Categories - Admin</title>
-    <link rel="stylesheet" href="/static/css/admin.css">
-</head>
-<body>
-    <nav class="admin-nav">
-        <h1>CMS Admin Panel</h1>
-        <ul>
-            <li><a href="/admin">Dashboard</a></li>
-            <li><a href="/admin/categories" class="active">Product Categories</a></li>
-            <li><a href="/admin/products">Products</a></li>
-            <li><a href="/admin/blog-categories">Blog Categories</a></li>
-            <li><a href="/admin/posts">Blog Posts</a></li>
-            <li><a href="/">View Site</a></li>
-        </ul>
-    </nav>
-    <div class="container">
-        <h2>Product Categories</h2>
-        <form method="post" action="/admin/categories/create" class="create-form">
-            <input type="text" name="name" placeholder="Category Name" required>
-            <input type="text" name="description" placeholder="Description" required>
-            <button type="submit" class="btn">Create Category</button>
-        </form>
-        <table>
-            <thead>
-                <tr>
-                    <th>Name</th>
-                    <th>Description</th>
-                    <th>Created</th>
-                    <th>Actions</th>
-                </tr>
-            </thead>
-            <tbody>
-                {}
-            </tbody>
-        </table>
-    </div>
-</body>
-</html>"#,
+    let content = format!(
+        r#"<h2>Product Categories</h2>
+    <form method="post" action="/admin/categories/create" class="create-form">
+        <input type="text" name="name" placeholder="Category Name" required>
+        <input type="text" name="description" placeholder="Description" required>
+        <button type="submit" class="btn">Create Category</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Name</th>
+                <th>Description</th>
+                <th>Created</th>
+                <th>Attributes</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
         rows
     );
 
-    Html(html)
+    Ok(Html(admin_page("Product Categories - Admin", "/admin/categories", &content)))
 }
 
 #[derive(Deserialize)]
@@ -152,27 +191,189 @@ pub struct CreateCategoryForm {
 pub async fn admin_create_category(
     State(store): State<Store>,
     Form(form): Form<CreateCategoryForm>,
-) -> impl IntoResponse {
+) -> AppResult<Redirect> {
     let category = Category::new(form.name, form.description);
-    store.categories.write().unwrap().insert(category.id.clone(), category);
-    Redirect::to("/admin/categories")
+    store.categories.write()?.insert(category.id.clone(), category);
+    Ok(Redirect::to("/admin/categories"))
 }
 
 /// Deletes a product category
 pub async fn admin_delete_category(
     State(store): State<Store>,
     Path(id): Path<String>,
-) -> impl IntoResponse {
-    store.categories.write().unwrap().remove(&id);
-    Redirect::to("/admin/categories")
+) -> AppResult<Redirect> {
+    store.categories.write()?.remove(&id);
+    store.remove_menu_links(&MenuLink::Category(id));
+    Ok(Redirect::to("/admin/categories"))
+}
+
+/// Meta title and description inputs, showing the values used without an override as placeholders
+fn seo_fields(seo: &SeoMeta, default_title: &str, default_description: &str) -> String {
+    format!(
+        r#"<label>Meta Title
+            <input type="text" name="meta_title" value="{}" placeholder="{}" maxlength="70">
+        </label>
+        <label>Meta Description
+            <textarea name="meta_description" rows="2" placeholder="{}" maxlength="160">{}</textarea>
+        </label>"#,
+        escape_html(&seo.title), escape_html(default_title),
+        escape_html(default_description), escape_html(&seo.description)
+    )
+}
+
+/// Shows the attribute schema of a product category
+pub async fn admin_edit_category(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let categories = store.categories.read()?;
+    let category = match categories.get(&id) {
+        Some(category) => category,
+        None => return Err(AppError::NotFound),
+    };
+
+    let mut rows = String::new();
+    for attribute in &category.attributes {
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>
+                    <form method="post" action="/admin/categories/attributes/delete/{}/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            attribute.name, attribute.attribute_type.label(), attribute.choices.join(", "),
+            attribute.unit.as_deref().unwrap_or(""), if attribute.filterable { "Yes" } else { "No" },
+            category.id, attribute.id
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>{} Attributes</h2>
+    <p>Attributes defined here can be filled in on every product of this category
+    and used as filters on the category shop page.</p>
+    <form method="post" action="/admin/categories/attributes/create/{}" class="create-form">
+        <input type="text" name="name" placeholder="Attribute Name (e.g. Screen Size)" required>
+        <select name="attribute_type" required>
+            <option value="text">Text</option>
+            <option value="number">Number</option>
+            <option value="boolean">Yes/No</option>
+            <option value="select">Select</option>
+        </select>
+        <input type="text" name="choices" placeholder="Choices for select, comma separated">
+        <input type="text" name="unit" placeholder="Unit (e.g. in, GB)">
+        <label><input type="checkbox" name="filterable" value="true" checked> Filterable</label>
+        <button type="submit" class="btn">Add Attribute</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Name</th>
+                <th>Type</th>
+                <th>Choices</th>
+                <th>Unit</th>
+                <th>Filterable</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+
+    <h2>Search Engines</h2>
+    <p>Leave a field empty to use the category name and description.</p>
+    <form method="post" action="/admin/categories/seo/{}" class="create-form">
+        {}
+        <button type="submit" class="btn">Save</button>
+    </form>"#,
+        category.name, category.id, rows, category.id,
+        seo_fields(&category.seo, &format!("{} - {}", category.name, store.site_name()), &category.description)
+    );
+
+    Ok(Html(admin_page("Category Attributes - Admin", "/admin/categories", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct SeoForm {
+    #[serde(default)]
+    meta_title: String,
+    #[serde(default)]
+    meta_description: String,
+}
+
+/// Saves the meta title and description overrides of a product category
+pub async fn admin_update_category_seo(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<SeoForm>,
+) -> AppResult<Redirect> {
+    if let Some(category) = store.categories.write()?.get_mut(&id) {
+        category.seo = SeoMeta::new(&form.meta_title, &form.meta_description);
+    }
+    Ok(Redirect::to(&format!("/admin/categories/edit/{}", id)))
+}
+
+#[derive(Deserialize)]
+pub struct CreateAttributeForm {
+    name: String,
+    attribute_type: String,
+    choices: String,
+    unit: String,
+    filterable: Option<String>,
+}
+
+/// Adds an attribute definition to a product category
+pub async fn admin_create_category_attribute(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<CreateAttributeForm>,
+) -> AppResult<Redirect> {
+    if let Some(attribute_type) = AttributeType::from_form(&form.attribute_type) {
+        let attribute = AttributeDefinition::new(
+            form.name,
+            attribute_type,
+            &form.choices,
+            Some(form.unit),
+            form.filterable.is_some(),
+        );
+        let valid = !attribute.name.is_empty()
+            && (attribute_type != AttributeType::Select || !attribute.choices.is_empty());
+        if valid {
+            if let Some(category) = store.categories.write()?.get_mut(&id) {
+                category.attributes.push(attribute);
+            }
+        }
+    }
+    Ok(Redirect::to(&format!("/admin/categories/edit/{}", id)))
+}
+
+/// Removes an attribute definition and its values from all products
+pub async fn admin_delete_category_attribute(
+    State(store): State<Store>,
+    Path((id, attribute_id)): Path<(String, String)>,
+) -> AppResult<Redirect> {
+    if let Some(category) = store.categories.write()?.get_mut(&id) {
+        category.attributes.retain(|a| a.id != attribute_id);
+    }
+    for product in store.products.write()?.values_mut() {
+        product.attributes.remove(&attribute_id);
+    }
+    Ok(Redirect::to(&format!("/admin/categories/edit/{}", id)))
 }
 
 /// Lists all products in admin panel
-pub async fn admin_list_products(State(store): State<Store>) -> impl IntoResponse {
-    let products = store.products.read().unwrap();
-    let categories = store.categories.read().unwrap();
+pub async fn admin_list_products(State(store): State<Store>) -> AppResult<Html<String>> {
+    let base = store.base_currency();
+    let products = store.products.read()?;
+    let categories = store.categories.read()?;
     let mut products_vec: Vec<_> = products.values().collect();
-    products_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    products_vec.sort_by_key(|p| std::cmp::Reverse(p.created_at));
 
     let mut rows = String::new();
     for product in products_vec {
@@ -185,16 +386,18 @@ pub async fn admin_list_products(State(store): State<Store>) -> impl IntoRespons
             r#"<tr>
                 <td>{}</td>
                 <td>{}</td>
-                <td>${:.2}</td>
+                <td>{}</td>
                 <td>{}</td>
                 <td>{}</td>
                 <td>
+                    <a href="/admin/products/edit/{}" class="btn btn-sm">Edit</a>
                     <form method="post" action="/admin/products/delete/{}" style="display:inline;">
                         <button type="submit" class="btn btn-danger">Delete</button>
                     </form>
                 </td>
             </tr>"#,
-            product.name, category_name, product.price, product.stock, product.created_at.format("%Y-%m-%d"), product.id
+            product.name, category_name, base.format(product.price), product.total_stock(), product.created_at.format("%Y-%m-%d"),
+            product.id, product.id
         ));
     }
 
@@ -203,62 +406,40 @@ pub async fn admin_list_products(State(store): State<Store>) -> impl IntoRespons
         category_options.push_str(&format!(r#"<option value="{}">{}</option>"#, cat.id, cat.name));
     }
 
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Products - Admin</title>
-    <link rel="stylesheet" href="/static/css/admin.css">
-</head>
-<body>
-    <nav class="admin-nav">
-        <h1>CMS Admin Panel</h1>
-        <ul>
-            <li><a href="/admin">Dashboard</a></li>
-            <li><a href="/admin/categories">Product Categories</a></li>
-            <li><a href="/admin/products" class="active">Products</a></li>
-            <li><a href="/admin/blog-categories">Blog Categories</a></li>
-            <li><a href="/admin/posts">Blog Posts</a></li>
-            <li><a href="/">View Site</a></li>
-        </ul>
-    </nav>
-    <div class="container">
-        <h2>Products</h2>
-        <form method="post" action="/admin/products/create" class="create-form">
-            <input type="text" name="name" placeholder="Product Name" required>
-            <input type="text" name="description" placeholder="Description" required>
-            <input type="number" step="0.01" name="price" placeholder="Price" required>
-            <input type="number" name="stock" placeholder="Stock" required>
-            <select name="category_id" required>
-                <option value="">Select Category</option>
-                {}
-            </select>
-            <button type="submit" class="btn">Create Product</button>
-        </form>
-        <table>
-            <thead>
-                <tr>
-                    <th>Name</th>
-                    <th>Category</th>
-                    <th>Price</th>
-                    <th>Stock</th>
-                    <th>Created</th>
-                    <th>Actions</th>
-                </tr>
-            </thead>
-            <tbody>
-                {}
-            </tbody>
-        </table>
-    </div>
-</body>
-</html>"#,
-        category_options, rows
+    let content = format!(
+        r#"<h2>Products</h2>
+    <form method="post" action="/admin/products/create" class="create-form">
+        <input type="text" name="name" placeholder="Product Name" required>
+        <input type="text" name="description" placeholder="Description" required>
+        <input type="number" step="0.01" name="price" placeholder="Price ({})" required>
+        <input type="number" name="stock" placeholder="Stock" required>
+        <select name="category_id" required>
+            <option value="">Select Category</option>
+            {}
+        </select>
+        <select name="tax_class" required>{}</select>
+        {}
+        <button type="submit" class="btn">Create Product</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Name</th>
+                <th>Category</th>
+                <th>Price</th>
+                <th>Stock</th>
+                <th>Created</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        base.code, category_options, tax_class_options(TaxClass::default()), tag_input(&store, &[]), rows
     );
 
-    Html(html)
+    Ok(Html(admin_page("Products - Admin", "/admin/products", &content)))
 }
 
 #[derive(Deserialize)]
@@ -268,258 +449,3851 @@ pub struct CreateProductForm {
     price: f64,
     category_id: String,
     stock: i32,
+    tax_class: String,
+    /// Comma separated tag names
+    #[serde(default)]
+    tags: String,
 }
 
 /// Creates a new product
 pub async fn admin_create_product(
     State(store): State<Store>,
     Form(form): Form<CreateProductForm>,
-) -> impl IntoResponse {
-    let product = Product::new(form.name, form.description, form.price, form.category_id, form.stock);
-    store.products.write().unwrap().insert(product.id.clone(), product);
-    Redirect::to("/admin/products")
+) -> AppResult<Redirect> {
+    let mut product = Product::new(form.name, form.description, form.price, form.category_id, form.stock);
+    product.tax_class = TaxClass::from_form(&form.tax_class).unwrap_or_default();
+    product.tag_ids = store.resolve_tags(&form.tags);
+    store.record_initial_stock(&product, "Admin");
+    store.products.write()?.insert(product.id.clone(), product);
+    Ok(Redirect::to("/admin/products"))
 }
 
 /// Deletes a product
 pub async fn admin_delete_product(
     State(store): State<Store>,
     Path(id): Path<String>,
-) -> impl IntoResponse {
-    store.products.write().unwrap().remove(&id);
-    Redirect::to("/admin/products")
+) -> AppResult<Redirect> {
+    store.products.write()?.remove(&id);
+    Ok(Redirect::to("/admin/products"))
 }
 
-/// Lists all blog categories in admin panel
-pub async fn admin_list_blog_categories(State(store): State<Store>) -> impl IntoResponse {
-    let blog_categories = store.blog_categories.read().unwrap();
-    let mut categories_vec: Vec<_> = blog_categories.values().collect();
-    categories_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+/// Shows the edit form for a product
+/// Includes an optional fixed price for every non-base currency
+pub async fn admin_edit_product(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let base = store.base_currency();
+    let settings = store.settings();
+    let currencies = store.currencies.read()?;
+    let products = store.products.read()?;
+    let categories = store.categories.read()?;
 
-    let mut rows = String::new();
-    for cat in categories_vec {
-        rows.push_str(&format!(
+    let product = match products.get(&id) {
+        Some(product) => product,
+        None => return Err(AppError::NotFound),
+    };
+
+    let mut attribute_fields = String::new();
+    if let Some(category) = categories.get(&product.category_id) {
+        for attribute in &category.attributes {
+            let current = product.attributes.get(&attribute.id).map(|v| v.raw()).unwrap_or_default();
+            let input = match attribute.attribute_type {
+                AttributeType::Select | AttributeType::Boolean => {
+                    let choices: Vec<(String, String)> = if attribute.attribute_type == AttributeType::Boolean {
+                        vec![("true".to_string(), "Yes".to_string()), ("false".to_string(), "No".to_string())]
+                    } else {
+                        attribute.choices.iter().map(|c| (c.clone(), c.clone())).collect()
+                    };
+                    let mut options = String::from(r#"<option value="">-</option>"#);
+                    for (value, label) in choices {
+                        let selected = if value == current { " selected" } else { "" };
+                        options.push_str(&format!(r#"<option value="{}"{}>{}</option>"#, value, selected, label));
+                    }
+                    format!(r#"<select name="attr_{}">{}</select>"#, attribute.id, options)
+                }
+                AttributeType::Number => format!(
+                    r#"<input type="number" step="any" name="attr_{}" value="{}">"#,
+                    attribute.id, current
+                ),
+                AttributeType::Text => format!(
+                    r#"<input type="text" name="attr_{}" value="{}">"#,
+                    attribute.id, current
+                ),
+            };
+            attribute_fields.push_str(&format!(
+                r#"<label>{}{}
+            {}
+        </label>"#,
+                attribute.name,
+                attribute.unit.as_ref().map(|u| format!(" ({})", u)).unwrap_or_default(),
+                input
+            ));
+        }
+    }
+
+    let mut currencies_vec: Vec<_> = currencies.values().filter(|c| c.code != base.code).collect();
+    currencies_vec.sort_by(|a, b| a.code.cmp(&b.code));
+
+    let mut override_fields = String::new();
+    for currency in currencies_vec {
+        let value = product
+            .price_overrides
+            .get(&currency.code)
+            .map(|price| format!("{:.*}", currency.decimals, price))
+            .unwrap_or_default();
+        override_fields.push_str(&format!(
+            r#"<label>{} price
+            <input type="number" step="0.01" name="override_{}" value="{}" placeholder="{} (converted)">
+        </label>"#,
+            currency.code, currency.code, value, currency.format(currency.convert(product.price))
+        ));
+    }
+
+    let mut option_rows = String::new();
+    for (index, option) in product.options.iter().enumerate() {
+        option_rows.push_str(&format!(
             r#"<tr>
                 <td>{}</td>
                 <td>{}</td>
+                <td>
+                    <form method="post" action="/admin/products/options/delete/{}/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            option.name, option.values.join(", "), product.id, index
+        ));
+    }
+
+    let mut variant_rows = String::new();
+    for variant in &product.variants {
+        variant_rows.push_str(&format!(
+            r#"<tr>
                 <td>{}</td>
                 <td>
-                    <form method="post" action="/admin/blog-categories/delete/{}" style="display:inline;">
+                    <form method="post" action="/admin/products/variants/update/{}/{}" style="display:inline;">
+                        <input type="text" name="sku" value="{}" required>
+                        <input type="number" step="0.01" name="price" value="{}" placeholder="{:.2}">
+                        <button type="submit" class="btn btn-sm">Save</button>
+                    </form>
+                </td>
+                <td>{}</td>
+                <td>
+                    <form method="post" action="/admin/products/variants/delete/{}/{}" style="display:inline;">
                         <button type="submit" class="btn btn-danger">Delete</button>
                     </form>
                 </td>
             </tr>"#,
-            cat.name, cat.description, cat.created_at.format("%Y-%m-%d"), cat.id
+            variant.title(&product.options), product.id, variant.id, variant.sku,
+            variant.price.map(|p| format!("{:.2}", p)).unwrap_or_default(), product.price,
+            variant.stock, product.id, variant.id
         ));
     }
 
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Blog Categories - Admin</title>
-    <link rel="stylesheet" href="/static/css/admin.css">
-</head>
-<body>
-    <nav class="admin-nav">
-        <h1>CMS Admin Panel</h1>
-        <ul>
-            <li><a href="/admin">Dashboard</a></li>
-            <li><a href="/admin/categories">Product Categories</a></li>
-            <li><a href="/admin/products">Products</a></li>
-            <li><a href="/admin/blog-categories" class="active">Blog Categories</a></li>
-            <li><a href="/admin/posts">Blog Posts</a></li>
-            <li><a href="/">View Site</a></li>
-        </ul>
-    </nav>
-    <div class="container">
-        <h2>Blog Categories</h2>
-        <form method="post" action="/admin/blog-categories/create" class="create-form">
-            <input type="text" name="name" placeholder="Category Name" required>
-            <input type="text" name="description" placeholder="Description" required>
-            <button type="submit" class="btn">Create Category</button>
-        </form>
-        <table>
-            <thead>
-                <tr>
-                    <th>Name</th>
-                    <th>Description</th>
-                    <th>Created</th>
-                    <th>Actions</th>
-                </tr>
-            </thead>
-            <tbody>
+    let mut gallery_items = String::new();
+    for (index, image) in product.images.iter().enumerate() {
+        let thumbnail = store
+            .media_by_url(&image.url)
+            .map(|asset| asset.url_for(ImageSize::Thumbnail))
+            .unwrap_or_else(|| image.url.clone());
+        let primary = if index == 0 {
+            r#"<span class="badge">Primary</span>"#.to_string()
+        } else {
+            format!(
+                r#"<form method="post" action="/admin/products/images/primary/{}/{}">
+                    <button type="submit" class="btn btn-sm">Make Primary</button>
+                </form>"#,
+                product.id, image.id
+            )
+        };
+        gallery_items.push_str(&format!(
+            r#"<li class="gallery-item" draggable="true" data-id="{}">
+                <img src="{}" alt="{}" draggable="false">
                 {}
-            </tbody>
-        </table>
-    </div>
-</body>
-</html>"#,
-        rows
+                <form method="post" action="/admin/products/images/alt/{}/{}">
+                    <input type="text" name="alt" value="{}" placeholder="Alt text">
+                    <button type="submit" class="btn btn-sm">Save</button>
+                </form>
+                <form method="post" action="/admin/products/images/delete/{}/{}">
+                    <button type="submit" class="btn btn-danger btn-sm">Remove</button>
+                </form>
+            </li>"#,
+            image.id, thumbnail, escape_html(product.image_alt(image)), primary,
+            product.id, image.id, escape_html(&image.alt),
+            product.id, image.id
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Edit Product</h2>
+    <form method="post" action="/admin/products/edit/{}" class="create-form">
+        <input type="text" name="name" value="{}" placeholder="Product Name" required>
+        <input type="text" name="description" value="{}" placeholder="Description" required>
+        <label>Price ({})
+            <input type="number" step="0.01" name="price" value="{:.2}" required>
+        </label>
+        <label>Tax Class
+            <select name="tax_class">{}</select>
+        </label>
+        {}
+        <label>Weight (kg)
+            <input type="number" step="0.001" min="0" name="weight" value="{}">
+        </label>
+        <label>Length, Width, Height (cm)
+            <input type="number" step="0.1" min="0" name="length" value="{}" placeholder="Length">
+            <input type="number" step="0.1" min="0" name="width" value="{}" placeholder="Width">
+            <input type="number" step="0.1" min="0" name="height" value="{}" placeholder="Height">
+        </label>
+        <label>Sale Price
+            <input type="number" step="0.01" name="sale_price" value="{}">
+        </label>
+        <label>Sale Starts ({})
+            <input type="datetime-local" name="sale_starts_at" value="{}">
+        </label>
+        <label>Sale Ends ({})
+            <input type="datetime-local" name="sale_ends_at" value="{}">
+        </label>
+        {}
+        {}
+        {}
+        <button type="submit" class="btn">Save Product</button>
+    </form>
+    <p>Leave a currency price empty to convert from the {} price using the exchange rate.
+    Stock is changed from the <a href="/admin/inventory/{}">inventory history</a>.</p>
+
+    <h2>Images</h2>
+    <p>The first image is the primary image shown on product cards. Drag images to change their order.</p>
+    <ul class="gallery-admin" id="gallery" data-reorder="/admin/products/images/reorder/{}">
+        {}
+    </ul>
+    <form method="post" action="/admin/products/images/add/{}" enctype="multipart/form-data" class="create-form">
+        {}
+        <input type="text" name="alt" placeholder="Alt text describing the image">
+        <button type="submit" class="btn">Add Image</button>
+    </form>
+    <script>
+        (function () {{
+            var list = document.getElementById('gallery');
+            var dragged = null;
+            list.addEventListener('dragstart', function (e) {{
+                dragged = e.target.closest('.gallery-item');
+                dragged.classList.add('dragging');
+            }});
+            list.addEventListener('dragover', function (e) {{
+                e.preventDefault();
+                var target = e.target.closest('.gallery-item');
+                if (!target || target === dragged) return;
+                var rect = target.getBoundingClientRect();
+                list.insertBefore(dragged, e.clientX > rect.left + rect.width / 2 ? target.nextSibling : target);
+            }});
+            list.addEventListener('dragend', function () {{
+                dragged.classList.remove('dragging');
+                var ids = Array.from(list.children).map(function (item) {{ return item.dataset.id; }});
+                fetch(list.dataset.reorder, {{ method: 'POST', body: new URLSearchParams({{ order: ids.join(',') }}) }})
+                    .then(function () {{ location.reload(); }});
+            }});
+        }})();
+    </script>
+
+    <h2>Options</h2>
+    <form method="post" action="/admin/products/options/create/{}" class="create-form">
+        <input type="text" name="name" placeholder="Option Name (e.g. Size)" required>
+        <input type="text" name="values" placeholder="Values, comma separated (e.g. S, M, L)" required>
+        <button type="submit" class="btn">Add Option</button>
+    </form>
+    <p>Changing options removes existing variants.</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Option</th>
+                <th>Values</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+
+    <h2>Variants</h2>
+    <form method="post" action="/admin/products/variants/generate/{}" class="create-form">
+        <button type="submit" class="btn">Generate Missing Variants</button>
+    </form>
+    <p>Leave a variant price empty to use the product price. Total stock: {}</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Variant</th>
+                <th>SKU / Price ({})</th>
+                <th>Stock</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        product.id, escape_html(&product.name), escape_html(&product.description), base.code, product.price,
+        tax_class_options(product.tax_class), tag_input(&store, &product.tag_ids),
+        product.weight,
+        product.dimensions.map(|d| d.length.to_string()).unwrap_or_default(),
+        product.dimensions.map(|d| d.width.to_string()).unwrap_or_default(),
+        product.dimensions.map(|d| d.height.to_string()).unwrap_or_default(),
+        product.sale_price.map(|p| format!("{:.2}", p)).unwrap_or_default(),
+        settings.timezone, format_datetime_local(product.sale_starts_at, &settings),
+        settings.timezone, format_datetime_local(product.sale_ends_at, &settings),
+        override_fields, attribute_fields,
+        seo_fields(&product.seo, &format!("{} - {}", product.name, store.site_name()), &product.description),
+        base.code, product.id,
+        product.id, gallery_items, product.id, image_picker(&store, None, false),
+        product.id, option_rows,
+        product.id, product.total_stock(), base.code, variant_rows
     );
 
-    Html(html)
+    Ok(Html(admin_page("Edit Product - Admin", "/admin/products", &content)))
 }
 
-/// Creates a new blog category
-pub async fn admin_create_blog_category(
+/// Updates a product, its tax class, tags, meta overrides, shipping size, sale, per-currency price overrides and attribute values
+/// Override fields are named `override_<CODE>`; empty fields remove the override
+pub async fn admin_update_product(
     State(store): State<Store>,
-    Form(form): Form<CreateCategoryForm>,
-) -> impl IntoResponse {
-    let category = BlogCategory::new(form.name, form.description);
-    store.blog_categories.write().unwrap().insert(category.id.clone(), category);
-    Redirect::to("/admin/blog-categories")
+    Path(id): Path<String>,
+    Form(form): Form<HashMap<String, String>>,
+) -> AppResult<Redirect> {
+    let field = |name: &str| form.get(name).map(|v| v.trim().to_string()).unwrap_or_default();
+    if let Ok(price) = field("price").parse::<f64>() {
+        let tag_ids = store.resolve_tags(&field("tags"));
+        let settings = store.settings();
+        let mut products = store.products.write()?;
+        let categories = store.categories.read()?;
+        if let Some(product) = products.get_mut(&id) {
+            product.update(field("name"), field("description"), price);
+            product.tax_class = TaxClass::from_form(&field("tax_class")).unwrap_or_default();
+            product.tag_ids = tag_ids;
+            product.seo = SeoMeta::new(&field("meta_title"), &field("meta_description"));
+            product.weight = field("weight").parse::<f64>().unwrap_or(0.0).max(0.0);
+            let size = |name: &str| field(name).parse::<f64>().ok().filter(|v| *v > 0.0);
+            product.dimensions = match (size("length"), size("width"), size("height")) {
+                (Some(length), Some(width), Some(height)) => Some(Dimensions { length, width, height }),
+                _ => None,
+            };
+            product.sale_price = field("sale_price").parse::<f64>().ok().filter(|p| *p >= 0.0);
+            product.sale_starts_at = parse_datetime_local(&field("sale_starts_at"), &settings);
+            product.sale_ends_at = parse_datetime_local(&field("sale_ends_at"), &settings);
+            product.price_overrides.clear();
+            for (key, value) in &form {
+                if let (Some(code), Ok(amount)) = (key.strip_prefix("override_"), value.trim().parse::<f64>()) {
+                    product.price_overrides.insert(code.to_string(), amount);
+                }
+            }
+
+            // Attribute fields are named `attr_<id>`; values that don't parse are dropped
+            product.attributes.clear();
+            if let Some(category) = categories.get(&product.category_id) {
+                for attribute in &category.attributes {
+                    let raw = field(&format!("attr_{}", attribute.id));
+                    if let Some(value) = attribute.parse(&raw) {
+                        product.attributes.insert(attribute.id.clone(), value);
+                    }
+                }
+            }
+        }
+    }
+    Ok(Redirect::to("/admin/products"))
 }
 
-/// Deletes a blog category
-pub async fn admin_delete_blog_category(
+/// Adds an image to the product gallery from an upload or the media library
+pub async fn admin_add_product_image(
     State(store): State<Store>,
     Path(id): Path<String>,
-) -> impl IntoResponse {
-    store.blog_categories.write().unwrap().remove(&id);
-    Redirect::to("/admin/blog-categories")
+    multipart: Multipart,
+) -> AppResult<Response> {
+    let back = format!("/admin/products/edit/{}", id);
+    let chosen = match read_upload_form(multipart).await {
+        Ok(mut form) => chosen_image(&store, &mut form)
+            .await
+            .and_then(|url| url.ok_or_else(|| "Choose an image from the library or upload one".to_string()))
+            .map(|url| (url, form.fields.remove("alt").unwrap_or_default())),
+        Err(error) => Err(error),
+    };
+    Ok(match chosen {
+        Ok((url, alt)) => {
+            if let Some(product) = store.products.write()?.get_mut(&id) {
+                product.images.push(ProductImage::new(url, alt));
+                product.updated_at = Utc::now();
+            }
+            Redirect::to(&back).into_response()
+        }
+        Err(error) => action_error_page("Upload Failed", &error, &back, "/admin/products").into_response(),
+    })
 }
 
-/// Lists all blog posts in admin panel
-pub async fn admin_list_posts(State(store): State<Store>) -> impl IntoResponse {
-    let posts = store.posts.read().unwrap();
-    let categories = store.blog_categories.read().unwrap();
-    let mut posts_vec: Vec<_> = posts.values().collect();
-    posts_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+#[derive(Deserialize)]
+pub struct ProductImageAltForm {
+    alt: String,
+}
+
+/// Changes the alt text of a gallery image
+pub async fn admin_update_product_image_alt(
+    State(store): State<Store>,
+    Path((id, image_id)): Path<(String, String)>,
+    Form(form): Form<ProductImageAltForm>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        if let Some(image) = product.images.iter_mut().find(|i| i.id == image_id) {
+            image.alt = form.alt.trim().to_string();
+        }
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+/// Moves a gallery image to the front, making it the primary image
+pub async fn admin_set_primary_product_image(
+    State(store): State<Store>,
+    Path((id, image_id)): Path<(String, String)>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        product.reorder_images(&[image_id.as_str()]);
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+#[derive(Deserialize)]
+pub struct ReorderImagesForm {
+    /// Comma separated image ids in their new order
+    order: String,
+}
+
+/// Reorders the gallery, posted by the drag and drop list on the edit page
+pub async fn admin_reorder_product_images(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<ReorderImagesForm>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        let ids: Vec<&str> = form.order.split(',').map(|id| id.trim()).collect();
+        product.reorder_images(&ids);
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+/// Removes an image from the gallery, the file stays in the media library
+pub async fn admin_delete_product_image(
+    State(store): State<Store>,
+    Path((id, image_id)): Path<(String, String)>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        product.images.retain(|i| i.id != image_id);
+        product.updated_at = Utc::now();
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+#[derive(Deserialize)]
+pub struct CreateOptionForm {
+    name: String,
+    values: String,
+}
+
+/// Adds an option type such as size or color to a product
+pub async fn admin_create_product_option(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<CreateOptionForm>,
+) -> AppResult<Redirect> {
+    let option = ProductOption::new(form.name, &form.values);
+    if !option.name.is_empty() && !option.values.is_empty() {
+        if let Some(product) = store.products.write()?.get_mut(&id) {
+            product.add_option(option);
+        }
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+/// Removes an option type from a product
+pub async fn admin_delete_product_option(
+    State(store): State<Store>,
+    Path((id, index)): Path<(String, usize)>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        if let Some(name) = product.options.get(index).map(|o| o.name.clone()) {
+            product.remove_option(&name);
+        }
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+/// Creates variants for all option combinations that don't have one
+pub async fn admin_generate_variants(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        product.generate_variants();
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+#[derive(Deserialize)]
+pub struct UpdateVariantForm {
+    sku: String,
+    price: String,
+}
+
+/// Updates the SKU and price of a variant
+/// An empty price makes the variant use the product price
+pub async fn admin_update_variant(
+    State(store): State<Store>,
+    Path((id, variant_id)): Path<(String, String)>,
+    Form(form): Form<UpdateVariantForm>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        if let Some(variant) = product.variants.iter_mut().find(|v| v.id == variant_id) {
+            variant.sku = form.sku.trim().to_string();
+            variant.price = form.price.trim().parse::<f64>().ok();
+        }
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+/// Deletes a single variant
+pub async fn admin_delete_variant(
+    State(store): State<Store>,
+    Path((id, variant_id)): Path<(String, String)>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        product.variants.retain(|v| v.id != variant_id);
+    }
+    Ok(Redirect::to(&format!("/admin/products/edit/{}", id)))
+}
+
+/// Lists stock levels of all products and variants
+pub async fn admin_inventory(State(store): State<Store>) -> AppResult<Html<String>> {
+    let default_threshold = *store.low_stock_threshold.read()?;
+    let products = store.products.read()?;
+    let mut products_vec: Vec<_> = products.values().collect();
+    products_vec.sort_by(|a, b| a.name.cmp(&b.name));
 
     let mut rows = String::new();
-    for post in posts_vec {
-        let category_name = categories
-            .get(&post.category_id)
-            .map(|c| c.name.as_str())
-            .unwrap_or("Unknown");
-        
-        let status = if post.published { "Published" } else { "Draft" };
+    for product in products_vec {
+        let threshold = product.low_stock_threshold.unwrap_or(default_threshold);
+        let mut entries: Vec<(String, String, i32)> = Vec::new();
+        if product.variants.is_empty() {
+            entries.push((product.name.clone(), "-".to_string(), product.stock));
+        } else {
+            for variant in &product.variants {
+                entries.push((
+                    format!("{} ({})", product.name, variant.title(&product.options)),
+                    variant.sku.clone(),
+                    variant.stock,
+                ));
+            }
+        }
+
+        for (name, sku, stock) in entries {
+            let status = if stock <= threshold {
+                r#"<span class="badge badge-warning">Low</span>"#
+            } else {
+                r#"<span class="badge">OK</span>"#
+            };
+            rows.push_str(&format!(
+                r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td><a href="/admin/inventory/{}" class="btn btn-sm">History</a></td>
+            </tr>"#,
+                name, sku, stock, threshold, status, product.id
+            ));
+        }
+    }
+
+    let content = format!(
+        r#"<h2>Inventory</h2>
+    <form method="post" action="/admin/inventory/default-threshold" class="create-form">
+        <label>Default low stock threshold
+            <input type="number" name="threshold" min="0" value="{}" required>
+        </label>
+        <button type="submit" class="btn">Save</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Product</th>
+                <th>SKU</th>
+                <th>Stock</th>
+                <th>Threshold</th>
+                <th>Status</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        default_threshold, rows
+    );
+
+    Ok(Html(admin_page("Inventory - Admin", "/admin/inventory", &content)))
+}
+
+/// Shows the stock movement history of a product
+/// Also offers forms to record a new movement and set the threshold
+pub async fn admin_product_inventory(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let default_threshold = *store.low_stock_threshold.read()?;
+    let products = store.products.read()?;
+    let product = match products.get(&id) {
+        Some(product) => product,
+        None => return Err(AppError::NotFound),
+    };
+
+    let movements = store.stock_movements.read()?;
+    let mut movements_vec: Vec<_> = movements.values().filter(|m| m.product_id == product.id).collect();
+    movements_vec.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+
+    let variant_title = |variant_id: &Option<String>| match variant_id {
+        Some(variant_id) => product
+            .variants
+            .iter()
+            .find(|v| &v.id == variant_id)
+            .map(|v| v.title(&product.options))
+            .unwrap_or_else(|| "Deleted variant".to_string()),
+        None => "-".to_string(),
+    };
 
+    let mut rows = String::new();
+    for movement in movements_vec {
         rows.push_str(&format!(
             r#"<tr>
                 <td>{}</td>
                 <td>{}</td>
                 <td>{}</td>
+                <td>{:+}</td>
+                <td>{}</td>
+                <td>{}</td>
                 <td>{}</td>
-                <td>
-                    <form method="post" action="/admin/posts/toggle/{}" style="display:inline;">
-                        <button type="submit" class="btn btn-sm">{}</button>
-                    </form>
-                    <form method="post" action="/admin/posts/delete/{}" style="display:inline;">
-                        <button type="submit" class="btn btn-danger">Delete</button>
-                    </form>
-                </td>
             </tr>"#,
-            post.title, category_name, status, post.created_at.format("%Y-%m-%d"), 
-            post.id, if post.published { "Unpublish" } else { "Publish" },
-            post.id
+            movement.created_at.format("%Y-%m-%d %H:%M"), variant_title(&movement.variant_id),
+            movement.kind.label(), movement.quantity, movement.stock_after, movement.reason, movement.user
         ));
     }
 
-    let mut category_options = String::new();
-    for cat in categories.values() {
-        category_options.push_str(&format!(r#"<option value="{}">{}</option>"#, cat.id, cat.name));
-    }
+    let variant_select = if product.variants.is_empty() {
+        String::new()
+    } else {
+        let mut options = String::new();
+        for variant in &product.variants {
+            options.push_str(&format!(
+                r#"<option value="{}">{} ({} in stock)</option>"#,
+                variant.id, variant.title(&product.options), variant.stock
+            ));
+        }
+        format!(r#"<select name="variant_id" required>{}</select>"#, options)
+    };
 
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Blog Posts - Admin</title>
-    <link rel="stylesheet" href="/static/css/admin.css">
-</head>
-<body>
-    <nav class="admin-nav">
-        <h1>CMS Admin Panel</h1>
-        <ul>
-            <li><a href="/admin">Dashboard</a></li>
-            <li><a href="/admin/categories">Product Categories</a></li>
-            <li><a href="/admin/products">Products</a></li>
-            <li><a href="/admin/blog-categories">Blog Categories</a></li>
-            <li><a href="/admin/posts" class="active">Blog Posts</a></li>
-            <li><a href="/">View Site</a></li>
-        </ul>
-    </nav>
-    <div class="container">
-        <h2>Blog Posts</h2>
-        <form method="post" action="/admin/posts/create" class="create-form">
-            <input type="text" name="title" placeholder="Post Title" required>
-            <textarea name="excerpt" placeholder="Excerpt" required></textarea>
-            <textarea name="content" placeholder="Content" required rows="5"></textarea>
-            <input type="text" name="author" placeholder="Author" required>
-            <select name="category_id" required>
-                <option value="">Select Category</option>
-                {}
-            </select>
-            <button type="submit" class="btn">Create Post</button>
-        </form>
-        <table>
-            <thead>
-                <tr>
-                    <th>Title</th>
-                    <th>Category</th>
-                    <th>Status</th>
-                    <th>Created</th>
-                    <th>Actions</th>
-                </tr>
-            </thead>
-            <tbody>
-                {}
-            </tbody>
-        </table>
-    </div>
-</body>
-</html>"#,
-        category_options, rows
+    let content = format!(
+        r#"<h2>Inventory: {}</h2>
+    <p>Current stock: {}. <a href="/admin/products/edit/{}">Edit product</a></p>
+    <form method="post" action="/admin/inventory/adjust/{}" class="create-form">
+        {}
+        <select name="kind" required>
+            <option value="adjustment">Adjustment (+/-)</option>
+            <option value="import">Import (+)</option>
+            <option value="return">Return (+)</option>
+            <option value="sale">Sale (-)</option>
+        </select>
+        <input type="number" name="quantity" placeholder="Quantity" required>
+        <input type="text" name="reason" placeholder="Reason" required>
+        <input type="text" name="user" placeholder="Your Name" required>
+        <button type="submit" class="btn">Record Movement</button>
+    </form>
+    <form method="post" action="/admin/inventory/threshold/{}" class="create-form">
+        <label>Low stock threshold (empty uses default of {})
+            <input type="number" name="threshold" min="0" value="{}">
+        </label>
+        <button type="submit" class="btn">Save Threshold</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Date</th>
+                <th>Variant</th>
+                <th>Type</th>
+                <th>Change</th>
+                <th>Stock After</th>
+                <th>Reason</th>
+                <th>User</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        product.name, product.total_stock(), product.id, product.id, variant_select,
+        product.id, default_threshold,
+        product.low_stock_threshold.map(|t| t.to_string()).unwrap_or_default(), rows
     );
 
-    Html(html)
+    Ok(Html(admin_page("Inventory History - Admin", "/admin/inventory", &content)))
 }
 
 #[derive(Deserialize)]
-pub struct CreatePostForm {
-    title: String,
-    content: String,
-    excerpt: String,
-    category_id: String,
-    author: String,
+pub struct StockMovementForm {
+    variant_id: Option<String>,
+    kind: String,
+    quantity: i32,
+    reason: String,
+    user: String,
 }
 
-/// Creates a new blog post
-pub async fn admin_create_post(
+/// Records a stock movement for a product or variant
+pub async fn admin_adjust_stock(
     State(store): State<Store>,
-    Form(form): Form<CreatePostForm>,
-) -> impl IntoResponse {
-    let post = Post::new(form.title, form.content, form.excerpt, form.category_id, form.author);
-    store.posts.write().unwrap().insert(post.id.clone(), post);
-    Redirect::to("/admin/posts")
+    Path(id): Path<String>,
+    Form(form): Form<StockMovementForm>,
+) -> AppResult<Response> {
+    let back = format!("/admin/inventory/{}", id);
+    let recorded = StockMovementKind::from_form(&form.kind).and_then(|kind| {
+        store.record_stock_movement(
+            &id,
+            form.variant_id.as_deref().filter(|v| !v.is_empty()),
+            kind,
+            form.quantity,
+            form.reason.trim().to_string(),
+            form.user.trim().to_string(),
+        )
+    });
+    Ok(match recorded {
+        Some(_) => Redirect::to(&back).into_response(),
+        None => action_error_page(
+            "Stock Not Changed",
+            "Check the variant and quantity, zero changes aren't recorded and stock can't go below zero",
+            &back,
+            "/admin/inventory",
+        )
+        .into_response(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct ThresholdForm {
+    threshold: String,
 }
 
-/// Toggles post published status
-pub async fn admin_toggle_post(
+/// Sets or clears the low stock threshold of a product
+pub async fn admin_set_product_threshold(
     State(store): State<Store>,
     Path(id): Path<String>,
-) -> impl IntoResponse {
-    if let Some(post) = store.posts.write().unwrap().get_mut(&id) {
-        if post.published {
-            post.unpublish();
-        } else {
-            post.publish();
-        }
+    Form(form): Form<ThresholdForm>,
+) -> AppResult<Redirect> {
+    if let Some(product) = store.products.write()?.get_mut(&id) {
+        product.low_stock_threshold = form.threshold.trim().parse::<i32>().ok().filter(|t| *t >= 0);
     }
-    Redirect::to("/admin/posts")
+    Ok(Redirect::to(&format!("/admin/inventory/{}", id)))
 }
 
-/// Deletes a blog post
-pub async fn admin_delete_post(
+/// Sets the store wide default low stock threshold
+pub async fn admin_set_default_threshold(
     State(store): State<Store>,
-    Path(id): Path<String>,
-) -> impl IntoResponse {
-    store.posts.write().unwrap().remove(&id);
-    Redirect::to("/admin/posts")
+    Form(form): Form<ThresholdForm>,
+) -> AppResult<Redirect> {
+    if let Some(threshold) = form.threshold.trim().parse::<i32>().ok().filter(|t| *t >= 0) {
+        *store.low_stock_threshold.write()? = threshold;
+    }
+    Ok(Redirect::to("/admin/inventory"))
 }
 
+/// Lists customer accounts with their number of orders
+pub async fn admin_list_customers(State(store): State<Store>) -> AppResult<Html<String>> {
+    let orders = store.orders.read()?;
+    let customers = store.customers.read()?;
+    let mut customers_vec: Vec<_> = customers.values().collect();
+    customers_vec.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+
+    let mut rows = String::new();
+    for customer in customers_vec {
+        let order_count = orders
+            .values()
+            .filter(|o| o.customer_id.as_deref() == Some(customer.id.as_str()))
+            .count();
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+            </tr>"#,
+            escape_html(&customer.name), escape_html(&customer.email), order_count, customer.addresses.len(),
+            customer.created_at.format("%Y-%m-%d")
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Customers</h2>
+    <p>Customers sign up on the shop. Guest orders are linked to an account with the same email once the customer has confirmed the email.</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Name</th>
+                <th>Email</th>
+                <th>Orders</th>
+                <th>Addresses</th>
+                <th>Joined</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        rows
+    );
+
+    Ok(Html(admin_page("Customers - Admin", "/admin/customers", &content)))
+}
+
+/// Lists all coupons with their usage
+pub async fn admin_list_coupons(State(store): State<Store>) -> AppResult<Html<String>> {
+    let base = store.base_currency();
+    let products = store.products.read()?;
+    let categories = store.categories.read()?;
+    let coupons = store.coupons.read()?;
+    let settings = store.settings();
+    let mut coupons_vec: Vec<_> = coupons.values().collect();
+    coupons_vec.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+
+    let mut rows = String::new();
+    for coupon in coupons_vec {
+        let validity = match (coupon.starts_at, coupon.ends_at) {
+            (None, None) => "Always".to_string(),
+            (start, end) => format!(
+                "{} &ndash; {}",
+                start.map(|d| settings.local_time(d).format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default(),
+                end.map(|d| settings.local_time(d).format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+            ),
+        };
+        let restrictions: Vec<&str> = coupon
+            .category_ids
+            .iter()
+            .filter_map(|id| categories.get(id).map(|c| c.name.as_str()))
+            .chain(coupon.product_ids.iter().filter_map(|id| products.get(id).map(|p| p.name.as_str())))
+            .collect();
+
+        rows.push_str(&format!(
+            r#"<tr>
+                <td><strong>{}</strong></td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}{}</td>
+                <td>{}</td>
+                <td>
+                    <form method="post" action="/admin/coupons/toggle/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-sm">{}</button>
+                    </form>
+                    <form method="post" action="/admin/coupons/delete/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            coupon.code, coupon.describe(&base), validity,
+            coupon.minimum_order.map(|m| format!("{:.2}", m)).unwrap_or_else(|| "-".to_string()),
+            if restrictions.is_empty() { "All products".to_string() } else { restrictions.join(", ") },
+            coupon.times_used,
+            coupon.usage_limit.map(|l| format!(" / {}", l)).unwrap_or_default(),
+            if coupon.active { "Active" } else { "Disabled" },
+            coupon.id, if coupon.active { "Disable" } else { "Enable" }, coupon.id
+        ));
+    }
+
+    let mut category_options = String::new();
+    for cat in categories.values() {
+        category_options.push_str(&format!(r#"<option value="{}">{}</option>"#, cat.id, cat.name));
+    }
+    let mut product_options = String::new();
+    for product in products.values() {
+        product_options.push_str(&format!(r#"<option value="{}">{}</option>"#, product.id, product.name));
+    }
+
+    let content = format!(
+        r#"<h2>Coupons</h2>
+    <form method="post" action="/admin/coupons/create" class="create-form">
+        <input type="text" name="code" placeholder="Code (e.g. SUMMER20)" required>
+        <select name="discount_type" required>
+            <option value="percentage">Percentage off</option>
+            <option value="fixed">Fixed amount off ({})</option>
+            <option value="free_shipping">Free shipping</option>
+        </select>
+        <input type="number" step="0.01" min="0" name="value" placeholder="Value">
+        <input type="number" step="0.01" min="0" name="minimum_order" placeholder="Minimum order ({})">
+        <input type="number" min="1" name="usage_limit" placeholder="Usage limit">
+        <label>Valid from ({})
+            <input type="datetime-local" name="starts_at">
+        </label>
+        <label>Valid until ({})
+            <input type="datetime-local" name="ends_at">
+        </label>
+        <label>Only categories
+            <select name="category_ids" multiple>{}</select>
+        </label>
+        <label>Only products
+            <select name="product_ids" multiple>{}</select>
+        </label>
+        <button type="submit" class="btn">Create Coupon</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Code</th>
+                <th>Discount</th>
+                <th>Valid</th>
+                <th>Min. Order</th>
+                <th>Applies To</th>
+                <th>Used</th>
+                <th>Status</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        store.base_currency.read()?, store.base_currency.read()?,
+        settings.timezone, settings.timezone, category_options, product_options, rows
+    );
+
+    Ok(Html(admin_page("Coupons - Admin", "/admin/coupons", &content)))
+}
+
+/// Creates a coupon
+/// Read as a list of pairs because the restriction selects send repeated keys
+pub async fn admin_create_coupon(
+    State(store): State<Store>,
+    Form(form): Form<Vec<(String, String)>>,
+) -> AppResult<Redirect> {
+    let field = |name: &str| {
+        form.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim().to_string())
+            .unwrap_or_default()
+    };
+    let values = |name: &str| -> Vec<String> {
+        form.iter()
+            .filter(|(key, value)| key == name && !value.is_empty())
+            .map(|(_, value)| value.clone())
+            .collect()
+    };
+
+    let code = field("code");
+    if let (false, Some(discount_type)) = (code.is_empty(), DiscountType::from_form(&field("discount_type"))) {
+        let mut coupon = Coupon::new(code, discount_type, field("value").parse::<f64>().unwrap_or(0.0).max(0.0));
+        coupon.minimum_order = field("minimum_order").parse::<f64>().ok();
+        coupon.usage_limit = field("usage_limit").parse::<u32>().ok();
+        let settings = store.settings();
+        coupon.starts_at = parse_datetime_local(&field("starts_at"), &settings);
+        coupon.ends_at = parse_datetime_local(&field("ends_at"), &settings);
+        coupon.category_ids = values("category_ids");
+        coupon.product_ids = values("product_ids");
+
+        let mut coupons = store.coupons.write()?;
+        if !coupons.values().any(|c| c.code == coupon.code) {
+            coupons.insert(coupon.id.clone(), coupon);
+        }
+    }
+    Ok(Redirect::to("/admin/coupons"))
+}
+
+/// Enables or disables a coupon
+pub async fn admin_toggle_coupon(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    if let Some(coupon) = store.coupons.write()?.get_mut(&id) {
+        coupon.active = !coupon.active;
+    }
+    Ok(Redirect::to("/admin/coupons"))
+}
+
+/// Deletes a coupon
+pub async fn admin_delete_coupon(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    store.coupons.write()?.remove(&id);
+    Ok(Redirect::to("/admin/coupons"))
+}
+
+/// Lists all orders, newest first
+pub async fn admin_list_orders(State(store): State<Store>) -> AppResult<Html<String>> {
+    let orders = store.orders.read()?;
+    let mut orders_vec: Vec<_> = orders.values().collect();
+    orders_vec.sort_by_key(|o| std::cmp::Reverse(o.created_at));
+
+    let mut rows = String::new();
+    for order in orders_vec {
+        let currency = store.currency_or_base(Some(&order.currency_code));
+        rows.push_str(&format!(
+            r#"<tr>
+                <td><a href="/admin/orders/{}">#{}</a></td>
+                <td>{}<br><small>{}{}</small></td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+            </tr>"#,
+            order.id, order.number, escape_html(&order.customer_name), escape_html(&order.email),
+            if order.customer_id.is_none() { " (guest)" } else { "" },
+            currency.format(order.total), order.status.label(), order.created_at.format("%Y-%m-%d %H:%M")
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Orders</h2>
+    <table>
+        <thead>
+            <tr>
+                <th>Order</th>
+                <th>Customer</th>
+                <th>Total</th>
+                <th>Status</th>
+                <th>Placed</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        rows
+    );
+
+    Ok(Html(admin_page("Orders - Admin", "/admin/orders", &content)))
+}
+
+/// Shows a single order with its items and status controls
+pub async fn admin_view_order(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let orders = store.orders.read()?;
+    let order = match orders.get(&id) {
+        Some(order) => order,
+        None => return Err(AppError::NotFound),
+    };
+    let currency = store.currency_or_base(Some(&order.currency_code));
+
+    let mut rows = String::new();
+    for item in &order.items {
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+            </tr>"#,
+            item.name, item.sku.as_deref().unwrap_or("-"), currency.format(item.unit_price),
+            item.quantity, currency.format(item.total)
+        ));
+    }
+
+    let mut tax_rows = String::new();
+    for tax_line in &order.tax_lines {
+        tax_rows.push_str(&format!(
+            r#"<tr><td colspan="4">{}</td><td>{}</td></tr>"#,
+            tax_line.label(), currency.format(tax_line.amount)
+        ));
+    }
+
+    let mut status_options = String::new();
+    for status in OrderStatus::ALL {
+        let selected = if status == order.status { " selected" } else { "" };
+        status_options.push_str(&format!(r#"<option value="{}"{}>{}</option>"#, status.label(), selected, status.label()));
+    }
+
+    let content = format!(
+        r#"<h2>Order #{}</h2>
+    <p>Placed {} by {} ({})</p>
+    <p><strong>Shipping address:</strong><br>{}<br>Region: {}</p>
+    <form method="post" action="/admin/orders/status/{}" class="create-form">
+        <select name="status">{}</select>
+        <button type="submit" class="btn">Update Status</button>
+    </form>
+    <p>Cancelling an order puts its items back into stock.</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Item</th>
+                <th>SKU</th>
+                <th>Price</th>
+                <th>Quantity</th>
+                <th>Total</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+            <tr><td colspan="4">Subtotal</td><td>{}</td></tr>
+            <tr><td colspan="4">Discount {}</td><td>-{}</td></tr>
+            <tr><td colspan="4">Shipping {}</td><td>{}</td></tr>
+            {}
+            <tr><td colspan="4"><strong>Total</strong></td><td><strong>{}</strong></td></tr>
+        </tbody>
+    </table>"#,
+        order.number, order.created_at.format("%Y-%m-%d %H:%M"), escape_html(&order.customer_name), escape_html(&order.email),
+        escape_html(&order.address).replace('\n', "<br>"), escape_html(&order.region), order.id, status_options, rows,
+        currency.format(order.subtotal), order.coupon_code.as_deref().unwrap_or(""),
+        currency.format(order.discount),
+        order.shipping_method.as_deref().unwrap_or(""), currency.format(order.shipping), tax_rows, currency.format(order.total)
+    );
+
+    Ok(Html(admin_page(&format!("Order #{} - Admin", order.number), "/admin/orders", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct OrderStatusForm {
+    status: String,
+}
+
+/// Changes the status of an order
+pub async fn admin_update_order_status(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<OrderStatusForm>,
+) -> AppResult<Response> {
+    let back = format!("/admin/orders/{}", id);
+    if let Some(status) = OrderStatus::from_form(&form.status) {
+        if let Err(error) = store.set_order_status(&id, status) {
+            return Ok(action_error_page("Status Not Changed", &error, &back, "/admin/orders").into_response());
+        }
+    }
+    Ok(Redirect::to(&back).into_response())
+}
+
+/// Lists the regional tax rates and the tax display setting
+pub async fn admin_list_taxes(State(store): State<Store>) -> AppResult<Html<String>> {
+    let prices_include_tax = *store.prices_include_tax.read()?;
+    let default_region = store.default_region.read()?.clone();
+    let tax_rates = store.tax_rates.read()?;
+    let mut rates_vec: Vec<_> = tax_rates.values().collect();
+    rates_vec.sort_by(|a, b| a.region.cmp(&b.region));
+
+    let mut rows = String::new();
+    let mut region_options = String::new();
+    for rate in rates_vec {
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}%</td>
+                <td>{}%</td>
+                <td>
+                    <form method="post" action="/admin/taxes/delete/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            rate.region, rate.region_name, rate.name, rate.standard_rate, rate.reduced_rate, rate.region
+        ));
+        let selected = if rate.region == default_region { " selected" } else { "" };
+        region_options.push_str(&format!(
+            r#"<option value="{}"{}>{} ({})</option>"#,
+            rate.region, selected, rate.region_name, rate.region
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Taxes</h2>
+    <p>Product prices are entered excluding tax. Exempt products are never taxed.</p>
+    <form method="post" action="/admin/taxes/settings" class="create-form">
+        <label>Show prices
+            <select name="prices_include_tax">
+                <option value="false"{}>Excluding tax</option>
+                <option value="true"{}>Including tax</option>
+            </select>
+        </label>
+        <label>Default region
+            <select name="default_region">{}</select>
+        </label>
+        <button type="submit" class="btn">Save Settings</button>
+    </form>
+    <p>The default region is used for shop prices and carts until the customer picks a region at checkout.
+    Regions like US-CA use the rates of their country when they have none of their own.</p>
+
+    <h2>Tax Rates</h2>
+    <form method="post" action="/admin/taxes/create" class="create-form">
+        <input type="text" name="region" placeholder="Region Code (e.g. DE or US-CA)" required>
+        <input type="text" name="region_name" placeholder="Region Name" required>
+        <input type="text" name="name" placeholder="Tax Name (e.g. VAT)" required>
+        <input type="number" step="0.001" min="0" name="standard_rate" placeholder="Standard Rate (%)" required>
+        <input type="number" step="0.001" min="0" name="reduced_rate" placeholder="Reduced Rate (%)" required>
+        <button type="submit" class="btn">Save Rates</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Region</th>
+                <th>Name</th>
+                <th>Tax</th>
+                <th>Standard</th>
+                <th>Reduced</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        if prices_include_tax { "" } else { " selected" },
+        if prices_include_tax { " selected" } else { "" },
+        region_options, rows
+    );
+
+    Ok(Html(admin_page("Taxes - Admin", "/admin/taxes", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct TaxSettingsForm {
+    prices_include_tax: bool,
+    default_region: String,
+}
+
+/// Saves the tax display setting and default region
+pub async fn admin_update_tax_settings(
+    State(store): State<Store>,
+    Form(form): Form<TaxSettingsForm>,
+) -> AppResult<Redirect> {
+    *store.prices_include_tax.write()? = form.prices_include_tax;
+    if store.tax_rates.read()?.contains_key(&form.default_region) {
+        *store.default_region.write()? = form.default_region;
+    }
+    Ok(Redirect::to("/admin/taxes"))
+}
+
+#[derive(Deserialize)]
+pub struct CreateTaxRateForm {
+    region: String,
+    region_name: String,
+    name: String,
+    standard_rate: f64,
+    reduced_rate: f64,
+}
+
+/// Creates the tax rates of a region, replacing existing ones
+pub async fn admin_create_tax_rate(
+    State(store): State<Store>,
+    Form(form): Form<CreateTaxRateForm>,
+) -> AppResult<Redirect> {
+    if form.standard_rate >= 0.0 && form.reduced_rate >= 0.0 {
+        let rate = TaxRate::new(form.region, form.region_name, form.name, form.standard_rate, form.reduced_rate);
+        if !rate.region.is_empty() {
+            store.tax_rates.write()?.insert(rate.region.clone(), rate);
+        }
+    }
+    Ok(Redirect::to("/admin/taxes"))
+}
+
+/// Deletes the tax rates of a region
+/// The default region can't be deleted
+pub async fn admin_delete_tax_rate(
+    State(store): State<Store>,
+    Path(region): Path<String>,
+) -> AppResult<Redirect> {
+    let default_region = store.default_region.read()?.clone();
+    if region != default_region {
+        store.tax_rates.write()?.remove(&region);
+    }
+    Ok(Redirect::to("/admin/taxes"))
+}
+
+/// Lists the shipping zones with their methods
+pub async fn admin_list_shipping(State(store): State<Store>) -> AppResult<Html<String>> {
+    let base_code = store.base_currency.read()?.clone();
+    let zones = store.shipping_zones.read()?;
+    let mut zones_vec: Vec<_> = zones.values().collect();
+    zones_vec.sort_by_key(|z| z.created_at);
+
+    let mut type_options = String::new();
+    for rate_type in ShippingRateType::ALL {
+        type_options.push_str(&format!(r#"<option value="{}">{}</option>"#, rate_type.label(), rate_type.label()));
+    }
+
+    let mut zones_html = String::new();
+    for zone in zones_vec {
+        let mut rows = String::new();
+        for method in &zone.methods {
+            rows.push_str(&format!(
+                r#"<tr>
+                    <td>{}</td>
+                    <td>{}</td>
+                    <td>{}</td>
+                    <td>
+                        <form method="post" action="/admin/shipping/methods/delete/{}/{}" style="display:inline;">
+                            <button type="submit" class="btn btn-danger">Delete</button>
+                        </form>
+                    </td>
+                </tr>"#,
+                method.name, method.rate_type.label(), method.describe(), zone.id, method.id
+            ));
+        }
+
+        zones_html.push_str(&format!(
+            r#"<h3>{} <small>({})</small></h3>
+    <form method="post" action="/admin/shipping/delete/{}" style="display:inline;">
+        <button type="submit" class="btn btn-danger">Delete Zone</button>
+    </form>
+    <form method="post" action="/admin/shipping/methods/create/{}" class="create-form">
+        <input type="text" name="name" placeholder="Method Name" required>
+        <select name="rate_type" required>{}</select>
+        <input type="number" step="0.01" min="0" name="cost" placeholder="Cost ({})">
+        <input type="number" step="0.01" min="0" name="per_kg" placeholder="Cost per kg">
+        <input type="number" step="0.01" min="0" name="threshold" placeholder="Free over">
+        <button type="submit" class="btn">Add Method</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Method</th>
+                <th>Type</th>
+                <th>Rate</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+            zone.name, zone.regions.join(", "), zone.id, zone.id, type_options, base_code, rows
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Shipping</h2>
+    <p>Regions are country codes like DE, subdivisions like US-CA, or * for everywhere else.
+    Costs are entered in {}.</p>
+    <form method="post" action="/admin/shipping/create" class="create-form">
+        <input type="text" name="name" placeholder="Zone Name" required>
+        <input type="text" name="regions" placeholder="Regions, comma separated (e.g. DE, FR)" required>
+        <button type="submit" class="btn">Create Zone</button>
+    </form>
+    {}"#,
+        base_code, zones_html
+    );
+
+    Ok(Html(admin_page("Shipping - Admin", "/admin/shipping", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct CreateZoneForm {
+    name: String,
+    regions: String,
+}
+
+/// Creates a shipping zone
+pub async fn admin_create_shipping_zone(
+    State(store): State<Store>,
+    Form(form): Form<CreateZoneForm>,
+) -> AppResult<Redirect> {
+    let zone = ShippingZone::new(form.name, &form.regions);
+    if !zone.regions.is_empty() {
+        store.shipping_zones.write()?.insert(zone.id.clone(), zone);
+    }
+    Ok(Redirect::to("/admin/shipping"))
+}
+
+/// Deletes a shipping zone with its methods
+pub async fn admin_delete_shipping_zone(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    store.shipping_zones.write()?.remove(&id);
+    Ok(Redirect::to("/admin/shipping"))
+}
+
+#[derive(Deserialize)]
+pub struct CreateShippingMethodForm {
+    name: String,
+    rate_type: String,
+    cost: Option<String>,
+    per_kg: Option<String>,
+    threshold: Option<String>,
+}
+
+/// Adds a shipping method to a zone
+pub async fn admin_create_shipping_method(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<CreateShippingMethodForm>,
+) -> AppResult<Redirect> {
+    let amount = |value: &Option<String>| {
+        value.as_deref().and_then(|v| v.trim().parse::<f64>().ok()).unwrap_or(0.0).max(0.0)
+    };
+    if let Some(rate_type) = ShippingRateType::from_form(&form.rate_type) {
+        if let Some(zone) = store.shipping_zones.write()?.get_mut(&id) {
+            zone.methods.push(ShippingMethod::new(
+                form.name,
+                rate_type,
+                amount(&form.cost),
+                amount(&form.per_kg),
+                amount(&form.threshold),
+            ));
+        }
+    }
+    Ok(Redirect::to("/admin/shipping"))
+}
+
+/// Removes a shipping method from a zone
+pub async fn admin_delete_shipping_method(
+    State(store): State<Store>,
+    Path((id, method_id)): Path<(String, String)>,
+) -> AppResult<Redirect> {
+    if let Some(zone) = store.shipping_zones.write()?.get_mut(&id) {
+        zone.methods.retain(|m| m.id != method_id);
+    }
+    Ok(Redirect::to("/admin/shipping"))
+}
+
+/// Lists recently queued emails with their delivery status
+pub async fn admin_list_emails(State(store): State<Store>) -> AppResult<Html<String>> {
+    let mut rows = String::new();
+    for record in store.mailer.records() {
+        let badge = match record.status {
+            EmailStatus::Sent => "badge",
+            EmailStatus::Queued | EmailStatus::Failed => "badge badge-warning",
+        };
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td><span class="{}">{}</span></td>
+                <td>{}</td>
+                <td>{}</td>
+            </tr>"#,
+            record.queued_at.format("%Y-%m-%d %H:%M:%S"), record.to, record.subject,
+            badge, record.status.label(), record.attempts, record.last_error.as_deref().unwrap_or("")
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Emails</h2>
+    <p>Emails are sent in the background and retried when delivery fails.
+    Without SMTP settings they are written to the outbox directory instead.</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Queued</th>
+                <th>To</th>
+                <th>Subject</th>
+                <th>Status</th>
+                <th>Attempts</th>
+                <th>Last Error</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        rows
+    );
+
+    Ok(Html(admin_page("Emails - Admin", "/admin/emails", &content)))
+}
+
+/// Files and text fields of a multipart form
+struct UploadForm {
+    /// Uploaded files as (file name, contents), empty file inputs are skipped
+    files: Vec<(String, Vec<u8>)>,
+    fields: HashMap<String, String>,
+}
+
+/// Reads a multipart form into memory
+async fn read_upload_form(mut multipart: Multipart) -> Result<UploadForm, String> {
+    let mut form = UploadForm { files: Vec::new(), fields: HashMap::new() };
+    while let Some(field) = multipart.next_field().await.map_err(|e| e.to_string())? {
+        let name = field.name().unwrap_or_default().to_string();
+        match field.file_name().map(|n| n.to_string()) {
+            Some(file_name) => {
+                let bytes = field.bytes().await.map_err(|e| e.to_string())?;
+                if !bytes.is_empty() {
+                    form.files.push((file_name, bytes.to_vec()));
+                }
+            }
+            None => {
+                let value = field.text().await.map_err(|e| e.to_string())?;
+                form.fields.insert(name, value);
+            }
+        }
+    }
+    Ok(form)
+}
+
+/// Resizes and stores an upload on a blocking thread so it doesn't stall other requests
+async fn upload_image(store: &Store, file_name: String, bytes: Vec<u8>) -> Result<MediaAsset, String> {
+    let store = store.clone();
+    tokio::task::spawn_blocking(move || store.upload_media(&file_name, &bytes))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Picks an image from an upload form with a `file` input and a `media_id` choice
+/// An uploaded file wins over a library image; choosing no image returns None
+async fn chosen_image(store: &Store, form: &mut UploadForm) -> Result<Option<String>, String> {
+    if !form.files.is_empty() {
+        let (file_name, bytes) = form.files.swap_remove(0);
+        return upload_image(store, file_name, bytes).await.map(|asset| Some(asset.url()));
+    }
+    let media_id = form.fields.get("media_id").map(|id| id.trim()).unwrap_or_default();
+    if media_id.is_empty() {
+        return Ok(None);
+    }
+    store
+        .media_assets
+        .read()
+        .unwrap()
+        .get(media_id)
+        .map(|asset| Some(asset.url()))
+        .ok_or_else(|| "The chosen image no longer exists".to_string())
+}
+
+/// Page shown when an upload or other action is rejected
+fn action_error_page(title: &str, error: &str, back: &str, active: &str) -> Html<String> {
+    let content = format!(
+        r#"<h2>{}</h2>
+    <p><span class="badge badge-warning">{}</span></p>
+    <a href="{}" class="btn">Back</a>"#,
+        title, error, back
+    );
+    Html(admin_page(&format!("{} - Admin", title), active, &content))
+}
+
+/// Form fields choosing an image from the media library or uploading a new one
+/// Optional pickers also offer to remove the current image
+fn image_picker(store: &Store, current: Option<&str>, optional: bool) -> String {
+    let mut assets: Vec<_> = store.media_assets.read().unwrap().values().cloned().collect();
+    assets.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+
+    let mut choices = String::new();
+    if optional {
+        choices.push_str(&format!(
+            r#"<label class="media-choice"><input type="radio" name="media_id" value=""{}><span class="media-none">No image</span></label>"#,
+            if current.is_none() { " checked" } else { "" }
+        ));
+    }
+    for asset in &assets {
+        let url = asset.url();
+        choices.push_str(&format!(
+            r#"<label class="media-choice"><input type="radio" name="media_id" value="{}"{}><img src="{}" alt="{}"></label>"#,
+            asset.id,
+            if current == Some(url.as_str()) { " checked" } else { "" },
+            asset.url_for(ImageSize::Thumbnail), asset.original_name
+        ));
+    }
+
+    format!(
+        r#"<div class="media-picker">{}</div>
+        <label>Or upload a new image
+            <input type="file" name="file" accept="image/jpeg,image/png,image/gif,image/webp">
+        </label>"#,
+        choices
+    )
+}
+
+/// Media library with all uploaded images
+pub async fn admin_media_library(State(store): State<Store>) -> AppResult<Html<String>> {
+    let mut assets: Vec<_> = store.media_assets.read()?.values().cloned().collect();
+    assets.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+
+    // Count where each image is used so deleting one isn't a surprise
+    let mut usage: HashMap<String, usize> = HashMap::new();
+    for image in store.products.read()?.values().flat_map(|p| p.images.iter()) {
+        *usage.entry(image.url.clone()).or_default() += 1;
+    }
+    for url in store.posts.read()?.values().filter_map(|p| p.image_url.clone()) {
+        *usage.entry(url).or_default() += 1;
+    }
+    if let Some(url) = store.settings().logo_url {
+        *usage.entry(url).or_default() += 1;
+    }
+
+    let mut cards = String::new();
+    for asset in &assets {
+        let url = asset.url();
+        let used = usage.get(&url).copied().unwrap_or(0);
+        cards.push_str(&format!(
+            r#"<div class="media-card">
+                <a href="{}" target="_blank"><img src="{}" alt="{}"></a>
+                <p class="media-name">{}</p>
+                <p class="media-meta">{} &times; {} &middot; {} &middot; {} &middot; {}</p>
+                <input type="text" value="{}" readonly onclick="this.select()">
+                <p class="media-meta">{}</p>
+                <form method="post" action="/admin/media/delete/{}" onsubmit="return confirm('Delete this image?')">
+                    <button type="submit" class="btn btn-danger btn-sm">Delete</button>
+                </form>
+            </div>"#,
+            url, asset.url_for(ImageSize::Card), asset.original_name, asset.original_name,
+            asset.width, asset.height, asset.content_type, asset.display_size(), asset.created_at.format("%Y-%m-%d"),
+            url,
+            match used {
+                0 => "Not used".to_string(),
+                1 => "Used once".to_string(),
+                n => format!("Used {} times", n),
+            },
+            asset.id
+        ));
+    }
+    if cards.is_empty() {
+        cards.push_str("<p>No images uploaded yet.</p>");
+    }
+
+    let content = format!(
+        r#"<h2>Media Library</h2>
+    <form method="post" action="/admin/media/upload" enctype="multipart/form-data" class="create-form">
+        <input type="file" name="file" accept="image/jpeg,image/png,image/gif,image/webp" multiple required>
+        <button type="submit" class="btn">Upload</button>
+    </form>
+    <p>JPEG, PNG, GIF and WebP images up to {} MB. Uploads are turned upright, stripped of metadata and
+    stored as thumbnail, card and full size WebP files with a JPEG or PNG fallback.
+    Images are chosen on the product and post edit pages; deleting an image removes it from everything using it.</p>
+    <div class="media-grid">
+        {}
+    </div>"#,
+        MAX_UPLOAD_SIZE / 1024 / 1024, cards
+    );
+
+    Ok(Html(admin_page("Media Library - Admin", "/admin/media", &content)))
+}
+
+/// Uploads one or more images to the media library
+pub async fn admin_upload_media(
+    State(store): State<Store>,
+    multipart: Multipart,
+) -> AppResult<Response> {
+    let form = match read_upload_form(multipart).await {
+        Ok(form) => form,
+        Err(error) => return Ok(action_error_page("Upload Failed", &error, "/admin/media", "/admin/media").into_response()),
+    };
+    for (file_name, bytes) in form.files {
+        if let Err(error) = upload_image(&store, file_name, bytes).await {
+            return Ok(action_error_page("Upload Failed", &error, "/admin/media", "/admin/media").into_response());
+        }
+    }
+    Ok(Redirect::to("/admin/media").into_response())
+}
+
+/// Deletes an image from the media library
+pub async fn admin_delete_media(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    store.delete_media(&id);
+    Ok(Redirect::to("/admin/media"))
+}
+
+/// Lists all currencies and their exchange rates
+pub async fn admin_list_currencies(State(store): State<Store>) -> AppResult<Html<String>> {
+    let base_code = store.base_currency.read()?.clone();
+    let currencies = store.currencies.read()?;
+    let mut currencies_vec: Vec<_> = currencies.values().collect();
+    currencies_vec.sort_by(|a, b| a.code.cmp(&b.code));
+
+    let mut rows = String::new();
+    for currency in currencies_vec {
+        let is_base = currency.code == base_code;
+        let actions = if is_base {
+            "<strong>Base currency</strong>".to_string()
+        } else {
+            format!(
+                r#"<form method="post" action="/admin/currencies/rate/{code}" style="display:inline;">
+                        <input type="number" step="0.0001" min="0.0001" name="rate" value="{rate}" required>
+                        <button type="submit" class="btn btn-sm">Update Rate</button>
+                    </form>
+                    <form method="post" action="/admin/currencies/base/{code}" style="display:inline;">
+                        <button type="submit" class="btn btn-sm">Make Base</button>
+                    </form>
+                    <form method="post" action="/admin/currencies/delete/{code}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>"#,
+                code = currency.code,
+                rate = currency.rate
+            )
+        };
+
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>
+                    {}
+                </td>
+            </tr>"#,
+            currency.code, currency.name, currency.rate, currency.locale,
+            currency.format(1234.5), actions
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Currencies</h2>
+    <p>Product prices are entered in {}. Rates are units of each currency per one {}.</p>
+    <form method="post" action="/admin/currencies/create" class="create-form">
+        <input type="text" name="code" placeholder="Code (e.g. EUR)" maxlength="3" required>
+        <input type="text" name="name" placeholder="Name" required>
+        <input type="text" name="symbol" placeholder="Symbol" required>
+        <input type="number" step="0.0001" min="0.0001" name="rate" placeholder="Exchange Rate" required>
+        <select name="locale" required>
+            <option value="en-US">1,234.50 (en-US)</option>
+            <option value="en-GB">1,234.50 (en-GB)</option>
+            <option value="de-DE">1.234,50 (de-DE)</option>
+            <option value="fr-FR">1 234,50 (fr-FR)</option>
+            <option value="de-CH">1'234.50 (de-CH)</option>
+            <option value="ja-JP">1,234 (ja-JP)</option>
+        </select>
+        <input type="number" name="decimals" min="0" max="4" value="2" required>
+        <button type="submit" class="btn">Add Currency</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Code</th>
+                <th>Name</th>
+                <th>Rate</th>
+                <th>Locale</th>
+                <th>Example</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        base_code, base_code, rows
+    );
+
+    Ok(Html(admin_page("Currencies - Admin", "/admin/currencies", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct CreateCurrencyForm {
+    code: String,
+    name: String,
+    symbol: String,
+    rate: f64,
+    locale: String,
+    decimals: usize,
+}
+
+/// Creates a new currency
+pub async fn admin_create_currency(
+    State(store): State<Store>,
+    Form(form): Form<CreateCurrencyForm>,
+) -> AppResult<Redirect> {
+    if form.rate > 0.0 {
+        let currency = Currency::new(form.code, form.name, form.symbol, form.rate, form.locale, form.decimals);
+        store.currencies.write()?.insert(currency.code.clone(), currency);
+    }
+    Ok(Redirect::to("/admin/currencies"))
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRateForm {
+    rate: f64,
+}
+
+/// Updates the exchange rate of a currency
+pub async fn admin_update_currency_rate(
+    State(store): State<Store>,
+    Path(code): Path<String>,
+    Form(form): Form<UpdateRateForm>,
+) -> AppResult<Redirect> {
+    let base_code = store.base_currency.read()?.clone();
+    if form.rate > 0.0 && code != base_code {
+        if let Some(currency) = store.currencies.write()?.get_mut(&code) {
+            currency.rate = form.rate;
+        }
+    }
+    Ok(Redirect::to("/admin/currencies"))
+}
+
+/// Makes a currency the store base currency
+pub async fn admin_set_base_currency(
+    State(store): State<Store>,
+    Path(code): Path<String>,
+) -> AppResult<Redirect> {
+    store.set_base_currency(&code);
+    Ok(Redirect::to("/admin/currencies"))
+}
+
+/// Deletes a currency
+/// The base currency can't be deleted
+pub async fn admin_delete_currency(
+    State(store): State<Store>,
+    Path(code): Path<String>,
+) -> AppResult<Redirect> {
+    let base_code = store.base_currency.read()?.clone();
+    if code != base_code {
+        store.currencies.write()?.remove(&code);
+        for product in store.products.write()?.values_mut() {
+            product.price_overrides.remove(&code);
+        }
+    }
+    Ok(Redirect::to("/admin/currencies"))
+}
+
+/// Lists all blog categories in admin panel
+pub async fn admin_list_blog_categories(State(store): State<Store>) -> AppResult<Html<String>> {
+    let blog_categories = store.blog_categories.read()?;
+    let mut categories_vec: Vec<_> = blog_categories.values().collect();
+    categories_vec.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+
+    let mut rows = String::new();
+    for cat in categories_vec {
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>
+                    <form method="post" action="/admin/blog-categories/delete/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            cat.name, cat.description, cat.created_at.format("%Y-%m-%d"), cat.id
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Blog Categories</h2>
+    <form method="post" action="/admin/blog-categories/create" class="create-form">
+        <input type="text" name="name" placeholder="Category Name" required>
+        <input type="text" name="description" placeholder="Description" required>
+        <button type="submit" class="btn">Create Category</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Name</th>
+                <th>Description</th>
+                <th>Created</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        rows
+    );
+
+    Ok(Html(admin_page("Blog Categories - Admin", "/admin/blog-categories", &content)))
+}
+
+/// Creates a new blog category
+pub async fn admin_create_blog_category(
+    State(store): State<Store>,
+    Form(form): Form<CreateCategoryForm>,
+) -> AppResult<Redirect> {
+    let category = BlogCategory::new(form.name, form.description);
+    store.blog_categories.write()?.insert(category.id.clone(), category);
+    Ok(Redirect::to("/admin/blog-categories"))
+}
+
+/// Deletes a blog category
+pub async fn admin_delete_blog_category(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    store.blog_categories.write()?.remove(&id);
+    Ok(Redirect::to("/admin/blog-categories"))
+}
+
+/// Small avatar of an author for admin tables, using the thumbnail size of uploads
+fn avatar_thumbnail(store: &Store, author: &Author) -> String {
+    match &author.avatar_url {
+        Some(url) => format!(
+            r#"<img class="avatar" src="{}" alt="{}">"#,
+            store.media_by_url(url).map(|a| a.url_for(ImageSize::Thumbnail)).unwrap_or_else(|| url.clone()),
+            author.name
+        ),
+        None => String::new(),
+    }
+}
+
+/// Builds the options of an author select, sorted by name
+fn author_options(authors: &HashMap<String, Author>, selected: &str) -> String {
+    let mut authors: Vec<_> = authors.values().collect();
+    authors.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut options = String::new();
+    for author in authors {
+        let attr = if author.id == selected { " selected" } else { "" };
+        options.push_str(&format!(r#"<option value="{}"{}>{}</option>"#, author.id, attr, author.name));
+    }
+    options
+}
+
+/// Lists author profiles with their number of posts
+pub async fn admin_list_authors(State(store): State<Store>) -> AppResult<Html<String>> {
+    let mut authors: Vec<_> = store.authors.read()?.values().cloned().collect();
+    authors.sort_by(|a, b| a.name.cmp(&b.name));
+    let posts = store.posts.read()?;
+
+    let mut rows = String::new();
+    for author in &authors {
+        let post_count = posts.values().filter(|p| p.author_id == author.id).count();
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td><a href="/blog/author/{}" target="_blank">/blog/author/{}</a></td>
+                <td>{}</td>
+                <td>
+                    <a href="/admin/authors/edit/{}" class="btn btn-sm">Edit</a>
+                    <form method="post" action="/admin/authors/delete/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            avatar_thumbnail(&store, author), author.name, author.slug, author.slug, post_count,
+            author.id, author.id
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Authors</h2>
+    <form method="post" action="/admin/authors/create" class="create-form">
+        <input type="text" name="name" placeholder="Name" required>
+        <textarea name="bio" placeholder="Short bio"></textarea>
+        <button type="submit" class="btn">Create Author</button>
+    </form>
+    <p>Authors are shown in post bylines and get an archive page with their posts.
+    Add an avatar from the edit page.</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Avatar</th>
+                <th>Name</th>
+                <th>Archive</th>
+                <th>Posts</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        rows
+    );
+
+    Ok(Html(admin_page("Authors - Admin", "/admin/authors", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct AuthorForm {
+    name: String,
+    #[serde(default)]
+    slug: String,
+    bio: String,
+}
+
+/// Creates an author profile
+pub async fn admin_create_author(
+    State(store): State<Store>,
+    Form(form): Form<AuthorForm>,
+) -> AppResult<Redirect> {
+    store.create_author(form.name.trim().to_string(), form.bio.trim().to_string());
+    Ok(Redirect::to("/admin/authors"))
+}
+
+/// Edit page for an author profile and avatar
+pub async fn admin_edit_author(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let author = match store.authors.read()?.get(&id) {
+        Some(author) => author.clone(),
+        None => return Err(AppError::NotFound),
+    };
+
+    let content = format!(
+        r#"<h2>Edit Author</h2>
+    <form method="post" action="/admin/authors/edit/{}" class="create-form">
+        <label>Name
+            <input type="text" name="name" value="{}" required>
+        </label>
+        <label>Slug
+            <input type="text" name="slug" value="{}">
+        </label>
+        <textarea name="bio" placeholder="Short bio">{}</textarea>
+        <button type="submit" class="btn">Save Author</button>
+    </form>
+
+    <h2>Avatar</h2>
+    <form method="post" action="/admin/authors/avatar/{}" enctype="multipart/form-data" class="create-form">
+        {}
+        <button type="submit" class="btn">Save Avatar</button>
+    </form>"#,
+        author.id, author.name, author.slug, author.bio,
+        author.id, image_picker(&store, author.avatar_url.as_deref(), true)
+    );
+
+    Ok(Html(admin_page("Edit Author - Admin", "/admin/authors", &content)))
+}
+
+/// Updates the name, slug and bio of an author
+pub async fn admin_update_author(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<AuthorForm>,
+) -> AppResult<Response> {
+    let back = format!("/admin/authors/edit/{}", id);
+    Ok(match store.update_author(&id, form.name.trim().to_string(), &form.slug, form.bio.trim().to_string()) {
+        Ok(()) => Redirect::to("/admin/authors").into_response(),
+        Err(error) => action_error_page("Author Not Saved", &error, &back, "/admin/authors").into_response(),
+    })
+}
+
+/// Sets the avatar of an author from an upload or the media library
+pub async fn admin_set_author_avatar(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    multipart: Multipart,
+) -> AppResult<Response> {
+    let back = format!("/admin/authors/edit/{}", id);
+    let chosen = match read_upload_form(multipart).await {
+        Ok(mut form) => chosen_image(&store, &mut form).await,
+        Err(error) => Err(error),
+    };
+    Ok(match chosen {
+        Ok(avatar_url) => {
+            if let Some(author) = store.authors.write()?.get_mut(&id) {
+                author.avatar_url = avatar_url;
+                author.updated_at = Utc::now();
+            }
+            Redirect::to(&back).into_response()
+        }
+        Err(error) => action_error_page("Upload Failed", &error, &back, "/admin/authors").into_response(),
+    })
+}
+
+/// Deletes an author without posts
+pub async fn admin_delete_author(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Response> {
+    Ok(match store.delete_author(&id) {
+        Ok(()) => Redirect::to("/admin/authors").into_response(),
+        Err(error) => action_error_page("Author Not Deleted", &error, "/admin/authors", "/admin/authors").into_response(),
+    })
+}
+
+/// Comma separated tag field with suggestions from the existing tags
+/// Suggestions complete the name being typed after the last comma
+fn tag_input(store: &Store, tag_ids: &[String]) -> String {
+    let mut names: Vec<_> = store.tags.read().unwrap().values().map(|t| t.name.clone()).collect();
+    names.sort_by_key(|n| n.to_lowercase());
+    let mut options = String::new();
+    for name in &names {
+        options.push_str(&format!(r#"<option value="{}">"#, name));
+    }
+    let current: Vec<_> = store.tags_for(tag_ids).into_iter().map(|t| t.name).collect();
+
+    format!(
+        r#"<label>Tags
+            <input type="text" name="tags" value="{}" list="tag-suggestions" autocomplete="off" placeholder="Comma separated, e.g. Rust, Web">
+        </label>
+        <datalist id="tag-suggestions">{}</datalist>
+        <script>
+        (function () {{
+            var list = document.getElementById('tag-suggestions');
+            var input = document.querySelector('input[list="tag-suggestions"]');
+            var names = Array.from(list.options).map(function (option) {{ return option.value; }});
+            input.addEventListener('input', function () {{
+                var parts = input.value.split(',');
+                var typed = parts.pop().trim().toLowerCase();
+                var chosen = parts.map(function (part) {{ return part.trim().toLowerCase(); }});
+                var prefix = parts.length ? parts.join(',') + ', ' : '';
+                list.innerHTML = '';
+                names.filter(function (name) {{
+                    return name.toLowerCase().indexOf(typed) === 0 && chosen.indexOf(name.toLowerCase()) < 0;
+                }}).forEach(function (name) {{
+                    var option = document.createElement('option');
+                    option.value = prefix + name;
+                    list.appendChild(option);
+                }});
+            }});
+        }})();
+        </script>"#,
+        current.join(", "),
+        options
+    )
+}
+
+/// Options of a parent page select, indented by depth
+/// `exclude` leaves out a page and its subpages, which can't be its parent
+fn page_parent_options(store: &Store, selected: Option<&str>, exclude: Option<&str>) -> String {
+    let mut options = String::from(r#"<option value="">No parent (top level)</option>"#);
+    let mut skip_below: Option<usize> = None;
+    for (depth, page) in store.page_tree() {
+        match skip_below {
+            Some(level) if depth > level => continue,
+            _ => skip_below = None,
+        }
+        if Some(page.id.as_str()) == exclude {
+            skip_below = Some(depth);
+            continue;
+        }
+        let state = if Some(page.id.as_str()) == selected { " selected" } else { "" };
+        options.push_str(&format!(
+            r#"<option value="{}"{}>{}{}</option>"#,
+            page.id, state, "&mdash; ".repeat(depth), page.title
+        ));
+    }
+    options
+}
+
+/// Lists pages as a tree with their URL and status
+pub async fn admin_list_pages(State(store): State<Store>) -> AppResult<Html<String>> {
+    let home_page_id = store.home_page_id.read()?.clone();
+    let tree = store.page_tree();
+
+    let mut rows = String::new();
+    let mut home_options = String::from(r#"<option value="">Built in welcome page</option>"#);
+    for (depth, page) in &tree {
+        let is_home = home_page_id.as_deref() == Some(page.id.as_str());
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}{}{}</td>
+                <td><a href="/{}" target="_blank">/{}</a></td>
+                <td><span class="badge">{}</span></td>
+                <td>{}</td>
+                <td>
+                    <a href="/admin/pages/edit/{}" class="btn btn-sm">Edit</a>
+                    <form method="post" action="/admin/pages/delete/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            "&mdash; ".repeat(*depth), page.title,
+            if is_home { r#" <span class="badge">Homepage</span>"# } else { "" },
+            page.slug, page.slug, page.status.label(), page.updated_at.format("%Y-%m-%d %H:%M"),
+            page.id, page.id
+        ));
+        if page.is_published() {
+            home_options.push_str(&format!(
+                r#"<option value="{}"{}>{}</option>"#,
+                page.id, if is_home { " selected" } else { "" }, page.title
+            ));
+        }
+    }
+    if rows.is_empty() {
+        rows.push_str(r#"<tr><td colspan="5">No pages yet.</td></tr>"#);
+    }
+
+    let content = format!(
+        r#"<h2>Pages</h2>
+    <form method="post" action="/admin/pages/create" class="create-form">
+        <input type="text" name="title" placeholder="Page Title (e.g. About Us)" required>
+        <label>Parent
+            <select name="parent_id">{}</select>
+        </label>
+        <button type="submit" class="btn">Create Page</button>
+    </form>
+    <p>Pages are served at <code>/&lt;slug&gt;</code> once published. Subpages are linked from their parent page.</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Title</th>
+                <th>URL</th>
+                <th>Status</th>
+                <th>Updated</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+
+    <h2>Homepage</h2>
+    <form method="post" action="/admin/pages/homepage" class="create-form">
+        <label>Page shown at /
+            <select name="page_id">{}</select>
+        </label>
+        <button type="submit" class="btn">Save Homepage</button>
+    </form>
+    <p>Only published pages can be the homepage.</p>"#,
+        page_parent_options(&store, None, None), rows, home_options
+    );
+
+    Ok(Html(admin_page("Pages - Admin", "/admin/pages", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct CreatePageForm {
+    title: String,
+    #[serde(default)]
+    parent_id: String,
+}
+
+/// Creates a draft page and opens it in the editor
+pub async fn admin_create_page(
+    State(store): State<Store>,
+    Form(form): Form<CreatePageForm>,
+) -> AppResult<Redirect> {
+    let parent_id = Some(form.parent_id).filter(|id| !id.is_empty());
+    let page = store.create_page(form.title.trim().to_string(), String::new(), parent_id);
+    Ok(Redirect::to(&format!("/admin/pages/edit/{}", page.id)))
+}
+
+/// Edit page for the content, place, status and meta overrides of a page
+pub async fn admin_edit_page(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let page = match store.pages.read()?.get(&id) {
+        Some(page) => page.clone(),
+        None => return Err(AppError::NotFound),
+    };
+
+    let mut status_options = String::new();
+    for status in PageStatus::ALL {
+        let selected = if status == page.status { " selected" } else { "" };
+        status_options.push_str(&format!(r#"<option value="{}"{}>{}</option>"#, status.label(), selected, status.label()));
+    }
+
+    let content = format!(
+        r#"<h2>Edit Page</h2>
+    <form method="post" action="/admin/pages/edit/{}" class="create-form">
+        <label>Title
+            <input type="text" name="title" value="{}" required>
+        </label>
+        <label>Slug
+            <input type="text" name="slug" value="{}">
+        </label>
+        <label>Parent
+            <select name="parent_id">{}</select>
+        </label>
+        <label>Status
+            <select name="status">{}</select>
+        </label>
+        <label>Content (HTML)
+            <textarea name="body" rows="15">{}</textarea>
+        </label>
+        {}
+        <button type="submit" class="btn">Save Page</button>
+    </form>
+    <p>{}</p>"#,
+        page.id, escape_html(&page.title), page.slug,
+        page_parent_options(&store, page.parent_id.as_deref(), Some(&page.id)),
+        status_options, escape_html(&page.body),
+        seo_fields(&page.seo, &format!("{} - {}", page.title, store.site_name()), &summary(&page.body)),
+        if page.is_published() {
+            format!(r#"The page is live at <a href="/{}" target="_blank">/{}</a>."#, page.slug, page.slug)
+        } else {
+            "Publish the page to make it visible on the site.".to_string()
+        }
+    );
+
+    Ok(Html(admin_page("Edit Page - Admin", "/admin/pages", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct PageForm {
+    title: String,
+    #[serde(default)]
+    slug: String,
+    #[serde(default)]
+    parent_id: String,
+    status: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    meta_title: String,
+    #[serde(default)]
+    meta_description: String,
+}
+
+/// Saves a page
+pub async fn admin_update_page(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<PageForm>,
+) -> AppResult<Response> {
+    let back = format!("/admin/pages/edit/{}", id);
+    let parent_id = Some(form.parent_id).filter(|id| !id.is_empty());
+    Ok(match store.update_page(&id, form.title.trim().to_string(), &form.slug, form.body, parent_id) {
+        Ok(()) => {
+            if let Some(page) = store.pages.write()?.get_mut(&id) {
+                page.status = PageStatus::from_form(&form.status).unwrap_or_default();
+                page.seo = SeoMeta::new(&form.meta_title, &form.meta_description);
+            }
+            Redirect::to("/admin/pages").into_response()
+        }
+        Err(error) => action_error_page("Page Not Saved", &error, &back, "/admin/pages").into_response(),
+    })
+}
+
+/// Deletes a page, its subpages move up a level
+pub async fn admin_delete_page(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    store.delete_page(&id);
+    Ok(Redirect::to("/admin/pages"))
+}
+
+#[derive(Deserialize)]
+pub struct HomePageForm {
+    page_id: String,
+}
+
+/// Chooses the page shown at `/`
+pub async fn admin_set_home_page(
+    State(store): State<Store>,
+    Form(form): Form<HomePageForm>,
+) -> AppResult<Response> {
+    Ok(match store.set_home_page(Some(form.page_id).filter(|id| !id.is_empty())) {
+        Ok(()) => Redirect::to("/admin/pages").into_response(),
+        Err(error) => action_error_page("Homepage Not Saved", &error, "/admin/pages", "/admin/pages").into_response(),
+    })
+}
+
+/// Name of the page, category or post a menu link points to, the address for URLs
+fn menu_link_title(store: &Store, link: &MenuLink) -> Option<String> {
+    match link {
+        MenuLink::Page(id) => store.pages.read().unwrap().get(id).map(|p| p.title.clone()),
+        MenuLink::Category(id) => store.categories.read().unwrap().get(id).map(|c| c.name.clone()),
+        MenuLink::Post(id) => store.posts.read().unwrap().get(id).map(|p| p.title.clone()),
+        MenuLink::Url(url) => Some(url.clone()),
+    }
+}
+
+/// Options of the menu target select, grouped by kind of target
+fn menu_target_options(store: &Store, selected: Option<&MenuLink>) -> String {
+    let selected = selected.map(|link| link.form_value()).unwrap_or_default();
+    let option = |value: String, label: &str| {
+        let state = if value == selected { " selected" } else { "" };
+        format!(r#"<option value="{}"{}>{}</option>"#, value, state, escape_html(label))
+    };
+
+    let mut options = option("url".to_string(), "Custom URL");
+    options.push_str(r#"<optgroup label="Pages">"#);
+    for (depth, page) in store.page_tree() {
+        options.push_str(&option(format!("page:{}", page.id), &format!("{}{}", "- ".repeat(depth), page.title)));
+    }
+    options.push_str(r#"</optgroup><optgroup label="Product Categories">"#);
+    let mut categories: Vec<_> = store.categories.read().unwrap().values().cloned().collect();
+    categories.sort_by(|a, b| a.name.cmp(&b.name));
+    for category in categories {
+        options.push_str(&option(format!("category:{}", category.id), &category.name));
+    }
+    options.push_str(r#"</optgroup><optgroup label="Blog Posts">"#);
+    let mut posts: Vec<_> = store.posts.read().unwrap().values().cloned().collect();
+    posts.sort_by_key(|p| std::cmp::Reverse(p.date()));
+    for post in posts {
+        options.push_str(&option(format!("post:{}", post.id), &post.title));
+    }
+    options.push_str("</optgroup>");
+    options
+}
+
+/// Options of the parent item select of a menu
+/// `exclude` leaves out an item and its children, which can't be its parent
+fn menu_parent_options(store: &Store, location: MenuLocation, selected: Option<&str>, exclude: Option<&str>) -> String {
+    let mut options = String::from(r#"<option value="">No parent (top level)</option>"#);
+    let mut skip_below: Option<usize> = None;
+    for (depth, item) in store.menu_tree(location) {
+        match skip_below {
+            Some(level) if depth > level => continue,
+            _ => skip_below = None,
+        }
+        if Some(item.id.as_str()) == exclude {
+            skip_below = Some(depth);
+            continue;
+        }
+        let state = if Some(item.id.as_str()) == selected { " selected" } else { "" };
+        options.push_str(&format!(
+            r#"<option value="{}"{}>{}{}</option>"#,
+            item.id, state, "&mdash; ".repeat(depth), escape_html(&item.label)
+        ));
+    }
+    options
+}
+
+/// Lists the header and footer menus with forms to add items
+pub async fn admin_list_menus(State(store): State<Store>) -> AppResult<Html<String>> {
+    let mut sections = String::new();
+    for location in MenuLocation::ALL {
+        let mut rows = String::new();
+        for (depth, item) in store.menu_tree(location) {
+            let target = match (menu_link_title(&store, &item.link), store.menu_href(&item.link)) {
+                (Some(title), Some(href)) => format!(
+                    r#"{}: <a href="{}" target="_blank">{}</a>"#,
+                    item.link.kind(), escape_html(&href), escape_html(&title)
+                ),
+                (Some(title), None) => format!(
+                    r#"{}: {} <span class="badge badge-warning">Hidden until published</span>"#,
+                    item.link.kind(), escape_html(&title)
+                ),
+                (None, _) => format!(r#"{}: <span class="badge badge-warning">Missing</span>"#, item.link.kind()),
+            };
+            rows.push_str(&format!(
+                r#"<tr>
+                <td>{}{}</td>
+                <td>{}</td>
+                <td>
+                    <form method="post" action="/admin/menus/move/{}/up" style="display:inline;">
+                        <button type="submit" class="btn btn-sm" title="Move up">&uarr;</button>
+                    </form>
+                    <form method="post" action="/admin/menus/move/{}/down" style="display:inline;">
+                        <button type="submit" class="btn btn-sm" title="Move down">&darr;</button>
+                    </form>
+                    <a href="/admin/menus/edit/{}" class="btn btn-sm">Edit</a>
+                    <form method="post" action="/admin/menus/delete/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+                "&mdash; ".repeat(depth), escape_html(&item.label), target,
+                item.id, item.id, item.id, item.id
+            ));
+        }
+        if rows.is_empty() {
+            rows.push_str(r#"<tr><td colspan="3">No items yet.</td></tr>"#);
+        }
+
+        sections.push_str(&format!(
+            r#"<h2>{} Menu</h2>
+    <form method="post" action="/admin/menus/create" class="create-form">
+        <input type="hidden" name="location" value="{}">
+        <input type="text" name="label" placeholder="Label (defaults to the page, category or post name)">
+        <label>Links To
+            <select name="target">{}</select>
+        </label>
+        <input type="text" name="url" placeholder="Custom URL (e.g. /shop or https://example.com)">
+        <label>Parent
+            <select name="parent_id">{}</select>
+        </label>
+        <button type="submit" class="btn">Add Item</button>
+    </form>
+    <table>
+        <thead>
+            <tr>
+                <th>Label</th>
+                <th>Links To</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+    "#,
+            location.label(), location.label(), menu_target_options(&store, None),
+            menu_parent_options(&store, location, None, None), rows
+        ));
+    }
+
+    let content = format!(
+        r#"{}
+    <p>Nested items show as a dropdown in the header and a list in the footer.
+    Links to draft pages stay hidden until the page is published.</p>"#,
+        sections
+    );
+
+    Ok(Html(admin_page("Menus - Admin", "/admin/menus", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct MenuItemForm {
+    #[serde(default)]
+    location: String,
+    #[serde(default)]
+    label: String,
+    target: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    parent_id: String,
+}
+
+impl MenuItemForm {
+    /// Target and label of the item, the label defaults to the name of the target
+    fn link_and_label(&self, store: &Store) -> Result<(MenuLink, String), String> {
+        let link = MenuLink::from_form(&self.target, &self.url).ok_or_else(|| "Enter the URL of the menu item".to_string())?;
+        let label = match self.label.trim() {
+            "" => menu_link_title(store, &link).ok_or_else(|| "The linked item doesn't exist".to_string())?,
+            label => label.to_string(),
+        };
+        Ok((link, label))
+    }
+
+    fn parent_id(&self) -> Option<String> {
+        Some(self.parent_id.clone()).filter(|id| !id.is_empty())
+    }
+}
+
+/// Adds an item to the end of a menu or parent item
+pub async fn admin_create_menu_item(
+    State(store): State<Store>,
+    Form(form): Form<MenuItemForm>,
+) -> AppResult<Response> {
+    let location = MenuLocation::from_form(&form.location).unwrap_or_default();
+    let result = form
+        .link_and_label(&store)
+        .and_then(|(link, label)| store.add_menu_item(MenuItem::new(location, label, link, form.parent_id())));
+    Ok(match result {
+        Ok(()) => Redirect::to("/admin/menus").into_response(),
+        Err(error) => action_error_page("Menu Item Not Added", &error, "/admin/menus", "/admin/menus").into_response(),
+    })
+}
+
+/// Edit page for the label, target and parent of a menu item
+pub async fn admin_edit_menu_item(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let item = match store.menu_items.read()?.get(&id) {
+        Some(item) => item.clone(),
+        None => return Err(AppError::NotFound),
+    };
+    let url = match &item.link {
+        MenuLink::Url(url) => url.as_str(),
+        _ => "",
+    };
+
+    let content = format!(
+        r#"<h2>Edit {} Menu Item</h2>
+    <form method="post" action="/admin/menus/edit/{}" class="create-form">
+        <label>Label
+            <input type="text" name="label" value="{}" required>
+        </label>
+        <label>Links To
+            <select name="target">{}</select>
+        </label>
+        <label>Custom URL
+            <input type="text" name="url" value="{}" placeholder="/shop or https://example.com">
+        </label>
+        <label>Parent
+            <select name="parent_id">{}</select>
+        </label>
+        <button type="submit" class="btn">Save Item</button>
+    </form>"#,
+        item.location.label(), item.id, escape_html(&item.label),
+        menu_target_options(&store, Some(&item.link)), escape_html(url),
+        menu_parent_options(&store, item.location, item.parent_id.as_deref(), Some(&item.id))
+    );
+
+    Ok(Html(admin_page("Edit Menu Item - Admin", "/admin/menus", &content)))
+}
+
+/// Saves a menu item
+pub async fn admin_update_menu_item(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<MenuItemForm>,
+) -> AppResult<Response> {
+    let back = format!("/admin/menus/edit/{}", id);
+    let result = form
+        .link_and_label(&store)
+        .and_then(|(link, label)| store.update_menu_item(&id, label, link, form.parent_id()));
+    Ok(match result {
+        Ok(()) => Redirect::to("/admin/menus").into_response(),
+        Err(error) => action_error_page("Menu Item Not Saved", &error, &back, "/admin/menus").into_response(),
+    })
+}
+
+/// Moves a menu item before the previous or after the next item, `direction` is `up` or `down`
+pub async fn admin_move_menu_item(
+    State(store): State<Store>,
+    Path((id, direction)): Path<(String, String)>,
+) -> AppResult<Redirect> {
+    store.move_menu_item(&id, direction == "up");
+    Ok(Redirect::to("/admin/menus"))
+}
+
+/// Deletes a menu item, its children move up a level
+pub async fn admin_delete_menu_item(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    store.delete_menu_item(&id);
+    Ok(Redirect::to("/admin/menus"))
+}
+
+/// Lists tags with the number of posts and products using them
+pub async fn admin_list_tags(State(store): State<Store>) -> AppResult<Html<String>> {
+    let mut tags: Vec<_> = store.tags.read()?.values().cloned().collect();
+    tags.sort_by_key(|t| t.name.to_lowercase());
+    let posts = store.posts.read()?;
+    let products = store.products.read()?;
+
+    let mut rows = String::new();
+    for tag in &tags {
+        let post_count = posts.values().filter(|p| p.tag_ids.contains(&tag.id)).count();
+        let product_count = products.values().filter(|p| p.tag_ids.contains(&tag.id)).count();
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>
+                    <form method="post" action="/admin/tags/edit/{}" style="display:inline;">
+                        <input type="text" name="name" value="{}" required>
+                        <input type="text" name="slug" value="{}">
+                        <button type="submit" class="btn btn-sm">Save</button>
+                    </form>
+                </td>
+                <td><a href="/blog/tag/{}" target="_blank">{}</a></td>
+                <td><a href="/shop/tag/{}" target="_blank">{}</a></td>
+                <td>
+                    <form method="post" action="/admin/tags/delete/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            tag.id, tag.name, tag.slug,
+            tag.slug, post_count, tag.slug, product_count,
+            tag.id
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Tags</h2>
+    <form method="post" action="/admin/tags/create" class="create-form">
+        <input type="text" name="names" placeholder="Tag names, comma separated" required>
+        <button type="submit" class="btn">Create Tags</button>
+    </form>
+    <p>Tags are shared by posts and products and are also created when typed into their forms.
+    Deleting a tag removes it from everything tagged with it.</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Name / Slug</th>
+                <th>Posts</th>
+                <th>Products</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        rows
+    );
+
+    Ok(Html(admin_page("Tags - Admin", "/admin/tags", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct CreateTagsForm {
+    names: String,
+}
+
+/// Creates tags from a comma separated list, existing names are kept
+pub async fn admin_create_tags(
+    State(store): State<Store>,
+    Form(form): Form<CreateTagsForm>,
+) -> AppResult<Redirect> {
+    store.resolve_tags(&form.names);
+    Ok(Redirect::to("/admin/tags"))
+}
+
+#[derive(Deserialize)]
+pub struct TagForm {
+    name: String,
+    #[serde(default)]
+    slug: String,
+}
+
+/// Renames a tag or changes its slug
+pub async fn admin_update_tag(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<TagForm>,
+) -> AppResult<Response> {
+    Ok(match store.update_tag(&id, form.name, &form.slug) {
+        Ok(()) => Redirect::to("/admin/tags").into_response(),
+        Err(error) => action_error_page("Tag Not Saved", &error, "/admin/tags", "/admin/tags").into_response(),
+    })
+}
+
+/// Deletes a tag
+pub async fn admin_delete_tag(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    store.delete_tag(&id);
+    Ok(Redirect::to("/admin/tags"))
+}
+
+/// Lists all blog posts in admin panel
+pub async fn admin_list_posts(
+    State(store): State<Store>,
+    Query(query): Query<PostListQuery>,
+) -> AppResult<Html<String>> {
+    let status_filter = query.status.as_deref().and_then(PostStatus::from_form);
+    let feed_full_content = *store.feed_full_content.read()?;
+    let settings = store.settings();
+    let posts = store.posts.read()?;
+    let categories = store.blog_categories.read()?;
+    let authors = store.authors.read()?;
+    let mut posts_vec: Vec<_> = posts
+        .values()
+        .filter(|p| status_filter.is_none_or(|status| p.status == status))
+        .collect();
+    posts_vec.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+
+    let mut rows = String::new();
+    for post in posts_vec {
+        let category_name = categories
+            .get(&post.category_id)
+            .map(|c| c.name.as_str())
+            .unwrap_or("Unknown");
+        let author_name = authors.get(&post.author_id).map(|a| a.name.as_str()).unwrap_or("Unknown");
+        let reviewer_name = post
+            .reviewer_id
+            .as_ref()
+            .and_then(|id| authors.get(id))
+            .map(|a| a.name.as_str())
+            .unwrap_or("-");
+
+        let status = match post.publish_at {
+            Some(at) if post.is_scheduled() => {
+                format!(
+                    "{}<br><small>Goes live {} {}</small>",
+                    post.status_label(), settings.local_time(at).format("%Y-%m-%d %H:%M"), settings.timezone
+                )
+            }
+            _ => post.status_label().to_string(),
+        };
+
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>
+                    <a href="/admin/posts/edit/{}" class="btn btn-sm">Edit</a>
+                    {}
+                    <form method="post" action="/admin/posts/delete/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            post.title, category_name, author_name, reviewer_name, status, post.created_at.format("%Y-%m-%d"),
+            post.id, status_buttons(post, "/admin/posts"),
+            post.id
+        ));
+    }
+
+    let mut filters = format!(
+        r#"<a href="/admin/posts" class="btn btn-sm{}">All</a>"#,
+        if status_filter.is_none() { " btn-active" } else { "" }
+    );
+    for status in PostStatus::ALL {
+        let count = posts.values().filter(|p| p.status == status).count();
+        filters.push_str(&format!(
+            r#" <a href="/admin/posts?status={}" class="btn btn-sm{}">{} ({})</a>"#,
+            status.label().replace(' ', "+"),
+            if status_filter == Some(status) { " btn-active" } else { "" },
+            status.label(),
+            count
+        ));
+    }
+
+    let mut category_options = String::new();
+    for cat in categories.values() {
+        category_options.push_str(&format!(r#"<option value="{}">{}</option>"#, cat.id, cat.name));
+    }
+
+    let content = format!(
+        r#"<h2>Blog Posts</h2>
+    <form method="post" action="/admin/posts/create" class="create-form">
+        <input type="text" name="title" placeholder="Post Title" required>
+        <textarea name="excerpt" placeholder="Excerpt" required></textarea>
+        <textarea name="content" placeholder="Content" required rows="5"></textarea>
+        <select name="author_id" required>
+            <option value="">Select Author</option>
+            {}
+        </select>
+        <select name="category_id" required>
+            <option value="">Select Category</option>
+            {}
+        </select>
+        {}
+        <label>Publish At ({}, optional)
+            <input type="datetime-local" name="publish_at">
+        </label>
+        <button type="submit" class="btn">Create Post</button>
+    </form>
+    <p class="post-filters">{}</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Title</th>
+                <th>Category</th>
+                <th>Author</th>
+                <th>Reviewer</th>
+                <th>Status</th>
+                <th>Created</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+
+    <h2>Feeds</h2>
+    <form method="post" action="/admin/posts/feed-settings" class="create-form">
+        <label>Feed content
+            <select name="feed_full_content">
+                <option value="false"{}>Excerpt</option>
+                <option value="true"{}>Full post</option>
+            </select>
+        </label>
+        <button type="submit" class="btn">Save Settings</button>
+    </form>
+    <p>The blog is published as <a href="/blog/feed.rss" target="_blank">RSS</a> and
+    <a href="/blog/feed.atom" target="_blank">Atom</a>. Every category and tag has its own feed under
+    <code>/blog/category/&lt;id&gt;/feed.rss</code> and <code>/blog/tag/&lt;slug&gt;/feed.rss</code>.</p>"#,
+        author_options(&authors, ""), category_options, tag_input(&store, &[]), settings.timezone, filters, rows,
+        if feed_full_content { "" } else { " selected" },
+        if feed_full_content { " selected" } else { "" }
+    );
+
+    Ok(Html(admin_page("Blog Posts - Admin", "/admin/posts", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct PostListQuery {
+    /// Only lists posts in this workflow state
+    status: Option<String>,
+}
+
+/// Buttons moving a post to the next workflow states
+/// `back` is where the admin returns after the change
+fn status_buttons(post: &Post, back: &str) -> String {
+    let mut buttons = String::new();
+    for (status, label) in post.status.transitions() {
+        buttons.push_str(&format!(
+            r#"<form method="post" action="/admin/posts/status/{}" style="display:inline;">
+                        <input type="hidden" name="status" value="{}">
+                        <input type="hidden" name="back" value="{}">
+                        <button type="submit" class="btn btn-sm">{}</button>
+                    </form>"#,
+            post.id, status.label(), back, label
+        ));
+    }
+    buttons
+}
+
+#[derive(Deserialize)]
+pub struct CreatePostForm {
+    title: String,
+    content: String,
+    excerpt: String,
+    category_id: String,
+    author_id: String,
+    /// Comma separated tag names
+    #[serde(default)]
+    tags: String,
+    /// Schedules the new post, only read when creating
+    #[serde(default)]
+    publish_at: String,
+}
+
+/// Creates a new blog post
+pub async fn admin_create_post(
+    State(store): State<Store>,
+    Form(form): Form<CreatePostForm>,
+) -> AppResult<Redirect> {
+    let mut post = Post::new(form.title, form.content, form.excerpt, form.category_id, form.author_id);
+    post.tag_ids = store.resolve_tags(&form.tags);
+    if let Some(publish_at) = parse_datetime_local(&form.publish_at, &store.settings()) {
+        post.schedule(publish_at);
+    }
+    store.add_post(post);
+    Ok(Redirect::to("/admin/posts"))
+}
+
+/// Edit page for a blog post and its image
+pub async fn admin_edit_post(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let post = match store.posts.read()?.get(&id) {
+        Some(post) => post.clone(),
+        None => return Err(AppError::NotFound),
+    };
+    let settings = store.settings();
+
+    let mut category_options = String::new();
+    for cat in store.blog_categories.read()?.values() {
+        let selected = if cat.id == post.category_id { " selected" } else { "" };
+        category_options.push_str(&format!(r#"<option value="{}"{}>{}</option>"#, cat.id, selected, cat.name));
+    }
+    let authors = store.authors.read()?.clone();
+
+    // Unsaved edits from the autosave are loaded into the editor until they are saved or discarded
+    let (title, excerpt, body, autosave_notice) = match &post.autosave {
+        Some(autosave) => (
+            autosave.title.as_str(),
+            autosave.excerpt.as_str(),
+            autosave.content.as_str(),
+            format!(
+                r#"<div class="autosave-notice">
+        <span class="badge badge-warning">Unsaved</span>
+        The editor shows changes autosaved on {} {}.
+        <form method="post" action="/admin/posts/autosave/discard/{}" style="display:inline;">
+            <button type="submit" class="btn btn-sm">Discard Autosave</button>
+        </form>
+    </div>"#,
+                settings.local_time(autosave.saved_at).format("%Y-%m-%d %H:%M:%S"),
+                settings.timezone,
+                post.id
+            ),
+        ),
+        None => (post.title.as_str(), post.excerpt.as_str(), post.content.as_str(), String::new()),
+    };
+
+    let mut comments = String::new();
+    for comment in &post.review_comments {
+        let name = authors.get(&comment.author_id).map(|a| a.name.as_str()).unwrap_or("Unknown");
+        comments.push_str(&format!(
+            r#"<div class="review-comment">
+            <strong>{}</strong> <small>{} {}</small>
+            <p>{}</p>
+        </div>"#,
+            name,
+            settings.local_time(comment.created_at).format("%Y-%m-%d %H:%M"),
+            settings.timezone,
+            escape_html(&comment.body).replace('\n', "<br>")
+        ));
+    }
+    if comments.is_empty() {
+        comments.push_str("<p>No review comments yet.</p>");
+    }
+
+    let content = format!(
+        r#"<h2>Edit Post</h2>
+    {}
+    <form method="post" action="/admin/posts/edit/{}" class="create-form" id="post-form" data-autosave="/admin/posts/autosave/{}">
+        <input type="text" name="title" value="{}" placeholder="Post Title" required>
+        <textarea name="excerpt" placeholder="Excerpt" required>{}</textarea>
+        <textarea name="content" placeholder="Content" required rows="10">{}</textarea>
+        <label>Author
+            <select name="author_id" required>{}</select>
+        </label>
+        <label>Category
+            <select name="category_id" required>{}</select>
+        </label>
+        {}
+        {}
+        <label>Saved By
+            <select name="editor_id" required>{}</select>
+        </label>
+        <button type="submit" class="btn">Save Post</button>
+        <small id="autosave-status">Changes are autosaved while you type.</small>
+    </form>
+    <p>Every save is kept as a revision. <a href="/admin/posts/revisions/{}">Revision history ({})</a></p>
+
+    <h2>Workflow</h2>
+    <p>Status: <span class="badge">{}</span> {}</p>
+    <form method="post" action="/admin/posts/reviewer/{}" class="create-form">
+        <label>Reviewer
+            <select name="reviewer_id">
+                <option value="">No reviewer</option>
+                {}
+            </select>
+        </label>
+        <button type="submit" class="btn">Assign Reviewer</button>
+    </form>
+
+    <h2>Review Comments</h2>
+    {}
+    <form method="post" action="/admin/posts/comments/create/{}" class="create-form">
+        <textarea name="body" placeholder="Comment for the author or reviewer" required></textarea>
+        <label>Comment As
+            <select name="author_id" required>{}</select>
+        </label>
+        <button type="submit" class="btn">Add Comment</button>
+    </form>
+
+    <h2>Featured Image</h2>
+    <form method="post" action="/admin/posts/image/{}" enctype="multipart/form-data" class="create-form">
+        {}
+        <button type="submit" class="btn">Save Featured Image</button>
+    </form>
+
+    <h2>Reader Comments</h2>
+    <form method="post" action="/admin/posts/comments-enabled/{}" class="create-form">
+        <p>Comments are {} for this post. <a href="/admin/comments?post={}">Moderate comments ({})</a></p>
+        <button type="submit" class="btn">{}</button>
+    </form>
+
+    <h2>Schedule</h2>
+    <form method="post" action="/admin/posts/schedule/{}" class="create-form">
+        <label>Publish At ({})
+            <input type="datetime-local" name="publish_at" value="{}">
+        </label>
+        <button type="submit" class="btn">Save Schedule</button>
+    </form>
+    <p>{} Leave the time empty to cancel a scheduled publication.</p>
+    <script>
+    (function () {{
+        var form = document.getElementById('post-form');
+        var status = document.getElementById('autosave-status');
+        var timer = null;
+        form.addEventListener('input', function () {{
+            clearTimeout(timer);
+            status.textContent = 'Unsaved changes';
+            timer = setTimeout(function () {{
+                fetch(form.dataset.autosave, {{ method: 'POST', body: new URLSearchParams(new FormData(form)) }})
+                    .then(function (response) {{ return response.ok ? response.text() : Promise.reject(); }})
+                    .then(function (text) {{ status.textContent = text; }})
+                    .catch(function () {{ status.textContent = 'Autosave failed'; }});
+            }}, 2000);
+        }});
+    }})();
+    </script>"#,
+        autosave_notice,
+        post.id, post.id, escape_html(title), escape_html(excerpt), escape_html(body),
+        author_options(&authors, &post.author_id), category_options, tag_input(&store, &post.tag_ids),
+        seo_fields(&post.seo, &format!("{} - {} Blog", post.title, store.site_name()), &post.excerpt),
+        author_options(&authors, &post.author_id),
+        post.id, store.post_revisions(&post.id).len(),
+        post.status.label(), status_buttons(&post, &format!("/admin/posts/edit/{}", post.id)),
+        post.id, author_options(&authors, post.reviewer_id.as_deref().unwrap_or("")),
+        comments, post.id, author_options(&authors, post.reviewer_id.as_deref().unwrap_or(&post.author_id)),
+        post.id, image_picker(&store, post.image_url.as_deref(), true),
+        post.id, if post.comments_enabled { "open" } else { "closed" }, post.id,
+        store.comments.read()?.values().filter(|c| c.post_id == post.id).count(),
+        if post.comments_enabled { "Close Comments" } else { "Open Comments" },
+        post.id, settings.timezone, format_datetime_local(post.publish_at, &settings),
+        match post.publish_at {
+            Some(at) if post.is_scheduled() && post.status == PostStatus::Approved => format!(
+                "The post goes live on {} {}.",
+                settings.local_time(at).format("%Y-%m-%d %H:%M"), settings.timezone
+            ),
+            Some(at) if post.is_scheduled() => format!(
+                "The post goes live on {} {} once it is approved.",
+                settings.local_time(at).format("%Y-%m-%d %H:%M"), settings.timezone
+            ),
+            _ if post.is_published() => "The post is published; scheduling it hides it until then.".to_string(),
+            _ => format!("The post is {}.", post.status.label().to_lowercase()),
+        }
+    );
+
+    Ok(Html(admin_page("Edit Post - Admin", "/admin/posts", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct UpdatePostForm {
+    title: String,
+    content: String,
+    excerpt: String,
+    category_id: String,
+    author_id: String,
+    /// Comma separated tag names
+    #[serde(default)]
+    tags: String,
+    #[serde(default)]
+    meta_title: String,
+    #[serde(default)]
+    meta_description: String,
+    /// Author profile of the person saving, recorded on the revision
+    editor_id: String,
+}
+
+/// Updates the text, author, category and meta overrides of a blog post and records a revision
+pub async fn admin_update_post(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<UpdatePostForm>,
+) -> AppResult<Redirect> {
+    let tag_ids = store.resolve_tags(&form.tags);
+    if let Some(post) = store.posts.write()?.get_mut(&id) {
+        post.author_id = form.author_id;
+        post.category_id = form.category_id;
+        post.tag_ids = tag_ids;
+        post.seo = SeoMeta::new(&form.meta_title, &form.meta_description);
+    }
+    // Fails only for deleted posts, which have nothing left to save
+    let _ = store.update_post(&id, form.title, form.content, form.excerpt, form.editor_id);
+    Ok(Redirect::to("/admin/posts"))
+}
+
+/// Sets the image of a blog post from an upload or the media library
+pub async fn admin_set_post_image(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    multipart: Multipart,
+) -> AppResult<Response> {
+    let back = format!("/admin/posts/edit/{}", id);
+    let chosen = match read_upload_form(multipart).await {
+        Ok(mut form) => chosen_image(&store, &mut form).await,
+        Err(error) => Err(error),
+    };
+    Ok(match chosen {
+        Ok(image_url) => {
+            if let Some(post) = store.posts.write()?.get_mut(&id) {
+                post.image_url = image_url;
+            }
+            Redirect::to(&back).into_response()
+        }
+        Err(error) => action_error_page("Upload Failed", &error, &back, "/admin/posts").into_response(),
+    })
+}
+
+/// Renders a word diff of two texts as (old, new) HTML with removed and added words marked
+fn diff_columns(old: &str, new: &str) -> (String, String) {
+    // Consecutive words with the same change are grouped into one marked run
+    let mut runs: Vec<(ChangeTag, String)> = Vec::new();
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        match runs.last_mut() {
+            Some((tag, text)) if *tag == change.tag() => text.push_str(change.value()),
+            _ => runs.push((change.tag(), change.value().to_string())),
+        }
+    }
+
+    let mut left = String::new();
+    let mut right = String::new();
+    for (tag, text) in runs {
+        let text = escape_html(&text);
+        match tag {
+            ChangeTag::Equal => {
+                left.push_str(&text);
+                right.push_str(&text);
+            }
+            ChangeTag::Delete => left.push_str(&format!("<del>{}</del>", text)),
+            ChangeTag::Insert => right.push_str(&format!("<ins>{}</ins>", text)),
+        }
+    }
+    (left, right)
+}
+
+/// Revision history of a post
+pub async fn admin_post_revisions(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let post = match store.posts.read()?.get(&id) {
+        Some(post) => post.clone(),
+        None => return Err(AppError::NotFound),
+    };
+    let revisions = store.post_revisions(&id);
+    let authors = store.authors.read()?;
+    let author_name = |author_id: &str| authors.get(author_id).map(|a| a.name.clone()).unwrap_or_else(|| "Unknown".to_string());
+
+    let mut rows = String::new();
+    for (index, revision) in revisions.iter().enumerate() {
+        let note = match &revision.restored_from {
+            Some(restored_id) => revisions
+                .iter()
+                .find(|r| &r.id == restored_id)
+                .map(|r| format!("Restored from {}", r.created_at.format("%Y-%m-%d %H:%M:%S")))
+                .unwrap_or_else(|| "Restored".to_string()),
+            None if index + 1 == revisions.len() => "Created".to_string(),
+            None => "Edited".to_string(),
+        };
+        let actions = if index == 0 {
+            format!(
+                r#"<span class="badge">Current</span>
+                    <a href="/admin/posts/revisions/{}/{}" class="btn btn-sm">Changes</a>"#,
+                post.id, revision.id
+            )
+        } else {
+            format!(
+                r#"<a href="/admin/posts/revisions/{}/{}" class="btn btn-sm">Changes</a>
+                    <a href="/admin/posts/revisions/{}/{}?against=current" class="btn btn-sm">Compare with Current</a>
+                    <form method="post" action="/admin/posts/revisions/restore/{}/{}" style="display:inline;">
+                        <button type="submit" class="btn btn-sm">Restore</button>
+                    </form>"#,
+                post.id, revision.id, post.id, revision.id, post.id, revision.id
+            )
+        };
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+            </tr>"#,
+            revision.created_at.format("%Y-%m-%d %H:%M:%S"), author_name(&revision.author_id),
+            revision.title, note, actions
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Revisions of "{}"</h2>
+    <p>Every save is kept. Restoring a revision saves its text as a new revision.
+    <a href="/admin/posts/edit/{}">Back to the post</a></p>
+    <table>
+        <thead>
+            <tr>
+                <th>Saved</th>
+                <th>Saved By</th>
+                <th>Title</th>
+                <th>Note</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        post.title, post.id, rows
+    );
+
+    Ok(Html(admin_page("Revisions - Admin", "/admin/posts", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct RevisionDiffQuery {
+    /// `current` compares with the latest revision instead of the previous one
+    against: Option<String>,
+}
+
+/// Side by side diff of a revision against the previous or the current revision
+pub async fn admin_post_revision_diff(
+    State(store): State<Store>,
+    Path((id, revision_id)): Path<(String, String)>,
+    Query(query): Query<RevisionDiffQuery>,
+) -> AppResult<Html<String>> {
+    let revisions = store.post_revisions(&id);
+    let index = match revisions.iter().position(|r| r.id == revision_id) {
+        Some(index) => index,
+        None => return Err(AppError::NotFound),
+    };
+    let revision = &revisions[index];
+
+    // Revisions are newest first, so the previous one follows and the current one leads
+    let (old, new, heading) = if query.against.as_deref() == Some("current") {
+        (revision, &revisions[0], "this revision and the current one")
+    } else {
+        match revisions.get(index + 1) {
+            Some(previous) => (previous, revision, "the previous revision and this one"),
+            None => (revision, revision, "the first revision"),
+        }
+    };
+
+    let mut rows = String::new();
+    for (field, old_text, new_text) in [
+        ("Title", &old.title, &new.title),
+        ("Excerpt", &old.excerpt, &new.excerpt),
+        ("Content", &old.content, &new.content),
+    ] {
+        let (left, right) = diff_columns(old_text, new_text);
+        rows.push_str(&format!(
+            r#"<tr>
+                <th>{}</th>
+                <td class="diff">{}</td>
+                <td class="diff">{}</td>
+            </tr>"#,
+            field, left, right
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Changes between {}</h2>
+    <p><a href="/admin/posts/revisions/{}">Back to the revisions</a></p>
+    <table class="diff-table">
+        <thead>
+            <tr>
+                <th></th>
+                <th>{}</th>
+                <th>{}</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+        heading, id,
+        old.created_at.format("%Y-%m-%d %H:%M:%S"), new.created_at.format("%Y-%m-%d %H:%M:%S"),
+        rows
+    );
+
+    Ok(Html(admin_page("Revision Changes - Admin", "/admin/posts", &content)))
+}
+
+/// Restores the text of an earlier revision
+/// The restore is recorded as saved by the post author
+pub async fn admin_restore_post_revision(
+    State(store): State<Store>,
+    Path((id, revision_id)): Path<(String, String)>,
+) -> AppResult<Response> {
+    let author_id = store.posts.read()?.get(&id).map(|p| p.author_id.clone()).unwrap_or_default();
+    Ok(match store.restore_revision(&id, &revision_id, author_id) {
+        Ok(()) => Redirect::to(&format!("/admin/posts/revisions/{}", id)).into_response(),
+        Err(error) => action_error_page(
+            "Revision Not Restored",
+            &error,
+            &format!("/admin/posts/revisions/{}", id),
+            "/admin/posts",
+        )
+        .into_response(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct SchedulePostForm {
+    publish_at: String,
+}
+
+/// Schedules a post for publication, or cancels the schedule when the time is empty
+/// Times in the past publish approved posts right away
+pub async fn admin_schedule_post(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<SchedulePostForm>,
+) -> AppResult<Redirect> {
+    let settings = store.settings();
+    if let Some(post) = store.posts.write()?.get_mut(&id) {
+        match parse_datetime_local(&form.publish_at, &settings) {
+            Some(publish_at) if publish_at <= Utc::now() && post.status == PostStatus::Approved => post.publish(),
+            Some(publish_at) => post.schedule(publish_at),
+            None if post.publish_at.is_some() => post.cancel_schedule(),
+            None => {}
+        }
+    }
+    Ok(Redirect::to(&format!("/admin/posts/edit/{}", id)))
+}
+
+#[derive(Deserialize)]
+pub struct PostStatusForm {
+    status: String,
+    /// Page to return to, the post list or the edit page
+    back: String,
+}
+
+/// Moves a post to another workflow state
+pub async fn admin_update_post_status(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<PostStatusForm>,
+) -> AppResult<Response> {
+    let back = if form.back.starts_with("/admin/posts") { form.back } else { "/admin/posts".to_string() };
+    let result = match PostStatus::from_form(&form.status) {
+        Some(status) => store.set_post_status(&id, status),
+        None => Err(format!("Unknown status {}", form.status)),
+    };
+    Ok(match result {
+        Ok(()) => Redirect::to(&back).into_response(),
+        Err(error) => action_error_page("Status Not Changed", &error, &back, "/admin/posts").into_response(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct PostReviewerForm {
+    reviewer_id: String,
+}
+
+/// Assigns the reviewer of a post, an empty value removes it
+pub async fn admin_assign_post_reviewer(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<PostReviewerForm>,
+) -> AppResult<Redirect> {
+    let known = store.authors.read()?.contains_key(&form.reviewer_id);
+    let reviewer_id = Some(form.reviewer_id).filter(|_| known);
+    if let Some(post) = store.posts.write()?.get_mut(&id) {
+        post.reviewer_id = reviewer_id;
+    }
+    Ok(Redirect::to(&format!("/admin/posts/edit/{}", id)))
+}
+
+#[derive(Deserialize)]
+pub struct ReviewCommentForm {
+    body: String,
+    author_id: String,
+}
+
+/// Adds a review comment to a post
+pub async fn admin_create_review_comment(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<ReviewCommentForm>,
+) -> AppResult<Redirect> {
+    let body = form.body.trim();
+    if !body.is_empty() {
+        if let Some(post) = store.posts.write()?.get_mut(&id) {
+            post.review_comments.push(ReviewComment::new(form.author_id, body.to_string()));
+        }
+    }
+    Ok(Redirect::to(&format!("/admin/posts/edit/{}", id)))
+}
+
+#[derive(Deserialize)]
+pub struct AutosavePostForm {
+    title: String,
+    content: String,
+    excerpt: String,
+}
+
+/// Keeps unsaved editor changes, called by the edit page while typing
+/// Answers with a short status line for the editor
+pub async fn admin_autosave_post(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<AutosavePostForm>,
+) -> AppResult<String> {
+    let mut posts = store.posts.write()?;
+    let post = posts.get_mut(&id).ok_or(AppError::NotFound)?;
+    post.autosave(form.title, form.content, form.excerpt);
+    let settings = store.settings();
+    Ok(format!("Autosaved at {} {}", settings.local_time(Utc::now()).format("%H:%M:%S"), settings.timezone))
+}
+
+/// Drops the autosaved changes of a post
+pub async fn admin_discard_post_autosave(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    if let Some(post) = store.posts.write()?.get_mut(&id) {
+        post.autosave = None;
+    }
+    Ok(Redirect::to(&format!("/admin/posts/edit/{}", id)))
+}
+
+/// Deletes a blog post
+pub async fn admin_delete_post(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    store.delete_post(&id);
+    Ok(Redirect::to("/admin/posts"))
+}
+
+#[derive(Deserialize)]
+pub struct FeedSettingsForm {
+    feed_full_content: bool,
+}
+
+/// Chooses between full posts and excerpts in the blog feeds
+pub async fn admin_update_feed_settings(
+    State(store): State<Store>,
+    Form(form): Form<FeedSettingsForm>,
+) -> AppResult<Redirect> {
+    *store.feed_full_content.write()? = form.feed_full_content;
+    Ok(Redirect::to("/admin/posts"))
+}
+
+/// Opens or closes a post for reader comments
+pub async fn admin_toggle_post_comments(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Redirect> {
+    if let Some(post) = store.posts.write()?.get_mut(&id) {
+        post.comments_enabled = !post.comments_enabled;
+    }
+    Ok(Redirect::to(&format!("/admin/posts/edit/{}", id)))
+}
+
+#[derive(Deserialize)]
+pub struct CommentListQuery {
+    /// Moderation state to list, the pending queue by default
+    status: Option<String>,
+    /// Only lists the comments of this post
+    post: Option<String>,
+}
+
+/// Buttons moving a comment to the other moderation states
+fn comment_status_buttons(comment: &Comment, back: &str) -> String {
+    let mut buttons = String::new();
+    for (status, label) in [
+        (CommentStatus::Approved, "Approve"),
+        (CommentStatus::Rejected, "Reject"),
+        (CommentStatus::Spam, "Spam"),
+    ] {
+        if comment.status != status {
+            buttons.push_str(&format!(
+                r#"<form method="post" action="/admin/comments/status/{}" style="display:inline;">
+                        <input type="hidden" name="status" value="{}">
+                        <input type="hidden" name="back" value="{}">
+                        <button type="submit" class="btn btn-sm">{}</button>
+                    </form>"#,
+                comment.id, status.label(), back, label
+            ));
+        }
+    }
+    buttons
+}
+
+/// Moderation queue of reader comments with the spam keyword list
+pub async fn admin_list_comments(
+    State(store): State<Store>,
+    Query(query): Query<CommentListQuery>,
+) -> AppResult<Html<String>> {
+    let status = query.status.as_deref().and_then(CommentStatus::from_form).unwrap_or_default();
+    let post_filter = query.post.filter(|p| !p.is_empty());
+    let filter_query = post_filter.as_ref().map(|p| format!("&post={}", p)).unwrap_or_default();
+    let back = format!("/admin/comments?status={}{}", status.label(), filter_query);
+
+    let posts = store.posts.read()?;
+    let comments = store.comments.read()?;
+    let in_scope: Vec<_> = comments
+        .values()
+        .filter(|c| post_filter.as_ref().is_none_or(|p| &c.post_id == p))
+        .collect();
+    let mut listed: Vec<_> = in_scope.iter().filter(|c| c.status == status).collect();
+    listed.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+
+    let mut rows = String::new();
+    for comment in listed {
+        let post_title = posts.get(&comment.post_id).map(|p| p.title.as_str()).unwrap_or("Deleted post");
+        let reply_to = comment
+            .parent_id
+            .as_ref()
+            .and_then(|id| comments.get(id))
+            .map(|parent| format!("<br><small>In reply to {}</small>", escape_html(&parent.author_name)))
+            .unwrap_or_default();
+        rows.push_str(&format!(
+            r#"<tr>
+                <td>{}<br><small>{}</small></td>
+                <td>{}{}</td>
+                <td><a href="/admin/posts/edit/{}">{}</a></td>
+                <td>{}</td>
+                <td>
+                    {}
+                    <a href="/admin/comments/edit/{}" class="btn btn-sm">Edit</a>
+                    <form method="post" action="/admin/comments/delete/{}" style="display:inline;">
+                        <input type="hidden" name="back" value="{}">
+                        <button type="submit" class="btn btn-danger">Delete</button>
+                    </form>
+                </td>
+            </tr>"#,
+            escape_html(&comment.author_name), escape_html(&comment.author_email),
+            escape_html(&comment.body).replace('\n', "<br>"), reply_to,
+            comment.post_id, post_title,
+            comment.created_at.format("%Y-%m-%d %H:%M"),
+            comment_status_buttons(comment, &back),
+            comment.id, comment.id, back
+        ));
+    }
+
+    let mut filters = String::new();
+    for tab in CommentStatus::ALL {
+        filters.push_str(&format!(
+            r#" <a href="/admin/comments?status={}{}" class="btn btn-sm{}">{} ({})</a>"#,
+            tab.label(),
+            filter_query,
+            if tab == status { " btn-active" } else { "" },
+            tab.label(),
+            in_scope.iter().filter(|c| c.status == tab).count()
+        ));
+    }
+    let scope = match post_filter.as_ref().and_then(|id| posts.get(id)) {
+        Some(post) => format!(r#"<p>Showing comments on <strong>{}</strong>. <a href="/admin/comments">Show all posts</a></p>"#, post.title),
+        None => String::new(),
+    };
+
+    let content = format!(
+        r#"<h2>Comments</h2>
+    {}
+    <p class="post-filters">{}</p>
+    <table>
+        <thead>
+            <tr>
+                <th>Author</th>
+                <th>Comment</th>
+                <th>Post</th>
+                <th>Received</th>
+                <th>Actions</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+
+    <h2>Spam Filter</h2>
+    <form method="post" action="/admin/comments/keywords" class="create-form">
+        <label>Spam Keywords (one per line)
+            <textarea name="keywords" rows="6">{}</textarea>
+        </label>
+        <button type="submit" class="btn">Save Keywords</button>
+    </form>
+    <p>New comments containing a keyword in their name, email or text go straight to spam.
+    Comments that fill the hidden honeypot field are dropped, and each visitor can post
+    {} comments every {} minutes.</p>"#,
+        scope, filters, rows,
+        store.spam_keywords.read()?.join("\n"),
+        COMMENT_RATE_LIMIT, COMMENT_RATE_WINDOW_MINUTES
+    );
+
+    Ok(Html(admin_page("Comments - Admin", "/admin/comments", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct CommentStatusForm {
+    status: String,
+    /// Queue page to return to
+    back: String,
+}
+
+/// Approves, rejects or marks a comment as spam
+pub async fn admin_update_comment_status(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<CommentStatusForm>,
+) -> AppResult<Redirect> {
+    if let Some(status) = CommentStatus::from_form(&form.status) {
+        store.set_comment_status(&id, status);
+    }
+    let back = if form.back.starts_with("/admin/comments") { form.back } else { "/admin/comments".to_string() };
+    Ok(Redirect::to(&back))
+}
+
+/// Edit form for a comment
+pub async fn admin_edit_comment(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let comment = match store.comments.read()?.get(&id) {
+        Some(comment) => comment.clone(),
+        None => return Err(AppError::NotFound),
+    };
+    let settings = store.settings();
+
+    let content = format!(
+        r#"<h2>Edit Comment</h2>
+    <form method="post" action="/admin/comments/edit/{}" class="create-form">
+        <input type="text" name="author_name" value="{}" placeholder="Name" required>
+        <textarea name="body" rows="6" required>{}</textarea>
+        <button type="submit" class="btn">Save Comment</button>
+    </form>
+    <p>From {} on {} {}, currently {}.</p>"#,
+        comment.id, escape_html(&comment.author_name), escape_html(&comment.body),
+        escape_html(&comment.author_email), settings.local_time(comment.created_at).format("%Y-%m-%d %H:%M"),
+        settings.timezone,
+        comment.status.label().to_lowercase()
+    );
+
+    Ok(Html(admin_page("Edit Comment - Admin", "/admin/comments", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct UpdateCommentForm {
+    author_name: String,
+    body: String,
+}
+
+/// Saves changes to a comment
+pub async fn admin_update_comment(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<UpdateCommentForm>,
+) -> AppResult<Response> {
+    let status = store.comments.read()?.get(&id).map(|c| c.status).unwrap_or_default();
+    Ok(match store.update_comment(&id, form.author_name.trim().to_string(), form.body.trim().to_string()) {
+        Ok(()) => Redirect::to(&format!("/admin/comments?status={}", status.label())).into_response(),
+        Err(error) => action_error_page(
+            "Comment Not Saved",
+            &error,
+            &format!("/admin/comments/edit/{}", id),
+            "/admin/comments",
+        )
+        .into_response(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct DeleteCommentForm {
+    back: String,
+}
+
+/// Deletes a comment, replies to it stay in the thread
+pub async fn admin_delete_comment(
+    State(store): State<Store>,
+    Path(id): Path<String>,
+    Form(form): Form<DeleteCommentForm>,
+) -> AppResult<Redirect> {
+    store.delete_comment(&id);
+    let back = if form.back.starts_with("/admin/comments") { form.back } else { "/admin/comments".to_string() };
+    Ok(Redirect::to(&back))
+}
+
+#[derive(Deserialize)]
+pub struct SpamKeywordsForm {
+    keywords: String,
+}
+
+/// Replaces the spam keyword list
+pub async fn admin_update_spam_keywords(
+    State(store): State<Store>,
+    Form(form): Form<SpamKeywordsForm>,
+) -> AppResult<Redirect> {
+    *store.spam_keywords.write()? = form
+        .keywords
+        .lines()
+        .map(|k| k.trim().to_lowercase())
+        .filter(|k| !k.is_empty())
+        .collect();
+    Ok(Redirect::to("/admin/comments"))
+}
+
+/// Search engine settings with the robots.txt rules and a link to the sitemap
+pub async fn admin_seo(State(store): State<Store>) -> AppResult<Html<String>> {
+    let content = format!(
+        r#"<h2>SEO</h2>
+    <p>The <a href="/sitemap.xml" target="_blank">sitemap</a> lists the homepage, shop, categories, products
+    and published posts with the time they last changed. Very large sites get a sitemap index instead.</p>
+
+    <h2>robots.txt</h2>
+    <form method="post" action="/admin/seo/robots" class="create-form">
+        <textarea name="rules" rows="10">{}</textarea>
+        <button type="submit" class="btn">Save robots.txt</button>
+    </form>
+    <p>Served at <a href="/robots.txt" target="_blank">/robots.txt</a>. A <code>Sitemap:</code> line pointing to
+    the sitemap is added unless the rules contain one.</p>"#,
+        escape_html(&store.robots_txt.read()?)
+    );
+
+    Ok(Html(admin_page("SEO - Admin", "/admin/seo", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct RobotsTxtForm {
+    rules: String,
+}
+
+/// Saves the robots.txt rules
+pub async fn admin_update_robots_txt(
+    State(store): State<Store>,
+    Form(form): Form<RobotsTxtForm>,
+) -> AppResult<Redirect> {
+    *store.robots_txt.write()? = form.rules.replace("\r\n", "\n");
+    Ok(Redirect::to("/admin/seo"))
+}
+
+
+/// Site settings with the name, logo, footer, contact address and display options
+pub async fn admin_settings(State(store): State<Store>) -> AppResult<Html<String>> {
+    let settings = store.settings();
+
+    let mut currencies: Vec<_> = store.currencies.read()?.values().cloned().collect();
+    currencies.sort_by(|a, b| a.code.cmp(&b.code));
+    let mut currency_options = String::new();
+    for currency in &currencies {
+        currency_options.push_str(&format!(
+            r#"<option value="{}"{}>{} - {}</option>"#,
+            currency.code,
+            if currency.code == settings.default_currency { " selected" } else { "" },
+            currency.code, currency.name
+        ));
+    }
+    let mut timezone_options = String::new();
+    for timezone in timezone_choices() {
+        timezone_options.push_str(&format!(
+            r#"<option value="{0}"{1}>{0}</option>"#,
+            timezone,
+            if timezone == settings.timezone { " selected" } else { "" }
+        ));
+    }
+
+    let content = format!(
+        r#"<h2>Site Settings</h2>
+    <form method="post" action="/admin/settings" class="create-form">
+        <label>Site name
+            <input type="text" name="site_name" value="{}" required>
+        </label>
+        <label>Tagline
+            <input type="text" name="tagline" value="{}">
+        </label>
+        <label>Footer text
+            <input type="text" name="footer_text" value="{}">
+        </label>
+        <p>Use <code>{{year}}</code> for the current year.</p>
+        <label>Contact email
+            <input type="email" name="contact_email" value="{}">
+        </label>
+        <label>Default currency
+            <select name="default_currency">{}</select>
+        </label>
+        <label>Timezone
+            <select name="timezone">{}</select>
+        </label>
+        <label>Posts per page
+            <input type="number" name="posts_per_page" value="{}" min="1" max="{}" required>
+        </label>
+        <button type="submit" class="btn">Save Settings</button>
+    </form>
+    <p>The default currency is shown to visitors who haven't picked one. Prices are still entered in the
+    <a href="/admin/currencies">base currency</a>. Dates on the site are shown in the timezone, and times
+    entered in the admin are read in it. Timezones are fixed UTC offsets that never change for daylight saving
+    time, so pick a new one when the clocks change.</p>
+
+    <h2>Logo</h2>
+    <p>Shown in the site header instead of the site name.</p>
+    <form method="post" action="/admin/settings/logo" enctype="multipart/form-data" class="create-form">
+        {}
+        <button type="submit" class="btn">Save Logo</button>
+    </form>"#,
+        escape_html(&settings.site_name), escape_html(&settings.tagline), escape_html(&settings.footer_text),
+        escape_html(&settings.contact_email), currency_options, timezone_options,
+        settings.posts_per_page, MAX_POSTS_PER_PAGE,
+        image_picker(&store, settings.logo_url.as_deref(), true)
+    );
+
+    Ok(Html(admin_page("Settings - Admin", "/admin/settings", &content)))
+}
+
+#[derive(Deserialize)]
+pub struct SettingsForm {
+    site_name: String,
+    tagline: String,
+    footer_text: String,
+    contact_email: String,
+    default_currency: String,
+    timezone: String,
+    posts_per_page: usize,
+}
+
+/// Saves the site settings, the logo is kept
+pub async fn admin_update_settings(
+    State(store): State<Store>,
+    Form(form): Form<SettingsForm>,
+) -> AppResult<Response> {
+    let settings = SiteSettings {
+        site_name: form.site_name.trim().to_string(),
+        tagline: form.tagline.trim().to_string(),
+        logo_url: store.settings().logo_url,
+        footer_text: form.footer_text.trim().to_string(),
+        contact_email: form.contact_email.trim().to_string(),
+        default_currency: form.default_currency,
+        timezone: form.timezone,
+        posts_per_page: form.posts_per_page,
+    };
+    Ok(match store.update_settings(settings) {
+        Ok(()) => Redirect::to("/admin/settings").into_response(),
+        Err(error) => action_error_page("Settings Not Saved", &error, "/admin/settings", "/admin/settings").into_response(),
+    })
+}
+
+/// Sets the site logo from an upload or the media library
+pub async fn admin_set_logo(
+    State(store): State<Store>,
+    multipart: Multipart,
+) -> AppResult<Response> {
+    let chosen = match read_upload_form(multipart).await {
+        Ok(mut form) => chosen_image(&store, &mut form).await,
+        Err(error) => Err(error),
+    };
+    let saved = chosen.and_then(|logo_url| store.update_settings(SiteSettings { logo_url, ..store.settings() }));
+    Ok(match saved {
+        Ok(()) => Redirect::to("/admin/settings").into_response(),
+        Err(error) => action_error_page("Logo Not Saved", &error, "/admin/settings", "/admin/settings").into_response(),
+    })
+}
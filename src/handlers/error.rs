@@ -0,0 +1,104 @@
+//! Errors of request handlers and the pages shown for them
+//! Handlers return `AppResult` and use `?` on store locks and missing records
+//! The error page itself is rendered by `render_error_pages`, which has the store for the site menus
+
+use std::sync::PoisonError;
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+};
+use crate::store::Store;
+use super::layout::public_page;
+
+/// Failure of a request, rendered as a themed error page with a matching status code
+#[derive(Debug, Clone)]
+pub enum AppError {
+    /// The page does not exist or is not public
+    NotFound,
+    /// The page belongs to someone else
+    Forbidden,
+    /// Something went wrong on the server, the message is logged and not shown to visitors
+    Internal(String),
+}
+
+/// Result of a handler that can fail with an error page
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::Forbidden => StatusCode::FORBIDDEN,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Heading and explanation shown on the error page
+    fn message(&self) -> (&'static str, &'static str) {
+        match self {
+            AppError::NotFound => ("Page Not Found", "The page you are looking for doesn't exist or is no longer available."),
+            AppError::Forbidden => ("Access Denied", "You don't have permission to view this page."),
+            AppError::Internal(_) => ("Something Went Wrong", "We couldn't complete your request. Please try again in a moment."),
+        }
+    }
+
+    /// Renders the themed error page
+    fn page(&self, store: &Store) -> Response {
+        let status = self.status();
+        let (heading, text) = self.message();
+        let content = format!(
+            r#"<section class="error-page">
+            <p class="error-code">{}</p>
+            <h1>{}</h1>
+            <p>{}</p>
+            <div class="hero-buttons">
+                <a href="/" class="btn btn-primary">Go to Homepage</a>
+                <a href="/shop" class="btn btn-secondary">Browse Products</a>
+            </div>
+        </section>"#,
+            status.as_u16(), heading, text
+        );
+        (status, Html(public_page(store, &format!("{} - {}", heading, store.site_name()), "", "", &content))).into_response()
+    }
+}
+
+/// Answers with the status code only, the page is added by `render_error_pages`
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::Internal(message) = &self {
+            tracing::error!("Request failed: {}", message);
+        }
+        let mut response = self.status().into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Middleware replacing the responses of failed handlers with the error page
+pub async fn render_error_pages(State(store): State<Store>, response: Response) -> Response {
+    match response.extensions().get::<AppError>() {
+        Some(error) => error.page(&store),
+        None => response,
+    }
+}
+
+/// A store lock held by a handler that panicked
+impl<T> From<PoisonError<T>> for AppError {
+    fn from(error: PoisonError<T>) -> Self {
+        AppError::Internal(error.to_string())
+    }
+}
+
+/// Fallback for paths no route matches
+pub async fn not_found() -> AppError {
+    AppError::NotFound
+}
+
+/// Response for a handler that panicked
+/// Clears lock poisoning so later requests keep working with the store
+pub fn panic_response(store: &Store) -> Response {
+    store.clear_poison();
+    tracing::error!("Request failed: handler panicked");
+    AppError::Internal("handler panicked".to_string()).page(store)
+}
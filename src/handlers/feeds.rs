@@ -0,0 +1,253 @@
+//! RSS and Atom feeds of the blog
+//! Every feed exists in both formats, for the whole blog, a blog category or a tag
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use crate::email::site_url;
+use crate::store::Store;
+use crate::models::Post;
+use super::layout::escape_html;
+use super::error::{AppError, AppResult};
+
+/// Number of posts listed in a feed
+const FEED_LIMIT: usize = 20;
+
+/// Posts and descriptions of one feed
+struct Feed {
+    title: String,
+    description: String,
+    /// Path of the feed without the `.rss` or `.atom` extension
+    path: String,
+    /// Page showing the same posts
+    page_path: String,
+    posts: Vec<Post>,
+}
+
+impl Feed {
+    /// Collects the newest published posts matching `filter`
+    fn new(store: &Store, title: String, description: String, path: String, page_path: String, filter: impl Fn(&Post) -> bool) -> AppResult<Self> {
+        let mut posts: Vec<_> = store
+            .posts
+            .read()?
+            .values()
+            .filter(|p| p.is_published() && filter(p))
+            .cloned()
+            .collect();
+        posts.sort_by_key(|p| std::cmp::Reverse(p.date()));
+        posts.truncate(FEED_LIMIT);
+        Ok(Self { title, description, path, page_path, posts })
+    }
+
+    /// Newest change of any post in the feed
+    fn updated(&self) -> DateTime<Utc> {
+        self.posts.iter().map(|p| p.updated_at).max().unwrap_or_else(Utc::now)
+    }
+}
+
+/// Autodiscovery `<link>` tags for the RSS and Atom versions of a feed
+/// `path` is the feed path without extension, like `/blog/feed`
+pub(crate) fn feed_links(title: &str, path: &str) -> String {
+    format!(
+        r#"
+    <link rel="alternate" type="application/rss+xml" title="{} (RSS)" href="{}.rss">
+    <link rel="alternate" type="application/atom+xml" title="{} (Atom)" href="{}.atom">"#,
+        escape_html(title), path, escape_html(title), path
+    )
+}
+
+/// Text of a post in a feed, the full post or its excerpt depending on the setting
+fn feed_content(full_content: bool, post: &Post) -> String {
+    if full_content {
+        format!("<p>{}</p>", post.content)
+    } else {
+        post.excerpt.clone()
+    }
+}
+
+/// Renders a feed as RSS 2.0
+fn rss(store: &Store, feed: &Feed) -> AppResult<impl IntoResponse> {
+    let site = site_url();
+    let categories = store.blog_categories.read()?;
+    let authors = store.authors.read()?;
+    let full_content = *store.feed_full_content.read()?;
+
+    let mut items = String::new();
+    for post in &feed.posts {
+        let link = format!("{}/blog/{}", site, post.id);
+        let mut terms: Vec<String> = categories.get(&post.category_id).map(|c| c.name.clone()).into_iter().collect();
+        terms.extend(store.tags_for(&post.tag_ids).into_iter().map(|t| t.name));
+        items.push_str(&format!(
+            r#"
+    <item>
+      <title>{}</title>
+      <link>{}</link>
+      <guid isPermaLink="true">{}</guid>
+      <pubDate>{}</pubDate>
+      <dc:creator>{}</dc:creator>{}
+      <description>{}</description>
+    </item>"#,
+            escape_html(&post.title),
+            link,
+            link,
+            post.date().to_rfc2822(),
+            escape_html(authors.get(&post.author_id).map(|a| a.name.as_str()).unwrap_or("Unknown")),
+            terms.iter().map(|t| format!("\n      <category>{}</category>", escape_html(t))).collect::<String>(),
+            escape_html(&feed_content(full_content, post))
+        ));
+    }
+
+    let body = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>{}</title>
+    <link>{}{}</link>
+    <description>{}</description>
+    <atom:link href="{}{}.rss" rel="self" type="application/rss+xml"/>
+    <lastBuildDate>{}</lastBuildDate>{}
+  </channel>
+</rss>
+"#,
+        escape_html(&feed.title),
+        site, feed.page_path,
+        escape_html(&feed.description),
+        site, feed.path,
+        feed.updated().to_rfc2822(),
+        items
+    );
+    Ok(([(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")], body))
+}
+
+/// Renders a feed as Atom
+fn atom(store: &Store, feed: &Feed) -> AppResult<impl IntoResponse> {
+    let site = site_url();
+    let categories = store.blog_categories.read()?;
+    let authors = store.authors.read()?;
+    let full_content = *store.feed_full_content.read()?;
+
+    let mut entries = String::new();
+    for post in &feed.posts {
+        let link = format!("{}/blog/{}", site, post.id);
+        let mut terms: Vec<String> = categories.get(&post.category_id).map(|c| c.name.clone()).into_iter().collect();
+        terms.extend(store.tags_for(&post.tag_ids).into_iter().map(|t| t.name));
+        let text = if full_content {
+            format!(r#"<content type="html">{}</content>"#, escape_html(&feed_content(full_content, post)))
+        } else {
+            format!("<summary>{}</summary>", escape_html(&post.excerpt))
+        };
+        entries.push_str(&format!(
+            r#"
+  <entry>
+    <title>{}</title>
+    <id>{}</id>
+    <link rel="alternate" type="text/html" href="{}"/>
+    <published>{}</published>
+    <updated>{}</updated>
+    <author><name>{}</name></author>{}
+    {}
+  </entry>"#,
+            escape_html(&post.title),
+            link,
+            link,
+            post.date().to_rfc3339_opts(SecondsFormat::Secs, true),
+            post.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            escape_html(authors.get(&post.author_id).map(|a| a.name.as_str()).unwrap_or("Unknown")),
+            terms.iter().map(|t| format!(r#"
+    <category term="{}"/>"#, escape_html(t))).collect::<String>(),
+            text
+        ));
+    }
+
+    let body = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>{}</title>
+  <subtitle>{}</subtitle>
+  <id>{}{}.atom</id>
+  <link rel="self" type="application/atom+xml" href="{}{}.atom"/>
+  <link rel="alternate" type="text/html" href="{}{}"/>
+  <updated>{}</updated>{}
+</feed>
+"#,
+        escape_html(&feed.title),
+        escape_html(&feed.description),
+        site, feed.path,
+        site, feed.path,
+        site, feed.page_path,
+        feed.updated().to_rfc3339_opts(SecondsFormat::Secs, true),
+        entries
+    );
+    Ok(([(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")], body))
+}
+
+/// Feed of all published posts
+fn blog_feed(store: &Store) -> AppResult<Feed> {
+    Feed::new(
+        store,
+        format!("{} Blog", store.site_name()),
+        "Read our latest articles and updates".to_string(),
+        "/blog/feed".to_string(),
+        "/blog".to_string(),
+        |_| true,
+    )
+}
+
+/// Feed of the posts in a blog category
+fn category_feed(store: &Store, id: &str) -> AppResult<Feed> {
+    let category = store.blog_categories.read()?.get(id).cloned().ok_or(AppError::NotFound)?;
+    Feed::new(
+        store,
+        format!("{} Blog - {}", store.site_name(), category.name),
+        category.description,
+        format!("/blog/category/{}/feed", category.id),
+        "/blog".to_string(),
+        |p| p.category_id == category.id,
+    )
+}
+
+/// Feed of the posts with a tag
+fn tag_feed(store: &Store, slug: &str) -> AppResult<Feed> {
+    let tag = store.tag_by_slug(slug).ok_or(AppError::NotFound)?;
+    Feed::new(
+        store,
+        format!("{} Blog - {}", store.site_name(), tag.name),
+        format!("Posts tagged {}", tag.name),
+        format!("/blog/tag/{}/feed", tag.slug),
+        format!("/blog/tag/{}", tag.slug),
+        |p| p.tag_ids.contains(&tag.id),
+    )
+}
+
+/// RSS feed of the blog
+pub async fn blog_rss(State(store): State<Store>) -> AppResult<impl IntoResponse> {
+    rss(&store, &blog_feed(&store)?)
+}
+
+/// Atom feed of the blog
+pub async fn blog_atom(State(store): State<Store>) -> AppResult<impl IntoResponse> {
+    atom(&store, &blog_feed(&store)?)
+}
+
+/// RSS feed of a blog category
+pub async fn blog_category_rss(State(store): State<Store>, Path(id): Path<String>) -> AppResult<impl IntoResponse> {
+    rss(&store, &category_feed(&store, &id)?)
+}
+
+/// Atom feed of a blog category
+pub async fn blog_category_atom(State(store): State<Store>, Path(id): Path<String>) -> AppResult<impl IntoResponse> {
+    atom(&store, &category_feed(&store, &id)?)
+}
+
+/// RSS feed of a tag
+pub async fn blog_tag_rss(State(store): State<Store>, Path(slug): Path<String>) -> AppResult<impl IntoResponse> {
+    rss(&store, &tag_feed(&store, &slug)?)
+}
+
+/// Atom feed of a tag
+pub async fn blog_tag_atom(State(store): State<Store>, Path(slug): Path<String>) -> AppResult<impl IntoResponse> {
+    atom(&store, &tag_feed(&store, &slug)?)
+}
@@ -0,0 +1,177 @@
+//! Shared page layouts
+//! Every page is rendered through one of these so the navigation
+//! and footer markup only lives in one place
+
+use crate::models::{MenuEntry, MenuLocation};
+use crate::store::Store;
+pub use crate::html::escape_html;
+
+/// Admin navigation entries as (href, label)
+const ADMIN_NAV: &[(&str, &str)] = &[
+    ("/admin", "Dashboard"),
+    ("/admin/categories", "Product Categories"),
+    ("/admin/products", "Products"),
+    ("/admin/blog-categories", "Blog Categories"),
+    ("/admin/inventory", "Inventory"),
+    ("/admin/orders", "Orders"),
+    ("/admin/customers", "Customers"),
+    ("/admin/coupons", "Coupons"),
+    ("/admin/taxes", "Taxes"),
+    ("/admin/shipping", "Shipping"),
+    ("/admin/posts", "Blog Posts"),
+    ("/admin/authors", "Authors"),
+    ("/admin/tags", "Tags"),
+    ("/admin/comments", "Comments"),
+    ("/admin/pages", "Pages"),
+    ("/admin/menus", "Menus"),
+    ("/admin/currencies", "Currencies"),
+    ("/admin/emails", "Emails"),
+    ("/admin/media", "Media"),
+    ("/admin/seo", "SEO"),
+    ("/admin/settings", "Settings"),
+    ("/", "View Site"),
+];
+
+/// Renders navigation links, marking the entry matching `active`
+fn nav_links(entries: &[(&str, &str)], active: &str) -> String {
+    let mut links = String::new();
+    for (href, label) in entries {
+        let class = if *href == active { r#" class="active""# } else { "" };
+        links.push_str(&format!(
+            r#"
+            <li><a href="{}"{}>{}</a></li>"#,
+            href, class, label
+        ));
+    }
+    links
+}
+
+/// Wraps admin page content with the admin navigation
+pub fn admin_page(title: &str, active: &str, content: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{}</title>
+    <link rel="stylesheet" href="/static/css/admin.css">
+</head>
+<body>
+    <nav class="admin-nav">
+        <h1>CMS Admin Panel</h1>
+        <ul>{}
+        </ul>
+    </nav>
+    <div class="container">
+        {}
+    </div>
+</body>
+</html>"#,
+        title,
+        nav_links(ADMIN_NAV, active),
+        content
+    )
+}
+
+/// Whether a menu entry or one of its children links to `active`
+fn menu_entry_active(entry: &MenuEntry, active: &str) -> bool {
+    entry.href == active || entry.children.iter().any(|child| menu_entry_active(child, active))
+}
+
+/// Renders menu entries as nested lists, marking the entries leading to `active`
+fn menu_links(entries: &[MenuEntry], active: &str) -> String {
+    let mut links = String::new();
+    for entry in entries {
+        let class = if menu_entry_active(entry, active) { r#" class="active""# } else { "" };
+        let submenu = if entry.children.is_empty() {
+            String::new()
+        } else {
+            format!(r#"<ul class="submenu">{}</ul>"#, menu_links(&entry.children, active))
+        };
+        links.push_str(&format!(
+            r#"
+            <li{}><a href="{}"{}>{}</a>{}</li>"#,
+            if entry.children.is_empty() { "" } else { r#" class="has-submenu""# },
+            escape_html(&entry.href), class, escape_html(&entry.label), submenu
+        ));
+    }
+    links
+}
+
+/// Wraps public page content with the site header and footer
+/// `nav_extra` is placed after the navigation links (e.g. the currency switcher)
+pub fn public_page(store: &Store, title: &str, active: &str, nav_extra: &str, content: &str) -> String {
+    public_page_with_head(store, title, "", active, nav_extra, content)
+}
+
+/// Like `public_page`, with extra tags for the `<head>` such as feed links
+/// The header and footer menus are the ones edited in the admin, the logo and footer text come from the site settings
+pub fn public_page_with_head(store: &Store, title: &str, head: &str, active: &str, nav_extra: &str, content: &str) -> String {
+    let settings = store.settings();
+    let site_name = escape_html(&settings.site_name);
+    let logo = match &settings.logo_url {
+        Some(url) => format!(r#"<img src="{}" alt="{}">"#, escape_html(url), site_name),
+        None => site_name.clone(),
+    };
+    let contact = if settings.contact_email.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"<p class="footer-contact">Contact us at <a href="mailto:{0}">{0}</a></p>
+        "#,
+            escape_html(&settings.contact_email)
+        )
+    };
+    let footer_menu = store.menu(MenuLocation::Footer);
+    let footer_nav = if footer_menu.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"<nav class="footer-nav">
+            <ul>{}
+            </ul>
+        </nav>
+        "#,
+            menu_links(&footer_menu, active)
+        )
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{}</title>
+    <meta property="og:site_name" content="{}">
+    <link rel="stylesheet" href="/static/css/public.css">{}
+</head>
+<body>
+    <header>
+        <nav class="main-nav">
+            <div class="logo"><a href="/">{}</a></div>
+            <ul>{}
+            </ul>
+            {}
+        </nav>
+    </header>
+    <main>
+        {}
+    </main>
+    <footer>
+        {}{}<p>{}</p>
+    </footer>
+</body>
+</html>"#,
+        escape_html(title),
+        site_name,
+        head,
+        logo,
+        menu_links(&store.menu(MenuLocation::Header), active),
+        nav_extra,
+        content,
+        footer_nav,
+        contact,
+        escape_html(&settings.footer_line())
+    )
+}
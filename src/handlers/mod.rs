@@ -1,6 +1,16 @@
 pub mod admin;
 pub mod public;
+pub mod account;
+pub mod feeds;
+pub mod sitemap;
+pub mod error;
+mod layout;
+mod seo;
 
 pub use admin::*;
 pub use public::*;
+pub use account::*;
+pub use feeds::*;
+pub use sitemap::*;
+pub use error::*;
 
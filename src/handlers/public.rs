@@ -1,280 +1,2113 @@
 use axum::{
-    extract::{Path, State},
-    response::{Html, IntoResponse},
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap},
+    response::{Html, IntoResponse, Redirect, Response},
+    Form,
 };
-use crate::store::Store;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use serde::Deserialize;
+use serde_json::json;
+use chrono::SecondsFormat;
+use crate::store::{default_home_body, Store};
+use crate::models::{AttributeType, AttributeValue, Cart, CartSummary, Category, Currency, Order, Product, TaxLine, Address, ImageSize, Author, Post, Tag, Comment, CommentStatus, SeoMeta, SiteSettings, MAX_LINE_QUANTITY};
+use super::layout::{escape_html, public_page, public_page_with_head};
+use super::feeds::feed_links;
+use super::seo::{breadcrumbs, summary, PageMeta};
+use super::error::{AppError, AppResult};
+use super::account::current_customer;
 
-/// Public homepage handler
-/// Shows links to shop and blog sections
-pub async fn public_home() -> impl IntoResponse {
-    let html = r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Welcome to Our Store</title>
-    <link rel="stylesheet" href="/static/css/public.css">
-</head>
-<body>
-    <header>
-        <nav class="main-nav">
-            <div class="logo">MyStore CMS</div>
-            <ul>
-                <li><a href="/">Home</a></li>
-                <li><a href="/shop">Shop</a></li>
-                <li><a href="/blog">Blog</a></li>
-                <li><a href="/admin">Admin</a></li>
-            </ul>
-        </nav>
-    </header>
-    <main>
-        <section class="hero">
-            <h1>Welcome to MyStore</h1>
-            <p>Discover amazing products and read our latest articles</p>
-            <div class="hero-buttons">
-                <a href="/shop" class="btn btn-primary">Browse Products</a>
-                <a href="/blog" class="btn btn-secondary">Read Blog</a>
-            </div>
-        </section>
-        <section class="features">
-            <div class="feature">
-                <h3>Quality Products</h3>
-                <p>We offer only the best products carefuly selected for you</p>
-            </div>
-            <div class="feature">
-                <h3>Fast Shipping</h3>
-                <p>Get your orders delivered quickly and securly</p>
-            </div>
-            <div class="feature">
-                <h3>Great Content</h3>
-                <p>Stay updated with our informative blog posts</p>
-            </div>
-        </section>
-    </main>
-    <footer>
-        <p>&copy; 2025 MyStore CMS. All rights reserved.</p>
-    </footer>
-</body>
-</html>"#;
+/// Name of the cookie holding the visitor's selected currency
+const CURRENCY_COOKIE: &str = "currency";
 
-    Html(html)
+/// Name of the cookie holding the visitor's cart id
+const CART_COOKIE: &str = "cart";
+
+/// Reads a cookie value from the request headers
+pub(crate) fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
 }
 
-/// Shop page handler
-/// Displays all available products organized by category
-pub async fn public_shop(State(store): State<Store>) -> impl IntoResponse {
-    let products = store.products.read().unwrap();
-    let categories = store.categories.read().unwrap();
-    
-    let mut products_vec: Vec<_> = products.values().filter(|p| p.is_available()).collect();
-    products_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+/// Returns the currency selected by the visitor, or the default currency of the site settings
+pub(crate) fn selected_currency(store: &Store, headers: &HeaderMap) -> Currency {
+    let code = cookie_value(headers, CURRENCY_COOKIE).unwrap_or_else(|| store.settings().default_currency);
+    store.currency_or_base(Some(&code))
+}
 
-    let mut products_html = String::new();
-    for product in products_vec {
-        let category_name = categories
-            .get(&product.category_id)
-            .map(|c| c.name.as_str())
-            .unwrap_or("Uncategorized");
+/// Returns the visitor's cart id from the cart cookie
+pub(crate) fn cart_id(headers: &HeaderMap) -> Option<String> {
+    cookie_value(headers, CART_COOKIE)
+}
+
+/// Builds the account and cart links and currency switcher shown in the public navigation
+pub(crate) fn nav_tools(store: &Store, headers: &HeaderMap, current: &Currency, return_to: &str) -> String {
+    let cart_count = cart_id(headers)
+        .and_then(|id| store.carts.read().unwrap().get(&id).map(|c| c.item_count()))
+        .unwrap_or(0);
+
+    let currencies = store.currencies.read().unwrap();
+    let mut currencies_vec: Vec<_> = currencies.values().collect();
+    currencies_vec.sort_by(|a, b| a.code.cmp(&b.code));
+
+    let mut options = String::new();
+    for currency in currencies_vec {
+        let selected = if currency.code == current.code { " selected" } else { "" };
+        options.push_str(&format!(
+            r#"<option value="{}"{}>{} ({})</option>"#,
+            currency.code, selected, currency.code, currency.symbol
+        ));
+    }
+
+    let account_link = match current_customer(store, headers) {
+        Some(_) => r#"<a href="/account" class="cart-link">Account</a>"#,
+        None => r#"<a href="/account/login" class="cart-link">Log In</a>"#,
+    };
+
+    format!(
+        r#"<div class="nav-tools">
+                {}
+                <a href="/cart" class="cart-link">Cart ({})</a>
+                <form method="get" action="/currency" class="currency-switcher">
+                    <input type="hidden" name="return_to" value="{}">
+                    <select name="code" onchange="this.form.submit()">{}</select>
+                    <noscript><button type="submit">Go</button></noscript>
+                </form>
+            </div>"#,
+        account_link, cart_count, escape_html(return_to), options
+    )
+}
+
+/// Adds a tax percentage to a net price
+fn with_tax(amount: f64, tax_rate: f64) -> f64 {
+    amount * (1.0 + tax_rate / 100.0)
+}
 
-        products_html.push_str(&format!(
-            r#"<div class="product-card">
-                <div class="product-image"></div>
-                <h3>{}</h3>
+/// Renders a product card for the shop grids
+/// The tax rate is added to the shown prices when prices are displayed including tax
+fn product_card(store: &Store, product: &Product, category: Option<&Category>, currency: &Currency, tax_rate: f64) -> String {
+    let (min_price, max_price) = product.current_price_range_in(currency);
+    let (min_price, max_price) = (with_tax(min_price, tax_rate), with_tax(max_price, tax_rate));
+    let regular_min = with_tax(product.price_range_in(currency).0, tax_rate);
+    let price = if min_price < max_price {
+        format!("From {}", price_html(min_price, regular_min, currency))
+    } else {
+        price_html(min_price, regular_min, currency)
+    };
+
+    let category_html = match category {
+        Some(category) => format!(r#"<a href="/shop/category/{}">{}</a>"#, category.id, category.name),
+        None => "Uncategorized".to_string(),
+    };
+
+    format!(
+        r#"<div class="product-card">
+                {}{}
+                <h3><a href="/shop/{}">{}</a></h3>
                 <p class="category">{}</p>
                 <p class="description">{}</p>
                 <div class="product-footer">
-                    <span class="price">${:.2}</span>
+                    <span class="price">{}</span>
                     <span class="stock">{} in stock</span>
                 </div>
             </div>"#,
-            product.name, category_name, product.description, product.price, product.stock
+        if product.is_on_sale() { r#"<span class="sale-badge">Sale</span>"# } else { "" },
+        product_image_html(store, product),
+        product.id, product.name, category_html, product.description,
+        price, product.total_stock()
+    )
+}
+
+/// `sizes` of images on product cards, three cards per row on wide screens
+const CARD_IMAGE_SIZES: &str = "(max-width: 768px) 100vw, 400px";
+
+/// `sizes` of images spanning the content column
+const CONTENT_IMAGE_SIZES: &str = "(max-width: 900px) 100vw, 900px";
+
+/// Renders an image as a `<picture>` offering every size in WebP and the fallback format
+/// URLs that aren't in the media library are rendered as a plain image
+fn responsive_image(store: &Store, url: &str, alt: &str, class: &str, sizes: &str) -> String {
+    match store.media_by_url(url) {
+        Some(asset) => format!(
+            r#"<picture>
+                    <source type="image/webp" srcset="{}" sizes="{}">
+                    <img class="{}" src="{}" srcset="{}" sizes="{}" width="{}" height="{}" alt="{}" loading="lazy">
+                </picture>"#,
+            asset.srcset(true), sizes,
+            class, asset.url_for(ImageSize::Card), asset.srcset(false), sizes,
+            asset.width, asset.height, escape_html(alt)
+        ),
+        None => format!(r#"<img class="{}" src="{}" alt="{}" loading="lazy">"#, class, url, escape_html(alt)),
+    }
+}
+
+/// Renders the primary image of a product, or the gradient placeholder for products without one
+fn product_image_html(store: &Store, product: &Product) -> String {
+    match product.primary_image() {
+        Some(image) => responsive_image(store, &image.url, product.image_alt(image), "product-image", CARD_IMAGE_SIZES),
+        None => r#"<div class="product-image"></div>"#.to_string(),
+    }
+}
+
+/// Renders the product gallery with previous and next buttons and a thumbnail strip
+/// Products with a single image or none get just that image or the placeholder
+fn product_gallery_html(store: &Store, product: &Product) -> String {
+    const SIZES: &str = "(max-width: 768px) 100vw, 50vw";
+    if product.images.len() < 2 {
+        let image = match product.primary_image() {
+            Some(image) => responsive_image(store, &image.url, product.image_alt(image), "product-image", SIZES),
+            None => r#"<div class="product-image"></div>"#.to_string(),
+        };
+        return format!(r#"<div class="product-gallery">{}</div>"#, image);
+    }
+
+    let mut slides = String::new();
+    let mut thumbs = String::new();
+    for (index, image) in product.images.iter().enumerate() {
+        let alt = product.image_alt(image);
+        slides.push_str(&format!(
+            r#"<div class="gallery-slide"{}>{}</div>"#,
+            if index == 0 { "" } else { " hidden" },
+            responsive_image(store, &image.url, alt, "product-image", SIZES)
+        ));
+        let thumbnail = store
+            .media_by_url(&image.url)
+            .map(|asset| asset.url_for(ImageSize::Thumbnail))
+            .unwrap_or_else(|| image.url.clone());
+        thumbs.push_str(&format!(
+            r#"<button type="button" class="gallery-thumb{}" data-index="{}" aria-label="Show image {}"><img src="{}" alt="{}"></button>"#,
+            if index == 0 { " active" } else { "" },
+            index, index + 1, thumbnail, escape_html(alt)
+        ));
+    }
+
+    format!(
+        r#"<div class="product-gallery" id="product-gallery">
+                <div class="gallery-main">
+                    {}
+                    <button type="button" class="gallery-nav gallery-prev" aria-label="Previous image">&lsaquo;</button>
+                    <button type="button" class="gallery-nav gallery-next" aria-label="Next image">&rsaquo;</button>
+                </div>
+                <div class="gallery-thumbs">{}</div>
+            </div>
+            <script>
+                (function () {{
+                    var gallery = document.getElementById('product-gallery');
+                    var slides = gallery.querySelectorAll('.gallery-slide');
+                    var thumbs = gallery.querySelectorAll('.gallery-thumb');
+                    var current = 0;
+                    function show(index) {{
+                        current = (index + slides.length) % slides.length;
+                        slides.forEach(function (slide, i) {{ slide.hidden = i !== current; }});
+                        thumbs.forEach(function (thumb, i) {{ thumb.classList.toggle('active', i === current); }});
+                    }}
+                    gallery.querySelector('.gallery-prev').addEventListener('click', function () {{ show(current - 1); }});
+                    gallery.querySelector('.gallery-next').addEventListener('click', function () {{ show(current + 1); }});
+                    thumbs.forEach(function (thumb) {{
+                        thumb.addEventListener('click', function () {{ show(Number(thumb.dataset.index)); }});
+                    }});
+                    document.addEventListener('keydown', function (e) {{
+                        if (/INPUT|SELECT|TEXTAREA/.test(e.target.tagName)) return;
+                        if (e.key === 'ArrowLeft') show(current - 1);
+                        if (e.key === 'ArrowRight') show(current + 1);
+                    }});
+                }})();
+            </script>"#,
+        slides, thumbs
+    )
+}
+
+/// Formats a price, showing the regular price struck through during a sale
+fn price_html(price: f64, regular_price: f64, currency: &Currency) -> String {
+    if price < regular_price {
+        format!(
+            r#"<del>{}</del> <span class="sale-price">{}</span>"#,
+            currency.format(regular_price),
+            currency.format(price)
+        )
+    } else {
+        currency.format(price)
+    }
+}
+
+/// Renders the add to cart controls of the product page
+fn add_to_cart_button(available: bool) -> String {
+    if available {
+        r#"<div class="cart-controls">
+                        <input type="number" name="quantity" value="1" min="1">
+                        <button type="submit" class="btn btn-primary">Add to Cart</button>
+                    </div>"#
+            .to_string()
+    } else {
+        r#"<button type="button" class="btn" disabled>Sold Out</button>"#.to_string()
+    }
+}
+
+/// Renders the list of category links shown above the shop grids
+fn category_links(categories: &HashMap<String, Category>, active: Option<&str>) -> String {
+    let mut categories_vec: Vec<_> = categories.values().collect();
+    categories_vec.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut links = format!(
+        r#"<a href="/shop"{}>All</a>"#,
+        if active.is_none() { r#" class="active""# } else { "" }
+    );
+    for category in categories_vec {
+        let class = if active == Some(category.id.as_str()) { r#" class="active""# } else { "" };
+        links.push_str(&format!(
+            r#"<a href="/shop/category/{}"{}>{}</a>"#,
+            category.id, class, category.name
+        ));
+    }
+    format!(r#"<nav class="category-links">{}</nav>"#, links)
+}
+
+/// Links the tags of a post or product to their archive under `base`, `/blog` or `/shop`
+fn tag_links(tags: &[Tag], base: &str) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let mut links = String::new();
+    for tag in tags {
+        links.push_str(&format!(r#"<a href="{}/tag/{}" class="tag">#{}</a>"#, base, tag.slug, tag.name));
+    }
+    format!(r#"<p class="tag-list">{}</p>"#, links)
+}
+
+/// Tag cloud for the tags of the given items, linking to the archives under `base`
+/// More used tags are shown larger, on a scale from 1 to 5
+fn tag_cloud_html(store: &Store, tag_ids: &[&[String]], base: &str) -> String {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for id in tag_ids.iter().flat_map(|ids| ids.iter()) {
+        *counts.entry(id.as_str()).or_default() += 1;
+    }
+    let ids: Vec<String> = counts.keys().map(|id| id.to_string()).collect();
+    let tags = store.tags_for(&ids);
+    if tags.is_empty() {
+        return String::new();
+    }
+
+    let min = counts.values().copied().min().unwrap_or(1);
+    let max = counts.values().copied().max().unwrap_or(1);
+    let mut links = String::new();
+    for tag in &tags {
+        let count = counts[tag.id.as_str()];
+        let weight = if max == min { 3 } else { 1 + (count - min) * 4 / (max - min) };
+        links.push_str(&format!(
+            r#"<a href="{}/tag/{}" class="tag tag-weight-{}" title="{} {}">{}</a>"#,
+            base, tag.slug, weight, count, if count == 1 { "item" } else { "items" }, tag.name
+        ));
+    }
+    format!(
+        r#"<aside class="tag-cloud">
+            <h3>Tags</h3>
+            {}
+        </aside>"#,
+        links
+    )
+}
+
+/// Active attribute filters parsed from the category page query string
+/// `attr_<id>` may repeat and matches any of the values,
+/// `min_<id>` and `max_<id>` bound number attributes
+#[derive(Default)]
+struct AttributeFilters {
+    values: HashMap<String, Vec<String>>,
+    ranges: HashMap<String, (Option<f64>, Option<f64>)>,
+}
+
+impl AttributeFilters {
+    fn from_query(query: &[(String, String)]) -> Self {
+        let mut filters = Self::default();
+        for (key, value) in query {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            if let Some(id) = key.strip_prefix("attr_") {
+                filters.values.entry(id.to_string()).or_default().push(value.to_string());
+            } else if let (Some(id), Ok(min)) = (key.strip_prefix("min_"), value.parse::<f64>()) {
+                filters.ranges.entry(id.to_string()).or_default().0 = Some(min);
+            } else if let (Some(id), Ok(max)) = (key.strip_prefix("max_"), value.parse::<f64>()) {
+                filters.ranges.entry(id.to_string()).or_default().1 = Some(max);
+            }
+        }
+        filters
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.ranges.is_empty()
+    }
+
+    /// Checks a product against every filter except the one for `skip`
+    /// Skipping lets facet counts show what selecting another value would give
+    fn matches(&self, product: &Product, skip: Option<&str>) -> bool {
+        let values_match = self.values.iter().filter(|(id, _)| Some(id.as_str()) != skip).all(|(id, wanted)| {
+            product.attributes.get(id).map(|v| wanted.contains(&v.raw())).unwrap_or(false)
+        });
+        let ranges_match = self.ranges.iter().filter(|(id, _)| Some(id.as_str()) != skip).all(|(id, (min, max))| {
+            match product.attributes.get(id) {
+                Some(AttributeValue::Number(n)) => min.map(|m| *n >= m).unwrap_or(true) && max.map(|m| *n <= m).unwrap_or(true),
+                _ => false,
+            }
+        });
+        values_match && ranges_match
+    }
+}
+
+/// Public homepage handler
+/// Shows the page chosen as homepage, or the built in welcome page
+pub async fn public_home(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    let currency = selected_currency(&store, &headers);
+    let settings = store.settings();
+    let (content, seo) = match store.home_page() {
+        Some(page) => (page.body, page.seo),
+        None => (default_home_body(&settings), SeoMeta::default()),
+    };
+
+    let meta = PageMeta::new(
+        seo.title_or(&format!("Welcome to {}", settings.site_name)),
+        seo.description_or(&settings.tagline),
+        "/",
+    );
+    Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &meta.head(),
+        "/",
+        &nav_tools(&store, &headers, &currency, "/"),
+        &content,
+    ))
+}
+
+/// Static page handler
+/// Serves published pages at `/<slug>` with their parents as breadcrumbs and links to their subpages
+pub async fn public_static_page(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+) -> AppResult<Response> {
+    let currency = selected_currency(&store, &headers);
+    let page = store.page_by_slug(&slug).filter(|p| p.is_published()).ok_or(AppError::NotFound)?;
+    if store.home_page().is_some_and(|home| home.id == page.id) {
+        return Ok(Redirect::permanent("/").into_response());
+    }
+
+    let path = format!("/{}", page.slug);
+    let ancestors: Vec<_> = store.page_ancestors(&page).into_iter().filter(|p| p.is_published()).collect();
+    let mut trail = vec![("Home", "/".to_string())];
+    trail.extend(ancestors.iter().map(|p| (p.title.as_str(), format!("/{}", p.slug))));
+    let breadcrumbs_html = trail
+        .iter()
+        .map(|(name, href)| format!(r#"<a href="{}">{}</a> / "#, href, name))
+        .collect::<String>();
+    trail.push((page.title.as_str(), path.clone()));
+
+    let mut children: Vec<_> = store
+        .pages
+        .read()?
+        .values()
+        .filter(|p| p.is_published() && p.parent_id.as_deref() == Some(page.id.as_str()))
+        .cloned()
+        .collect();
+    children.sort_by(|a, b| a.title.cmp(&b.title));
+    let children_html = if children.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"<nav class="subpages">
+                <h2>In This Section</h2>
+                <ul>{}</ul>
+            </nav>"#,
+            children
+                .iter()
+                .map(|child| format!(r#"<li><a href="/{}">{}</a></li>"#, child.slug, child.title))
+                .collect::<String>()
+        )
+    };
+
+    let content = format!(
+        r#"<section class="page-header">
+            <p class="breadcrumbs">{}</p>
+            <h1>{}</h1>
+        </section>
+        <article class="page-content">
+            {}
+            {}
+        </article>"#,
+        breadcrumbs_html, page.title, page.body, children_html
+    );
+
+    let meta = PageMeta::new(
+        page.seo.title_or(&format!("{} - {}", page.title, store.site_name())),
+        page.seo.description_or(&summary(&page.body)),
+        path.clone(),
+    )
+    .with_json_ld(breadcrumbs(&trail));
+    Ok(Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &meta.head(),
+        &path,
+        &nav_tools(&store, &headers, &currency, &path),
+        &content,
+    ))
+    .into_response())
+}
+
+/// Shop page handler
+/// Displays all available products organized by category
+pub async fn public_shop(State(store): State<Store>, headers: HeaderMap) -> AppResult<Html<String>> {
+    let currency = selected_currency(&store, &headers);
+    // Rendered before the products lock is taken, it reads the currencies
+    let nav = nav_tools(&store, &headers, &currency, "/shop");
+    let products = store.products.read()?;
+    let categories = store.categories.read()?;
+
+    let mut products_vec: Vec<_> = products.values().filter(|p| p.is_available()).collect();
+    products_vec.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+
+    let mut products_html = String::new();
+    for product in &products_vec {
+        products_html.push_str(&product_card(
+            &store,
+            product,
+            categories.get(&product.category_id),
+            &currency,
+            store.display_tax_rate(product.tax_class),
         ));
     }
+    let tag_ids: Vec<_> = products_vec.iter().map(|p| p.tag_ids.as_slice()).collect();
 
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Shop - MyStore</title>
-    <link rel="stylesheet" href="/static/css/public.css">
-</head>
-<body>
-    <header>
-        <nav class="main-nav">
-            <div class="logo">MyStore CMS</div>
-            <ul>
-                <li><a href="/">Home</a></li>
-                <li><a href="/shop" class="active">Shop</a></li>
-                <li><a href="/blog">Blog</a></li>
-                <li><a href="/admin">Admin</a></li>
-            </ul>
-        </nav>
-    </header>
-    <main>
-        <section class="page-header">
+    let content = format!(
+        r#"<section class="page-header">
             <h1>Our Products</h1>
             <p>Browse our collection of quality products</p>
         </section>
+        {}
         <section class="products-grid">
             {}
         </section>
-    </main>
-    <footer>
-        <p>&copy; 2025 MyStore CMS. All rights reserved.</p>
-    </footer>
-</body>
-</html>"#,
-        products_html
+        {}"#,
+        category_links(&categories, None), products_html, tag_cloud_html(&store, &tag_ids, "/shop")
     );
 
-    Html(html)
+    let meta = PageMeta::new(format!("Shop - {}", store.site_name()), "Browse our collection of quality products", "/shop")
+        .with_json_ld(breadcrumbs(&[("Home", "/".to_string()), ("Shop", "/shop".to_string())]));
+    Ok(Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &meta.head(),
+        "/shop",
+        &nav,
+        &content,
+    )))
 }
 
-/// Blog listing page handler
-/// Shows all published blog posts
-pub async fn public_blog(State(store): State<Store>) -> impl IntoResponse {
-    let posts = store.posts.read().unwrap();
-    let categories = store.blog_categories.read().unwrap();
-    
-    let mut posts_vec: Vec<_> = posts.values().filter(|p| p.published).collect();
-    posts_vec.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+/// Tag shop page handler
+/// Lists the available products with a tag
+pub async fn public_shop_tag(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+) -> AppResult<Html<String>> {
+    let currency = selected_currency(&store, &headers);
+    let tag = match store.tag_by_slug(&slug) {
+        Some(tag) => tag,
+        None => return Err(AppError::NotFound),
+    };
+    let nav = nav_tools(&store, &headers, &currency, &format!("/shop/tag/{}", tag.slug));
+    let products = store.products.read()?;
+    let categories = store.categories.read()?;
 
-    let mut posts_html = String::new();
-    for post in posts_vec {
-        let category_name = categories
-            .get(&post.category_id)
-            .map(|c| c.name.as_str())
-            .unwrap_or("Uncategorized");
+    let mut products_vec: Vec<_> = products
+        .values()
+        .filter(|p| p.is_available() && p.tag_ids.contains(&tag.id))
+        .collect();
+    products_vec.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+
+    let mut products_html = String::new();
+    for product in &products_vec {
+        products_html.push_str(&product_card(
+            &store,
+            product,
+            categories.get(&product.category_id),
+            &currency,
+            store.display_tax_rate(product.tax_class),
+        ));
+    }
+    if products_html.is_empty() {
+        products_html.push_str(r#"<p class="empty">No products with this tag yet.</p>"#);
+    }
+    let all_tag_ids: Vec<_> = products.values().filter(|p| p.is_available()).map(|p| p.tag_ids.as_slice()).collect();
+
+    let content = format!(
+        r#"<section class="page-header">
+            <h1>#{}</h1>
+            <p>Products tagged {}</p>
+        </section>
+        {}
+        <section class="products-grid">
+            {}
+        </section>
+        {}"#,
+        tag.name, tag.name, category_links(&categories, None), products_html,
+        tag_cloud_html(&store, &all_tag_ids, "/shop")
+    );
+
+    let meta = PageMeta::new(
+        format!("{} - {}", tag.name, store.site_name()),
+        format!("Products tagged {}", tag.name),
+        format!("/shop/tag/{}", tag.slug),
+    );
+    Ok(Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &meta.head(),
+        "/shop",
+        &nav,
+        &content,
+    )))
+}
+
+/// Category shop page handler
+/// Lists the products of one category with filters built from its attributes
+pub async fn public_shop_category(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(query): Query<Vec<(String, String)>>,
+) -> AppResult<Html<String>> {
+    let currency = selected_currency(&store, &headers);
+    let nav = nav_tools(&store, &headers, &currency, &format!("/shop/category/{}", id));
+    let products = store.products.read()?;
+    let categories = store.categories.read()?;
+
+    let category = match categories.get(&id) {
+        Some(category) => category,
+        None => return Err(AppError::NotFound),
+    };
 
-        posts_html.push_str(&format!(
-            r#"<article class="blog-card">
-                <h3><a href="/blog/{}">{}</a></h3>
+    let filters = AttributeFilters::from_query(&query);
+    let in_category: Vec<_> = products
+        .values()
+        .filter(|p| p.category_id == category.id && p.is_available())
+        .collect();
+
+    // Build one facet per filterable attribute
+    let mut facets_html = String::new();
+    for attribute in category.attributes.iter().filter(|a| a.filterable) {
+        let candidates: Vec<_> = in_category.iter().filter(|p| filters.matches(p, Some(&attribute.id))).collect();
+
+        let facet_body = if attribute.attribute_type == AttributeType::Number {
+            let (min, max) = filters.ranges.get(&attribute.id).copied().unwrap_or_default();
+            format!(
+                r#"<input type="number" step="any" name="min_{}" value="{}" placeholder="Min">
+                    <input type="number" step="any" name="max_{}" value="{}" placeholder="Max">"#,
+                attribute.id, min.map(|m| m.to_string()).unwrap_or_default(),
+                attribute.id, max.map(|m| m.to_string()).unwrap_or_default()
+            )
+        } else {
+            // Count products per value, keeping the order of select choices
+            let mut counts: Vec<(AttributeValue, usize)> = Vec::new();
+            for value in candidates.iter().filter_map(|p| p.attributes.get(&attribute.id)) {
+                match counts.iter_mut().find(|(v, _)| v == value) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((value.clone(), 1)),
+                }
+            }
+            counts.sort_by_key(|(value, _)| {
+                attribute.choices.iter().position(|c| *c == value.raw()).unwrap_or(usize::MAX)
+            });
+
+            let selected = filters.values.get(&attribute.id);
+            let mut body = String::new();
+            for (value, count) in counts {
+                let checked = if selected.map(|s| s.contains(&value.raw())).unwrap_or(false) { " checked" } else { "" };
+                body.push_str(&format!(
+                    r#"<label><input type="checkbox" name="attr_{}" value="{}"{}> {} ({})</label>"#,
+                    attribute.id, escape_html(&value.raw()), checked, escape_html(&attribute.display(&value)), count
+                ));
+            }
+            body
+        };
+
+        if !facet_body.is_empty() {
+            facets_html.push_str(&format!(
+                r#"<fieldset class="facet">
+                    <legend>{}{}</legend>
+                    {}
+                </fieldset>"#,
+                attribute.name,
+                attribute.unit.as_ref().map(|u| format!(" ({})", u)).unwrap_or_default(),
+                facet_body
+            ));
+        }
+    }
+
+    let mut matching: Vec<_> = in_category.iter().filter(|p| filters.matches(p, None)).collect();
+    matching.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+
+    let mut products_html = String::new();
+    for product in &matching {
+        products_html.push_str(&product_card(&store, product, Some(category), &currency, store.display_tax_rate(product.tax_class)));
+    }
+    if matching.is_empty() {
+        products_html.push_str(r#"<p class="empty">No products match the selected filters.</p>"#);
+    }
+
+    let filters_html = if facets_html.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"<aside class="filters">
+                <form method="get" action="/shop/category/{}">
+                    {}
+                    <button type="submit" class="btn">Apply Filters</button>
+                    {}
+                </form>
+            </aside>"#,
+            category.id, facets_html,
+            if filters.is_empty() {
+                String::new()
+            } else {
+                format!(r#"<a href="/shop/category/{}" class="clear-filters">Clear filters</a>"#, category.id)
+            }
+        )
+    };
+
+    let content = format!(
+        r#"<section class="page-header">
+            <h1>{}</h1>
+            <p>{}</p>
+        </section>
+        {}
+        <div class="shop-layout">
+            {}
+            <section class="products-grid">
+                {}
+            </section>
+        </div>"#,
+        category.name, category.description, category_links(&categories, Some(&category.id)),
+        filters_html, products_html
+    );
+
+    let path = format!("/shop/category/{}", category.id);
+    let meta = PageMeta::new(
+        category.seo.title_or(&format!("{} - {}", category.name, store.site_name())),
+        category.seo.description_or(&category.description),
+        path.clone(),
+    )
+    .with_json_ld(breadcrumbs(&[
+        ("Home", "/".to_string()),
+        ("Shop", "/shop".to_string()),
+        (category.name.as_str(), path),
+    ]));
+    Ok(Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &meta.head(),
+        "/shop",
+        &nav,
+        &content,
+    )))
+}
+
+/// Product detail page handler
+/// Shows a single product with a variant picker for products with options
+pub async fn public_product(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let currency = selected_currency(&store, &headers);
+    let nav = nav_tools(&store, &headers, &currency, &format!("/shop/{}", id));
+    let products = store.products.read()?;
+    let categories = store.categories.read()?;
+
+    let product = match products.get(&id) {
+        Some(product) => product,
+        None => return Err(AppError::NotFound),
+    };
+
+    let tax_rate = store.display_tax_rate(product.tax_class);
+    let tax_note = if *store.prices_include_tax.read()? { "incl. tax" } else { "excl. tax" };
+    let category = categories.get(&product.category_id);
+    let category_html = match category {
+        Some(category) => format!(r#"<a href="/shop/category/{}">{}</a>"#, category.id, category.name),
+        None => "Uncategorized".to_string(),
+    };
+
+    let mut specs_rows = String::new();
+    for attribute in category.map(|c| c.attributes.as_slice()).unwrap_or_default() {
+        if let Some(value) = product.attributes.get(&attribute.id) {
+            specs_rows.push_str(&format!(
+                r#"<tr><th>{}</th><td>{}</td></tr>"#,
+                attribute.name, escape_html(&attribute.display(value))
+            ));
+        }
+    }
+    if product.weight > 0.0 {
+        specs_rows.push_str(&format!(r#"<tr><th>Weight</th><td>{} kg</td></tr>"#, product.weight));
+    }
+    if let Some(dimensions) = product.dimensions {
+        specs_rows.push_str(&format!(r#"<tr><th>Dimensions</th><td>{}</td></tr>"#, dimensions));
+    }
+    let specs_html = if specs_rows.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"<h2>Specifications</h2>
+                <table class="specs">{}</table>"#,
+            specs_rows
+        )
+    };
+
+    let purchase_html = if product.variants.is_empty() {
+        format!(
+            r#"<form method="post" action="/cart/add" class="add-to-cart">
+                    <input type="hidden" name="product_id" value="{}">
+                    <p class="price">{} <small class="tax-note">{}</small></p>
+                    <p class="stock">{}</p>
+                    {}
+                </form>"#,
+            product.id,
+            price_html(
+                with_tax(product.current_price_for(None, &currency), tax_rate),
+                with_tax(product.price_in(&currency), tax_rate),
+                &currency,
+            ),
+            tax_note,
+            if product.is_available() {
+                format!("{} in stock", product.stock)
+            } else {
+                "Out of stock".to_string()
+            },
+            add_to_cart_button(product.is_available())
+        )
+    } else {
+        let first_available = product.variants.iter().find(|v| v.is_available());
+
+        let mut options = String::new();
+        for variant in &product.variants {
+            let price = with_tax(product.current_price_for(Some(variant), &currency), tax_rate);
+            let regular = with_tax(product.regular_price_for(Some(variant), &currency), tax_rate);
+            let state = if !variant.is_available() {
+                " disabled"
+            } else if first_available.map(|v| v.id == variant.id).unwrap_or(false) {
+                " selected"
+            } else {
+                ""
+            };
+            options.push_str(&format!(
+                r#"<option value="{}" data-price="{}" data-regular="{}" data-stock="{}" data-sku="{}"{}>{}{}</option>"#,
+                variant.id, currency.format(price),
+                if price < regular { currency.format(regular) } else { String::new() },
+                variant.stock, variant.sku, state,
+                variant.title(&product.options),
+                if variant.is_available() { "" } else { " (sold out)" }
+            ));
+        }
+
+        let (price, stock, sku) = match first_available {
+            Some(variant) => (
+                price_html(
+                    with_tax(product.current_price_for(Some(variant), &currency), tax_rate),
+                    with_tax(product.regular_price_for(Some(variant), &currency), tax_rate),
+                    &currency,
+                ),
+                format!("{} in stock", variant.stock),
+                variant.sku.clone(),
+            ),
+            None => (
+                currency.format(with_tax(product.price_in(&currency), tax_rate)),
+                "Out of stock".to_string(),
+                String::new(),
+            ),
+        };
+
+        format!(
+            r#"<form method="post" action="/cart/add" class="add-to-cart">
+                    <input type="hidden" name="product_id" value="{}">
+                    <label class="variant-picker">{}
+                        <select name="variant_id" id="variant-picker">{}</select>
+                    </label>
+                    <p class="price"><span id="variant-price">{}</span> <small class="tax-note">{}</small></p>
+                    <p class="stock" id="variant-stock">{}</p>
+                    <p class="sku">SKU: <span id="variant-sku">{}</span></p>
+                    {}
+                </form>
+                <script>
+                    document.getElementById('variant-picker').addEventListener('change', function () {{
+                        var option = this.options[this.selectedIndex];
+                        var price = document.getElementById('variant-price');
+                        price.textContent = option.dataset.price;
+                        if (option.dataset.regular) {{
+                            var regular = document.createElement('del');
+                            regular.textContent = option.dataset.regular;
+                            price.prepend(regular, ' ');
+                        }}
+                        document.getElementById('variant-stock').textContent = option.dataset.stock + ' in stock';
+                        document.getElementById('variant-sku').textContent = option.dataset.sku;
+                    }});
+                </script>"#,
+            product.id,
+            product.options.iter().map(|o| o.name.as_str()).collect::<Vec<_>>().join(" / "),
+            options, price, tax_note, stock, sku,
+            add_to_cart_button(first_available.is_some())
+        )
+    };
+
+    let content = format!(
+        r#"<article class="product-detail">
+            {}
+            <div class="product-info">
+                <h1>{}</h1>
+                <p class="category">{}</p>
+                <p class="description">{}</p>
+                {}
+                {}
+                {}
+                <a href="/shop" class="btn">&larr; Back to Shop</a>
+            </div>
+        </article>"#,
+        product_gallery_html(&store, product),
+        product.name, category_html, product.description, purchase_html, specs_html,
+        tag_links(&store.tags_for(&product.tag_ids), "/shop")
+    );
+
+    let path = format!("/shop/{}", product.id);
+    let image = product.primary_image().map(|image| image.url.as_str());
+    let mut trail = vec![("Home", "/".to_string()), ("Shop", "/shop".to_string())];
+    if let Some(category) = category {
+        trail.push((category.name.as_str(), format!("/shop/category/{}", category.id)));
+    }
+    trail.push((product.name.as_str(), path.clone()));
+    let meta = PageMeta::new(
+        product.seo.title_or(&format!("{} - {}", product.name, store.site_name())),
+        product.seo.description_or(&product.description),
+        path,
+    )
+    .with_kind("product")
+    .with_image(image);
+    let mut product_data = json!({
+        "@context": "https://schema.org",
+        "@type": "Product",
+        "name": product.name,
+        "description": product.description,
+        "url": meta.url(),
+        "category": category.map(|c| c.name.as_str()),
+        "offers": {
+            "@type": "Offer",
+            "url": meta.url(),
+            "price": format!("{:.2}", with_tax(product.current_price_for(None, &currency), tax_rate)),
+            "priceCurrency": currency.code,
+            "availability": if product.is_available() {
+                "https://schema.org/InStock"
+            } else {
+                "https://schema.org/OutOfStock"
+            },
+        },
+    });
+    if let Some(image) = &meta.image {
+        product_data["image"] = json!(image);
+    }
+    let meta = meta.with_json_ld(product_data).with_json_ld(breadcrumbs(&trail));
+    Ok(Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &meta.head(),
+        "/shop",
+        &nav,
+        &content,
+    )))
+}
+
+/// Renders the image of a post, nothing for posts without one
+fn post_image_html(store: &Store, image_url: Option<&str>, alt: &str) -> String {
+    image_url
+        .map(|url| responsive_image(store, url, alt, "post-image", CONTENT_IMAGE_SIZES))
+        .unwrap_or_default()
+}
+
+/// Links an author name to their archive page
+fn author_link(author: Option<&Author>) -> String {
+    match author {
+        Some(author) => format!(r#"<a href="/blog/author/{}">{}</a>"#, author.slug, author.name),
+        None => "Unknown".to_string(),
+    }
+}
+
+/// Renders the avatar of an author, nothing for authors without one
+fn avatar_html(store: &Store, author: &Author) -> String {
+    match &author.avatar_url {
+        Some(url) => format!(
+            r#"<img class="author-avatar" src="{}" alt="{}">"#,
+            store.media_by_url(url).map(|a| a.url_for(ImageSize::Thumbnail)).unwrap_or_else(|| url.clone()),
+            author.name
+        ),
+        None => String::new(),
+    }
+}
+
+/// Renders a post summary for the blog list and archives
+fn blog_card_html(store: &Store, post: &Post, category_name: &str, author: Option<&Author>) -> String {
+    format!(
+        r#"<article class="blog-card">
+                {}<h3><a href="/blog/{}">{}</a></h3>
                 <div class="post-meta">
                     <span class="category">{}</span>
                     <span class="author">by {}</span>
                     <span class="date">{}</span>
+                    <span class="reading-time">{} min read</span>
                 </div>
                 <p class="excerpt">{}</p>
+                {}
                 <a href="/blog/{}" class="read-more">Read More &rarr;</a>
             </article>"#,
-            post.id, post.title, category_name, post.author, 
-            post.created_at.format("%B %d, %Y"), post.excerpt, post.id
-        ));
+        post_image_html(store, post.image_url.as_deref(), &post.title),
+        post.id, post.title, category_name, author_link(author),
+        store.settings().local_time(post.date()).format("%B %d, %Y"), post.reading_time(), post.excerpt,
+        tag_links(&store.tags_for(&post.tag_ids), "/blog"), post.id
+    )
+}
+
+#[derive(Deserialize)]
+pub struct ListingQuery {
+    /// Page of the listing, starting at 1
+    page: Option<usize>,
+}
+
+/// Picks one page of a blog listing, sized by the posts per page setting
+/// Returns the items and the number of pages, pages past the last one don't exist
+fn paginate<T>(store: &Store, items: Vec<T>, page: usize) -> AppResult<(Vec<T>, usize)> {
+    let per_page = store.settings().posts_per_page.max(1);
+    let pages = items.len().div_ceil(per_page).max(1);
+    if page == 0 || page > pages {
+        return Err(AppError::NotFound);
     }
+    Ok((items.into_iter().skip((page - 1) * per_page).take(per_page).collect(), pages))
+}
+
+/// Address of a page of a listing, the first page has no query string
+fn listing_path(path: &str, page: usize) -> String {
+    if page > 1 { format!("{}?page={}", path, page) } else { path.to_string() }
+}
 
-    let html = format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>Blog - MyStore</title>
-    <link rel="stylesheet" href="/static/css/public.css">
-</head>
-<body>
-    <header>
-        <nav class="main-nav">
-            <div class="logo">MyStore CMS</div>
-            <ul>
-                <li><a href="/">Home</a></li>
-                <li><a href="/shop">Shop</a></li>
-                <li><a href="/blog" class="active">Blog</a></li>
-                <li><a href="/admin">Admin</a></li>
-            </ul>
-        </nav>
-    </header>
-    <main>
-        <section class="page-header">
+/// Links to the newer and older pages of a listing
+fn pagination_html(path: &str, page: usize, pages: usize) -> String {
+    if pages <= 1 {
+        return String::new();
+    }
+    let newer = if page > 1 {
+        format!(r#"<a href="{}" class="btn btn-secondary">&larr; Newer Posts</a>"#, listing_path(path, page - 1))
+    } else {
+        String::new()
+    };
+    let older = if page < pages {
+        format!(r#"<a href="{}" class="btn btn-secondary">Older Posts &rarr;</a>"#, listing_path(path, page + 1))
+    } else {
+        String::new()
+    };
+    format!(
+        r#"<nav class="pagination">{}<span>Page {} of {}</span>{}</nav>"#,
+        newer, page, pages, older
+    )
+}
+
+/// Blog listing page handler
+/// Shows the published blog posts, newest first and split into pages
+pub async fn public_blog(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Query(query): Query<ListingQuery>,
+) -> AppResult<Html<String>> {
+    let currency = selected_currency(&store, &headers);
+    let posts = store.posts.read()?;
+    let categories = store.blog_categories.read()?;
+    let authors = store.authors.read()?;
+
+    let mut posts_vec: Vec<_> = posts.values().filter(|p| p.is_published()).collect();
+    posts_vec.sort_by_key(|p| std::cmp::Reverse(p.date()));
+    let tag_ids: Vec<_> = posts_vec.iter().map(|p| p.tag_ids.as_slice()).collect();
+    let page = query.page.unwrap_or(1);
+    let (posts_vec, pages) = paginate(&store, posts_vec, page)?;
+
+    let mut posts_html = String::new();
+    for post in &posts_vec {
+        let category_name = categories
+            .get(&post.category_id)
+            .map(|c| c.name.as_str())
+            .unwrap_or("Uncategorized");
+        posts_html.push_str(&blog_card_html(&store, post, category_name, authors.get(&post.author_id)));
+    }
+
+    let content = format!(
+        r#"<section class="page-header">
             <h1>Our Blog</h1>
             <p>Read our latest articles and updates</p>
         </section>
         <section class="blog-list">
             {}
-        </section>
-    </main>
-    <footer>
-        <p>&copy; 2025 MyStore CMS. All rights reserved.</p>
-    </footer>
-</body>
-</html>"#,
-        posts_html
+            {}
+            {}
+        </section>"#,
+        posts_html, pagination_html("/blog", page, pages), tag_cloud_html(&store, &tag_ids, "/blog")
     );
+    // Menus can link to posts, so the posts lock is released before rendering them
+    drop(posts);
+
+    let meta = PageMeta::new(format!("Blog - {}", store.site_name()), "Read our latest articles and updates", listing_path("/blog", page))
+        .with_json_ld(breadcrumbs(&[("Home", "/".to_string()), ("Blog", "/blog".to_string())]));
+    Ok(Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &format!("{}{}", meta.head(), feed_links(&format!("{} Blog", store.site_name()), "/blog/feed")),
+        "/blog",
+        &nav_tools(&store, &headers, &currency, "/blog"),
+        &content,
+    )))
+}
+
+/// Renders approved comments replying to `parent` with their replies nested below
+/// Comment times are shown in the site timezone
+fn comment_thread_html(settings: &SiteSettings, comments: &[&Comment], parent: Option<&str>) -> String {
+    let mut html = String::new();
+    for comment in comments.iter().filter(|c| c.parent_id.as_deref() == parent) {
+        let replies = comment_thread_html(settings, comments, Some(&comment.id));
+        html.push_str(&format!(
+            r#"<li class="comment" id="comment-{}">
+                    <div class="comment-meta"><strong>{}</strong> <span class="date">{}</span></div>
+                    <p>{}</p>
+                    <button type="button" class="reply-link" data-reply="{}" data-name="{}">Reply</button>
+                    {}
+                </li>"#,
+            comment.id,
+            escape_html(&comment.author_name),
+            settings.local_time(comment.created_at).format("%B %d, %Y at %H:%M"),
+            escape_html(&comment.body).replace('\n', "<br>"),
+            comment.id,
+            escape_html(&comment.author_name),
+            if replies.is_empty() { String::new() } else { format!(r#"<ul class="comment-replies">{}</ul>"#, replies) }
+        ));
+    }
+    html
+}
+
+/// Comment section of a post with the approved comments and the comment form
+fn comments_section_html(store: &Store, post: &Post, notice: Option<&str>) -> String {
+    let comments = store.comments.read().unwrap();
+    let mut approved: Vec<_> = comments
+        .values()
+        .filter(|c| c.post_id == post.id && c.status == CommentStatus::Approved)
+        .collect();
+    approved.sort_by_key(|c| c.created_at);
+
+    let thread = comment_thread_html(&store.settings(), &approved, None);
+    let list = if thread.is_empty() {
+        "<p>No comments yet.</p>".to_string()
+    } else {
+        format!(r#"<ul class="comment-list">{}</ul>"#, thread)
+    };
+    let notice = match notice {
+        Some("pending") => r#"<p class="comment-notice">Thanks! Your comment will appear once it has been approved.</p>"#,
+        _ => "",
+    };
+
+    let form = if post.comments_enabled {
+        format!(
+            r#"<form method="post" action="/blog/{}/comments" class="comment-form" id="comment-form">
+                <h3>Leave a Comment</h3>
+                <p class="replying-to" id="replying-to" hidden>Replying to <strong></strong> <button type="button" id="cancel-reply">Cancel</button></p>
+                <input type="hidden" name="parent_id" id="parent-id" value="">
+                <input type="text" name="author_name" placeholder="Name" required>
+                <input type="email" name="author_email" placeholder="Email (not published)" required>
+                <textarea name="body" placeholder="Your comment" rows="5" required></textarea>
+                <label class="hp" aria-hidden="true">Leave this field empty
+                    <input type="text" name="website" tabindex="-1" autocomplete="off">
+                </label>
+                <label class="notify"><input type="checkbox" name="notify_replies" value="on"> Email me when someone replies</label>
+                <button type="submit" class="btn">Post Comment</button>
+            </form>
+            <script>
+                (function () {{
+                    var parent = document.getElementById('parent-id');
+                    var replying = document.getElementById('replying-to');
+                    document.querySelectorAll('.reply-link').forEach(function (button) {{
+                        button.addEventListener('click', function () {{
+                            parent.value = button.dataset.reply;
+                            replying.querySelector('strong').textContent = button.dataset.name;
+                            replying.hidden = false;
+                            document.getElementById('comment-form').scrollIntoView({{ behavior: 'smooth' }});
+                        }});
+                    }});
+                    document.getElementById('cancel-reply').addEventListener('click', function () {{
+                        parent.value = '';
+                        replying.hidden = true;
+                    }});
+                }})();
+            </script>"#,
+            post.id
+        )
+    } else {
+        r#"<p class="comments-closed">Comments are closed for this post.</p>"#.to_string()
+    };
+
+    format!(
+        r#"<section class="comments" id="comments">
+            <h2>Comments ({})</h2>
+            {}
+            {}
+            {}
+        </section>"#,
+        approved.len(), notice, list, form
+    )
+}
 
-    Html(html)
+#[derive(Deserialize)]
+pub struct BlogPostQuery {
+    /// Set after posting a comment to thank the reader
+    comment: Option<String>,
 }
 
 /// Individual blog post handler
-/// Displays a single blog post with full content
+/// Displays a single blog post with full content and its comments
 pub async fn public_blog_post(
     State(store): State<Store>,
+    headers: HeaderMap,
     Path(id): Path<String>,
-) -> impl IntoResponse {
-    let posts = store.posts.read().unwrap();
-    let categories = store.blog_categories.read().unwrap();
+    Query(query): Query<BlogPostQuery>,
+) -> AppResult<Html<String>> {
+    let currency = selected_currency(&store, &headers);
+    // Cloned so the posts lock isn't held while the menus are rendered
+    let post = store.posts.read()?.get(&id).cloned();
+    let categories = store.blog_categories.read()?;
+    let authors = store.authors.read()?;
 
-    if let Some(post) = posts.get(&id) {
-        if !post.published {
-            return Html("<h1>Post not found</h1>".to_string());
+    if let Some(post) = &post {
+        if !post.is_published() {
+            return Err(AppError::NotFound);
         }
 
         let category_name = categories
             .get(&post.category_id)
             .map(|c| c.name.as_str())
             .unwrap_or("Uncategorized");
+        let author = authors.get(&post.author_id);
+        let author_box = author
+            .map(|author| {
+                format!(
+                    r#"<aside class="author-box">
+                {}
+                <div>
+                    <h3>Written by {}</h3>
+                    <p>{}</p>
+                </div>
+            </aside>"#,
+                    avatar_html(&store, author), author_link(Some(author)), author.bio
+                )
+            })
+            .unwrap_or_default();
 
-        let html = format!(
-            r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>{} - MyStore Blog</title>
-    <link rel="stylesheet" href="/static/css/public.css">
-</head>
-<body>
-    <header>
-        <nav class="main-nav">
-            <div class="logo">MyStore CMS</div>
-            <ul>
-                <li><a href="/">Home</a></li>
-                <li><a href="/shop">Shop</a></li>
-                <li><a href="/blog" class="active">Blog</a></li>
-                <li><a href="/admin">Admin</a></li>
-            </ul>
-        </nav>
-    </header>
-    <main>
-        <article class="blog-post">
+        let content = format!(
+            r#"<article class="blog-post">
             <header class="post-header">
                 <h1>{}</h1>
                 <div class="post-meta">
                     <span class="category">{}</span>
                     <span class="author">by {}</span>
                     <span class="date">{}</span>
+                    <span class="reading-time">{} min read</span>
                 </div>
             </header>
+            {}
             <div class="post-content">
                 <p>{}</p>
             </div>
+            {}
+            {}
+            {}
             <footer class="post-footer">
                 <a href="/blog" class="btn">&larr; Back to Blog</a>
             </footer>
-        </article>
-    </main>
-    <footer>
-        <p>&copy; 2025 MyStore CMS. All rights reserved.</p>
-    </footer>
-</body>
-</html>"#,
-            post.title, post.title, category_name, post.author,
-            post.created_at.format("%B %d, %Y"), post.content
+        </article>"#,
+            post.title, category_name, author_link(author),
+            store.settings().local_time(post.date()).format("%B %d, %Y"), post.reading_time(),
+            post_image_html(&store, post.image_url.as_deref(), &post.title), post.content,
+            tag_links(&store.tags_for(&post.tag_ids), "/blog"), author_box,
+            comments_section_html(&store, post, query.comment.as_deref())
         );
 
-        Html(html)
+        let path = format!("/blog/{}", post.id);
+        let meta = PageMeta::new(
+            post.seo.title_or(&format!("{} - {} Blog", post.title, store.site_name())),
+            post.seo.description_or(&post.excerpt),
+            path.clone(),
+        )
+        .with_kind("article")
+        .with_image(post.image_url.as_deref());
+        let mut post_data = json!({
+            "@context": "https://schema.org",
+            "@type": "BlogPosting",
+            "headline": post.title,
+            "description": meta.description,
+            "url": meta.url(),
+            "mainEntityOfPage": meta.url(),
+            "datePublished": post.date().to_rfc3339_opts(SecondsFormat::Secs, true),
+            "dateModified": post.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "articleSection": category_name,
+            "author": author.map(|author| json!({
+                "@type": "Person",
+                "name": author.name,
+                "url": format!("{}/blog/author/{}", crate::email::site_url(), author.slug),
+            })),
+        });
+        if let Some(image) = &meta.image {
+            post_data["image"] = json!(image);
+        }
+        let meta = meta.with_json_ld(post_data).with_json_ld(breadcrumbs(&[
+            ("Home", "/".to_string()),
+            ("Blog", "/blog".to_string()),
+            (post.title.as_str(), path),
+        ]));
+        Ok(Html(public_page_with_head(
+            &store,
+            &meta.title,
+            &format!("{}{}", meta.head(), feed_links(&format!("{} Blog", store.site_name()), "/blog/feed")),
+            "/blog",
+            &nav_tools(&store, &headers, &currency, &format!("/blog/{}", post.id)),
+            &content,
+        )))
+    } else {
+        Err(AppError::NotFound)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CommentForm {
+    #[serde(default)]
+    parent_id: String,
+    author_name: String,
+    author_email: String,
+    body: String,
+    notify_replies: Option<String>,
+    /// Honeypot, hidden from readers and only filled in by bots
+    #[serde(default)]
+    website: String,
+}
+
+/// Posts a reader comment to the moderation queue
+/// Bots filling the honeypot get the same answer as everyone else, but nothing is stored
+pub async fn public_create_comment(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    Path(id): Path<String>,
+    Form(form): Form<CommentForm>,
+) -> impl IntoResponse {
+    let back = format!("/blog/{}?comment=pending#comments", id);
+    if !form.website.is_empty() {
+        tracing::info!("Dropped a comment from {} that filled the honeypot", address.ip());
+        return Redirect::to(&back).into_response();
+    }
+
+    let comment = Comment::new(
+        id.clone(),
+        Some(form.parent_id).filter(|p| !p.is_empty()),
+        form.author_name.trim().to_string(),
+        form.author_email,
+        form.body.trim().to_string(),
+        form.notify_replies.is_some(),
+    );
+    match store.submit_comment(comment, &address.ip().to_string()) {
+        Ok(_) => Redirect::to(&back).into_response(),
+        Err(error) => {
+            let currency = selected_currency(&store, &headers);
+            let content = format!(
+                r#"<section class="page-header">
+            <h1>Comment Not Posted</h1>
+            <p>{}</p>
+            <a href="/blog/{}#comments" class="btn">&larr; Back to the Post</a>
+        </section>"#,
+                error, id
+            );
+            Html(public_page(
+                &store,
+                &format!("Comment Not Posted - {} Blog", store.site_name()),
+                "/blog",
+                &nav_tools(&store, &headers, &currency, &format!("/blog/{}", id)),
+                &content,
+            ))
+            .into_response()
+        }
+    }
+}
+
+/// Author archive page with the profile and published posts of an author
+pub async fn public_blog_author(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+    Query(query): Query<ListingQuery>,
+) -> AppResult<Html<String>> {
+    let currency = selected_currency(&store, &headers);
+    let author = match store.author_by_slug(&slug) {
+        Some(author) => author,
+        None => return Err(AppError::NotFound),
+    };
+    let posts = store.posts.read()?;
+    let categories = store.blog_categories.read()?;
+
+    let mut posts_vec: Vec<_> = posts.values().filter(|p| p.is_published() && p.author_id == author.id).collect();
+    posts_vec.sort_by_key(|p| std::cmp::Reverse(p.date()));
+    let path = format!("/blog/author/{}", author.slug);
+    let page = query.page.unwrap_or(1);
+    let (posts_vec, pages) = paginate(&store, posts_vec, page)?;
+
+    let mut posts_html = String::new();
+    for post in posts_vec {
+        let category_name = categories
+            .get(&post.category_id)
+            .map(|c| c.name.as_str())
+            .unwrap_or("Uncategorized");
+        posts_html.push_str(&blog_card_html(&store, post, category_name, Some(&author)));
+    }
+    if posts_html.is_empty() {
+        posts_html.push_str("<p>No posts yet.</p>");
+    }
+
+    let content = format!(
+        r#"<section class="page-header author-header">
+            {}
+            <h1>{}</h1>
+            <p>{}</p>
+        </section>
+        <section class="blog-list">
+            {}
+            {}
+        </section>"#,
+        avatar_html(&store, &author), author.name, author.bio, posts_html, pagination_html(&path, page, pages)
+    );
+    drop(posts);
+
+    let meta = PageMeta::new(
+        format!("{} - {} Blog", author.name, store.site_name()),
+        author.bio.clone(),
+        listing_path(&path, page),
+    )
+    .with_kind("profile")
+    .with_image(author.avatar_url.as_deref());
+    Ok(Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &format!("{}{}", meta.head(), feed_links(&format!("{} Blog", store.site_name()), "/blog/feed")),
+        "/blog",
+        &nav_tools(&store, &headers, &currency, &meta.path),
+        &content,
+    )))
+}
+
+/// Tag archive page with the published posts of a tag
+pub async fn public_blog_tag(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+    Query(query): Query<ListingQuery>,
+) -> AppResult<Html<String>> {
+    let currency = selected_currency(&store, &headers);
+    let tag = match store.tag_by_slug(&slug) {
+        Some(tag) => tag,
+        None => return Err(AppError::NotFound),
+    };
+    let posts = store.posts.read()?;
+    let categories = store.blog_categories.read()?;
+    let authors = store.authors.read()?;
+
+    let mut posts_vec: Vec<_> = posts.values().filter(|p| p.is_published() && p.tag_ids.contains(&tag.id)).collect();
+    posts_vec.sort_by_key(|p| std::cmp::Reverse(p.date()));
+    let path = format!("/blog/tag/{}", tag.slug);
+    let page = query.page.unwrap_or(1);
+    let (posts_vec, pages) = paginate(&store, posts_vec, page)?;
+
+    let mut posts_html = String::new();
+    for post in posts_vec {
+        let category_name = categories
+            .get(&post.category_id)
+            .map(|c| c.name.as_str())
+            .unwrap_or("Uncategorized");
+        posts_html.push_str(&blog_card_html(&store, post, category_name, authors.get(&post.author_id)));
+    }
+    if posts_html.is_empty() {
+        posts_html.push_str("<p>No posts yet.</p>");
+    }
+    let all_tag_ids: Vec<_> = posts.values().filter(|p| p.is_published()).map(|p| p.tag_ids.as_slice()).collect();
+
+    let content = format!(
+        r#"<section class="page-header">
+            <h1>#{}</h1>
+            <p>Posts tagged {}</p>
+        </section>
+        <section class="blog-list">
+            {}
+            {}
+            {}
+        </section>"#,
+        tag.name, tag.name, posts_html, pagination_html(&path, page, pages), tag_cloud_html(&store, &all_tag_ids, "/blog")
+    );
+    drop(posts);
+
+    let meta = PageMeta::new(
+        format!("{} - {} Blog", tag.name, store.site_name()),
+        format!("Posts tagged {}", tag.name),
+        listing_path(&path, page),
+    );
+    Ok(Html(public_page_with_head(
+        &store,
+        &meta.title,
+        &format!(
+            "{}{}{}",
+            meta.head(),
+            feed_links(&format!("{} Blog", store.site_name()), "/blog/feed"),
+            feed_links(&format!("{} Blog - {}", store.site_name(), tag.name), &format!("/blog/tag/{}/feed", tag.slug))
+        ),
+        "/blog",
+        &nav_tools(&store, &headers, &currency, &meta.path),
+        &content,
+    )))
+}
+
+#[derive(Deserialize)]
+pub struct CurrencyQuery {
+    code: String,
+    return_to: Option<String>,
+}
+
+/// Switches the display currency
+/// Remembers the choice in a cookie and sends the visitor back
+pub async fn public_set_currency(
+    State(store): State<Store>,
+    Query(query): Query<CurrencyQuery>,
+) -> impl IntoResponse {
+    // Only allow local paths so the switcher can't be used as an open redirect,
+    // browsers treat both `//host` and `/\host` as another site
+    let return_to = query
+        .return_to
+        .filter(|path| path.starts_with('/') && !path[1..].starts_with(['/', '\\']))
+        .unwrap_or_else(|| "/".to_string());
+
+    if !store.currencies.read().unwrap().contains_key(&query.code) {
+        return Redirect::to(&return_to).into_response();
+    }
+
+    let cookie = format!(
+        "{}={}; Path=/; Max-Age=31536000; SameSite=Lax",
+        CURRENCY_COOKIE, query.code
+    );
+    ([(header::SET_COOKIE, cookie)], Redirect::to(&return_to)).into_response()
+}
+
+/// Renders the totals block shared by the cart and checkout pages
+/// Shipping is shown once picked, taxes are added as lines, or noted as included when prices are displayed including tax
+fn cart_totals_html(summary: &CartSummary, currency: &Currency) -> String {
+    let (subtotal, discount) = if summary.prices_include_tax {
+        (summary.subtotal_with_tax(), summary.discount_with_tax())
+    } else {
+        (summary.subtotal, summary.discount)
+    };
+
+    let mut rows = format!(
+        r#"<tr><th>Subtotal</th><td>{}</td></tr>"#,
+        currency.format(subtotal)
+    );
+    if let Some(coupon) = &summary.coupon {
+        if discount > 0.0 {
+            rows.push_str(&format!(
+                r#"<tr><th>Discount ({})</th><td>-{}</td></tr>"#,
+                escape_html(&coupon.code), currency.format(discount)
+            ));
+        }
+    }
+    rows.push_str(&match (&summary.shipping, &summary.coupon) {
+        (Some(shipping), _) => format!(
+            r#"<tr><th>Shipping ({})</th><td>{}</td></tr>"#,
+            shipping.name,
+            if shipping.cost > 0.0 { currency.format(shipping.cost) } else { "Free".to_string() }
+        ),
+        (None, Some(coupon)) if summary.free_shipping => {
+            format!(r#"<tr><th>Shipping ({})</th><td>Free</td></tr>"#, escape_html(&coupon.code))
+        }
+        (None, _) => r#"<tr><th>Shipping</th><td>Calculated at checkout</td></tr>"#.to_string(),
+    });
+    if !summary.prices_include_tax {
+        rows.push_str(&tax_lines_html(&summary.tax_lines, currency, false));
+    }
+    rows.push_str(&format!(
+        r#"<tr class="total"><th>Total</th><td>{}</td></tr>"#,
+        currency.format(summary.total)
+    ));
+    if summary.prices_include_tax {
+        rows.push_str(&tax_lines_html(&summary.tax_lines, currency, true));
+    }
+    format!(r#"<table class="cart-totals">{}</table>"#, rows)
+}
+
+/// Renders tax breakdown rows for a totals table
+fn tax_lines_html(tax_lines: &[TaxLine], currency: &Currency, included: bool) -> String {
+    let mut rows = String::new();
+    for tax_line in tax_lines {
+        rows.push_str(&format!(
+            r#"<tr class="tax-line"><th>{}{}</th><td>{}</td></tr>"#,
+            if included { "Includes " } else { "" },
+            tax_line.label(),
+            currency.format(tax_line.amount)
+        ));
+    }
+    rows
+}
+
+#[derive(Deserialize)]
+pub struct AddToCartForm {
+    product_id: String,
+    variant_id: Option<String>,
+    quantity: Option<i32>,
+}
+
+/// Adds a product to the visitor's cart
+/// Creates the cart and its cookie on first use
+pub async fn public_add_to_cart(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<AddToCartForm>,
+) -> AppResult<Response> {
+    let quantity = form.quantity.unwrap_or(1).clamp(1, MAX_LINE_QUANTITY);
+    let variant_id = form.variant_id.filter(|v| !v.is_empty());
+    {
+        // Products with variants can only be bought as one of them
+        let products = store.products.read()?;
+        let product = products.get(&form.product_id).ok_or(AppError::NotFound)?;
+        let known = match &variant_id {
+            Some(variant_id) => product.variants.iter().any(|v| &v.id == variant_id),
+            None => product.variants.is_empty(),
+        };
+        if !known {
+            return Err(AppError::NotFound);
+        }
+    }
+
+    let mut carts = store.carts.write()?;
+    let existing = cart_id(&headers).filter(|id| carts.contains_key(id));
+    let (id, is_new) = match existing {
+        Some(id) => (id, false),
+        None => {
+            let cart = Cart::new();
+            let id = cart.id.clone();
+            carts.insert(id.clone(), cart);
+            (id, true)
+        }
+    };
+    if let Some(cart) = carts.get_mut(&id) {
+        cart.add(form.product_id, variant_id, quantity);
+    }
+
+    if is_new {
+        let cookie = format!("{}={}; Path=/; Max-Age=2592000; HttpOnly; SameSite=Lax", CART_COOKIE, id);
+        Ok(([(header::SET_COOKIE, cookie)], Redirect::to("/cart")).into_response())
     } else {
-        Html("<h1>Post not found</h1>".to_string())
+        Ok(Redirect::to("/cart").into_response())
     }
 }
 
+/// Cart page handler
+/// Lists the cart items with quantities, coupon entry and totals
+pub async fn public_cart(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    let currency = selected_currency(&store, &headers);
+    let cart = cart_id(&headers)
+        .and_then(|id| store.carts.read().unwrap().get(&id).cloned())
+        .unwrap_or_default();
+    let summary = store.summarize_cart(&cart, &currency);
+
+    let body = if summary.lines.is_empty() {
+        r#"<p class="empty">Your cart is empty. <a href="/shop">Continue shopping</a></p>"#.to_string()
+    } else {
+        let mut rows = String::new();
+        for line in &summary.lines {
+            let stock_warning = if line.quantity > line.available {
+                format!(r#"<br><span class="warning">Only {} available</span>"#, line.available)
+            } else {
+                String::new()
+            };
+            rows.push_str(&format!(
+                r#"<tr>
+                    <td><a href="/shop/{}">{}</a>{}{}</td>
+                    <td>{}</td>
+                    <td>
+                        <form method="post" action="/cart/update" class="inline-form">
+                            <input type="hidden" name="product_id" value="{}">
+                            <input type="hidden" name="variant_id" value="{}">
+                            <input type="number" name="quantity" value="{}" min="0" max="{}">
+                            <button type="submit" class="btn btn-small">Update</button>
+                        </form>
+                    </td>
+                    <td>{}</td>
+                </tr>"#,
+                line.product_id, line.name,
+                line.sku.as_ref().map(|sku| format!(r#"<br><small>SKU: {}</small>"#, sku)).unwrap_or_default(),
+                stock_warning,
+                price_html(
+                    if summary.prices_include_tax { line.with_tax(line.unit_price) } else { line.unit_price },
+                    if summary.prices_include_tax { line.with_tax(line.regular_price) } else { line.regular_price },
+                    &currency,
+                ),
+                line.product_id, line.variant_id.as_deref().unwrap_or_default(), line.quantity, MAX_LINE_QUANTITY,
+                currency.format(if summary.prices_include_tax { line.with_tax(line.total) } else { line.total })
+            ));
+        }
+
+        let coupon_html = match (&summary.coupon, &cart.coupon_code) {
+            (Some(coupon), _) => format!(
+                r#"<form method="post" action="/cart/coupon/remove" class="coupon-form">
+                    <p>Coupon <strong>{}</strong> applied: {}</p>
+                    <button type="submit" class="btn btn-small">Remove</button>
+                </form>"#,
+                escape_html(&coupon.code), coupon.describe(&currency)
+            ),
+            (None, code) => format!(
+                r#"<form method="post" action="/cart/coupon" class="coupon-form">
+                    {}
+                    <input type="text" name="code" placeholder="Coupon code" value="{}">
+                    <button type="submit" class="btn btn-small">Apply</button>
+                </form>"#,
+                summary
+                    .coupon_error
+                    .as_ref()
+                    .map(|e| format!(r#"<p class="warning">{}</p>"#, e))
+                    .unwrap_or_default(),
+                escape_html(code.as_deref().unwrap_or(""))
+            ),
+        };
+
+        format!(
+            r#"<table class="cart-table">
+                <thead>
+                    <tr>
+                        <th>Product</th>
+                        <th>Price</th>
+                        <th>Quantity</th>
+                        <th>Total</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {}
+                </tbody>
+            </table>
+            <div class="cart-summary">
+                {}
+                {}
+                <a href="/checkout" class="btn btn-primary">Proceed to Checkout</a>
+            </div>"#,
+            rows, coupon_html, cart_totals_html(&summary, &currency)
+        )
+    };
+
+    let content = format!(
+        r#"<section class="page-header">
+            <h1>Your Cart</h1>
+        </section>
+        <section class="cart">
+            {}
+        </section>"#,
+        body
+    );
+
+    Html(public_page(
+        &store,
+        &format!("Cart - {}", store.site_name()),
+        "/cart",
+        &nav_tools(&store, &headers, &currency, "/cart"),
+        &content,
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct CartQuantityForm {
+    product_id: String,
+    variant_id: Option<String>,
+    quantity: i32,
+}
+
+/// Changes the quantity of a cart line, removing it at zero
+pub async fn public_update_cart(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<CartQuantityForm>,
+) -> impl IntoResponse {
+    let variant_id = form.variant_id.as_deref().filter(|v| !v.is_empty());
+    if let Some(id) = cart_id(&headers) {
+        if let Some(cart) = store.carts.write().unwrap().get_mut(&id) {
+            cart.set_quantity(&form.product_id, variant_id, form.quantity);
+        }
+    }
+    Redirect::to("/cart")
+}
+
+#[derive(Deserialize)]
+pub struct CouponForm {
+    code: String,
+}
+
+/// Applies a coupon code to the cart
+/// The code is validated whenever the cart is priced
+pub async fn public_apply_coupon(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<CouponForm>,
+) -> impl IntoResponse {
+    if let Some(id) = cart_id(&headers) {
+        if let Some(cart) = store.carts.write().unwrap().get_mut(&id) {
+            let code = form.code.trim().to_uppercase();
+            cart.coupon_code = if code.is_empty() { None } else { Some(code) };
+        }
+    }
+    Redirect::to("/cart")
+}
+
+/// Removes the coupon from the cart
+pub async fn public_remove_coupon(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    if let Some(id) = cart_id(&headers) {
+        if let Some(cart) = store.carts.write().unwrap().get_mut(&id) {
+            cart.coupon_code = None;
+        }
+    }
+    Redirect::to("/cart")
+}
+
+#[derive(Deserialize, Default)]
+pub struct CheckoutForm {
+    name: String,
+    email: String,
+    address: String,
+    /// Set when a logged in customer wants the address in their address book
+    save_address: Option<String>,
+}
+
+/// Renders the checkout page with the order summary and customer form
+fn checkout_page(store: &Store, headers: &HeaderMap, form: &CheckoutForm, error: Option<&str>) -> String {
+    let currency = selected_currency(store, headers);
+    let cart = cart_id(headers)
+        .and_then(|id| store.carts.read().unwrap().get(&id).cloned())
+        .unwrap_or_default();
+    let summary = store.summarize_cart(&cart, &currency);
+    let customer = current_customer(store, headers);
+
+    // Logged in customers get their details filled in and can pick a saved address
+    let (name, email) = match &customer {
+        Some(customer) if form.email.is_empty() => (customer.name.clone(), customer.email.clone()),
+        _ => (form.name.clone(), form.email.clone()),
+    };
+    let saved_address_html = match &customer {
+        Some(customer) => {
+            let mut options = String::new();
+            for address in &customer.addresses {
+                options.push_str(&format!(
+                    r#"<option data-recipient="{}" data-address="{}">{}</option>"#,
+                    escape_html(&address.recipient), escape_html(&address.address), escape_html(&address.label)
+                ));
+            }
+            if options.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    r#"<label>Saved Address
+                    <select id="saved-address">
+                        <option value="">Enter a new address</option>
+                        {}
+                    </select>
+                </label>
+                <script>
+                    document.getElementById('saved-address').addEventListener('change', function () {{
+                        var option = this.options[this.selectedIndex];
+                        if (option.dataset.address) {{
+                            this.form.elements.name.value = option.dataset.recipient;
+                            this.form.elements.address.value = option.dataset.address;
+                        }}
+                    }});
+                </script>"#,
+                    options
+                )
+            }
+        }
+        None => r#"<p><a href="/account/login">Log in</a> to use your saved addresses, or check out as a guest.</p>"#
+            .to_string(),
+    };
+
+    let save_address_html = if customer.is_some() {
+        r#"<label class="checkbox"><input type="checkbox" name="save_address" value="yes"> Save this address to my account</label>"#
+    } else {
+        ""
+    };
+
+    let mut region_options = String::new();
+    let mut regions = store.regions();
+    if !regions.iter().any(|(code, _)| *code == summary.region) {
+        regions.push((summary.region.clone(), summary.region.clone()));
+    }
+    for (code, name) in regions {
+        let selected = if code == summary.region { " selected" } else { "" };
+        region_options.push_str(&format!(r#"<option value="{}"{}>{}</option>"#, escape_html(&code), selected, escape_html(&name)));
+    }
+
+    let shipping_html = if summary.shipping_options.is_empty() {
+        r#"<p class="warning">We don't ship to this region yet.</p>"#.to_string()
+    } else {
+        let mut options = String::new();
+        for quote in &summary.shipping_options {
+            let checked = if summary.shipping.as_ref().map(|s| s.method_id == quote.method_id).unwrap_or(false) {
+                " checked"
+            } else {
+                ""
+            };
+            options.push_str(&format!(
+                r#"<label><input type="radio" name="method_id" value="{}" onchange="this.form.submit()"{}> {} &ndash; {}</label>"#,
+                quote.method_id, checked, quote.name,
+                if quote.cost > 0.0 { currency.format(quote.cost) } else { "Free".to_string() }
+            ));
+        }
+        format!(
+            r#"<form method="post" action="/checkout/shipping" class="shipping-form">
+                <h3>Shipping Method</h3>
+                {}
+                <noscript><button type="submit" class="btn btn-small">Update</button></noscript>
+            </form>"#,
+            options
+        )
+    };
+
+    let mut items = String::new();
+    for line in &summary.lines {
+        items.push_str(&format!(
+            r#"<tr><td>{} &times; {}</td><td>{}</td></tr>"#,
+            line.quantity, line.name,
+            currency.format(if summary.prices_include_tax { line.with_tax(line.total) } else { line.total })
+        ));
+    }
+
+    let content = format!(
+        r#"<section class="page-header">
+            <h1>Checkout</h1>
+        </section>
+        <section class="checkout">
+            {}
+            <form method="post" action="/checkout/region" class="inline-form region-form">
+                <label>Shipping region
+                    <select name="region" onchange="this.form.submit()">{}</select>
+                </label>
+                <noscript><button type="submit" class="btn btn-small">Update</button></noscript>
+            </form>
+            {}
+            <form method="post" action="/checkout" class="checkout-form">
+                {}
+                <label>Full Name
+                    <input type="text" name="name" value="{}" required>
+                </label>
+                <label>Email
+                    <input type="email" name="email" value="{}" required>
+                </label>
+                <label>Shipping Address
+                    <textarea name="address" rows="4" required>{}</textarea>
+                </label>
+                {}
+                <button type="submit" class="btn btn-primary">Place Order</button>
+            </form>
+            <div class="cart-summary">
+                <table class="cart-totals">{}</table>
+                {}
+                <a href="/cart">&larr; Back to Cart</a>
+            </div>
+        </section>"#,
+        error.map(|e| format!(r#"<p class="warning">{}</p>"#, e)).unwrap_or_default(),
+        region_options,
+        shipping_html,
+        saved_address_html,
+        escape_html(&name), escape_html(&email), escape_html(&form.address),
+        save_address_html,
+        items, cart_totals_html(&summary, &currency)
+    );
+
+    public_page(
+        store,
+        &format!("Checkout - {}", store.site_name()),
+        "/cart",
+        &nav_tools(store, headers, &currency, "/checkout"),
+        &content,
+    )
+}
+
+/// Checkout page handler
+pub async fn public_checkout(State(store): State<Store>, headers: HeaderMap) -> impl IntoResponse {
+    let has_items = cart_id(&headers)
+        .and_then(|id| store.carts.read().unwrap().get(&id).map(|c| !c.items.is_empty()))
+        .unwrap_or(false);
+    if !has_items {
+        return Redirect::to("/cart").into_response();
+    }
+    Html(checkout_page(&store, &headers, &CheckoutForm::default(), None)).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct RegionForm {
+    region: String,
+}
+
+/// Sets the region the cart ships to
+/// Taxes in the checkout totals follow the region
+pub async fn public_set_region(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<RegionForm>,
+) -> impl IntoResponse {
+    let region = form.region.trim().to_uppercase();
+    if !store.regions().iter().any(|(code, _)| *code == region) {
+        return Redirect::to("/checkout");
+    }
+    if let Some(id) = cart_id(&headers) {
+        if let Some(cart) = store.carts.write().unwrap().get_mut(&id) {
+            cart.region = Some(region);
+        }
+    }
+    Redirect::to("/checkout")
+}
+
+#[derive(Deserialize)]
+pub struct ShippingForm {
+    method_id: String,
+}
+
+/// Picks the shipping method of the cart
+pub async fn public_set_shipping(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<ShippingForm>,
+) -> impl IntoResponse {
+    if let Some(id) = cart_id(&headers) {
+        if let Some(cart) = store.carts.write().unwrap().get_mut(&id) {
+            cart.shipping_method_id = Some(form.method_id);
+        }
+    }
+    Redirect::to("/checkout")
+}
+
+/// Places the order for the current cart
+pub async fn public_place_order(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Form(form): Form<CheckoutForm>,
+) -> impl IntoResponse {
+    let currency = selected_currency(&store, &headers);
+    let customer = current_customer(&store, &headers);
+    let result = match cart_id(&headers) {
+        Some(id) => store.place_order(
+            &id,
+            &currency,
+            customer.as_ref().map(|c| c.id.clone()),
+            form.name.trim().to_string(),
+            form.email.trim().to_string(),
+            form.address.trim().to_string(),
+        ),
+        None => Err("Your cart is empty".to_string()),
+    };
+
+    match result {
+        Ok(order) => {
+            if let (Some(customer), Some(_)) = (&customer, &form.save_address) {
+                if let Some(customer) = store.customers.write().unwrap().get_mut(&customer.id) {
+                    customer.addresses.push(Address::new(
+                        format!("Order #{}", order.number),
+                        order.customer_name.clone(),
+                        order.address.clone(),
+                        order.region.clone(),
+                    ));
+                }
+            }
+            Redirect::to(&format!("/order/{}", order.id)).into_response()
+        }
+        Err(error) => Html(checkout_page(&store, &headers, &form, Some(&error))).into_response(),
+    }
+}
+
+/// Order confirmation page
+/// The order id is random, so the link itself is the access token
+pub async fn public_order(
+    State(store): State<Store>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> AppResult<Html<String>> {
+    let currency_for_nav = selected_currency(&store, &headers);
+    let orders = store.orders.read()?;
+    let order = match orders.get(&id) {
+        Some(order) => order,
+        None => return Err(AppError::NotFound),
+    };
+    let content = format!(
+        r#"<section class="page-header">
+            <h1>Thank you for your order!</h1>
+            <p>Order #{} &middot; {}</p>
+        </section>
+        <section class="order-confirmation">
+            <p>We'll keep you updated at {}.</p>
+            {}
+            <a href="/shop" class="btn">Continue Shopping</a>
+        </section>"#,
+        order.number, order.status.label(), escape_html(&order.email), order_details_html(&store, order)
+    );
+
+    Ok(Html(public_page(
+        &store,
+        &format!("Order #{} - {}", order.number, store.site_name()),
+        "/shop",
+        &nav_tools(&store, &headers, &currency_for_nav, &format!("/order/{}", order.id)),
+        &content,
+    )))
+}
+
+/// Renders the items, totals and shipping address of a placed order
+pub(crate) fn order_details_html(store: &Store, order: &Order) -> String {
+    let currency = store.currency_or_base(Some(&order.currency_code));
+
+    let mut rows = String::new();
+    for item in &order.items {
+        rows.push_str(&format!(
+            r#"<tr><td>{} &times; {}</td><td>{}</td></tr>"#,
+            item.quantity, item.name, currency.format(item.total)
+        ));
+    }
+    rows.push_str(&format!(r#"<tr><th>Subtotal</th><td>{}</td></tr>"#, currency.format(order.subtotal)));
+    if order.discount > 0.0 {
+        rows.push_str(&format!(
+            r#"<tr><th>Discount ({})</th><td>-{}</td></tr>"#,
+            escape_html(order.coupon_code.as_deref().unwrap_or("")),
+            currency.format(order.discount)
+        ));
+    }
+    if let Some(method) = &order.shipping_method {
+        rows.push_str(&format!(
+            r#"<tr><th>Shipping ({})</th><td>{}</td></tr>"#,
+            method,
+            if order.shipping > 0.0 { currency.format(order.shipping) } else { "Free".to_string() }
+        ));
+    }
+    rows.push_str(&tax_lines_html(&order.tax_lines, &currency, false));
+    rows.push_str(&format!(r#"<tr class="total"><th>Total</th><td>{}</td></tr>"#, currency.format(order.total)));
+
+    format!(
+        r#"<table class="cart-totals">{}</table>
+            <h3>Shipping to</h3>
+            <p class="address">{}</p>"#,
+        rows, escape_html(&order.address).replace('\n', "<br>")
+    )
+}
@@ -0,0 +1,154 @@
+//! Search engine and social sharing tags of public pages
+//! Renders the meta description, canonical link, Open Graph and Twitter card tags and JSON-LD data
+
+use serde_json::{json, Value};
+use crate::email::site_url;
+use super::layout::escape_html;
+
+/// Head tags of one public page
+pub struct PageMeta {
+    pub title: String,
+    pub description: String,
+    /// Path of the canonical URL, with a query string only for later pages of a listing
+    pub path: String,
+    /// Image shown when the page is shared
+    pub image: Option<String>,
+    /// Open Graph type, like `website`, `product` or `article`
+    pub kind: &'static str,
+    /// schema.org objects embedded as JSON-LD
+    pub json_ld: Vec<Value>,
+}
+
+impl PageMeta {
+    /// Creates the tags of a plain website page
+    pub fn new(title: impl Into<String>, description: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            description: description.into(),
+            path: path.into(),
+            image: None,
+            kind: "website",
+            json_ld: Vec::new(),
+        }
+    }
+
+    pub fn with_image(mut self, image: Option<&str>) -> Self {
+        self.image = image.map(absolute_url);
+        self
+    }
+
+    pub fn with_kind(mut self, kind: &'static str) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_json_ld(mut self, data: Value) -> Self {
+        self.json_ld.push(data);
+        self
+    }
+
+    /// Absolute canonical URL of the page
+    pub fn url(&self) -> String {
+        absolute_url(&self.path)
+    }
+
+    /// Renders the tags for the page `<head>`
+    pub fn head(&self) -> String {
+        let title = escape_html(&self.title);
+        let description = escape_html(&self.description);
+        let url = escape_html(&self.url());
+        let mut head = format!(
+            r#"
+    <meta name="description" content="{}">
+    <link rel="canonical" href="{}">
+    <meta property="og:type" content="{}">
+    <meta property="og:title" content="{}">
+    <meta property="og:description" content="{}">
+    <meta property="og:url" content="{}">
+    <meta name="twitter:card" content="{}">
+    <meta name="twitter:title" content="{}">
+    <meta name="twitter:description" content="{}">"#,
+            description, url, self.kind, title, description, url,
+            if self.image.is_some() { "summary_large_image" } else { "summary" },
+            title, description
+        );
+        if let Some(image) = &self.image {
+            let image = escape_html(image);
+            head.push_str(&format!(
+                r#"
+    <meta property="og:image" content="{}">
+    <meta name="twitter:image" content="{}">"#,
+                image, image
+            ));
+        }
+        for data in &self.json_ld {
+            // A `</script>` inside a string value would end the script element early
+            head.push_str(&format!(
+                r#"
+    <script type="application/ld+json">{}</script>"#,
+                data.to_string().replace("</", "<\\/")
+            ));
+        }
+        head
+    }
+}
+
+/// Turns a site path into an absolute URL, leaving full URLs as they are
+pub fn absolute_url(path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else {
+        format!("{}{}", site_url(), path)
+    }
+}
+
+/// Plain text start of an HTML text for a meta description, about 160 characters long
+pub fn summary(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let words: Vec<_> = text.split_whitespace().collect();
+    let mut summary = String::new();
+    for word in words {
+        if summary.chars().count() + word.chars().count() > 155 {
+            summary.push_str("...");
+            break;
+        }
+        if !summary.is_empty() {
+            summary.push(' ');
+        }
+        summary.push_str(word);
+    }
+    summary
+}
+
+/// schema.org `BreadcrumbList` from `(name, path)` pairs, starting at the homepage
+pub fn breadcrumbs(items: &[(&str, String)]) -> Value {
+    let elements: Vec<Value> = items
+        .iter()
+        .enumerate()
+        .map(|(index, (name, path))| {
+            json!({
+                "@type": "ListItem",
+                "position": index + 1,
+                "name": name,
+                "item": absolute_url(path),
+            })
+        })
+        .collect();
+    json!({
+        "@context": "https://schema.org",
+        "@type": "BreadcrumbList",
+        "itemListElement": elements,
+    })
+}
@@ -0,0 +1,141 @@
+//! Sitemap and robots.txt for search engines
+//! Large sitemaps are split into numbered files listed by a sitemap index
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use crate::email::site_url;
+use crate::store::Store;
+use super::layout::escape_html;
+use super::error::{AppError, AppResult};
+
+/// Most URLs in one sitemap file, the limit of the sitemap protocol
+const SITEMAP_LIMIT: usize = 50_000;
+
+/// Page listed in the sitemap
+struct SitemapUrl {
+    path: String,
+    lastmod: Option<DateTime<Utc>>,
+}
+
+/// Every public page worth indexing
+fn sitemap_urls(store: &Store) -> AppResult<Vec<SitemapUrl>> {
+    let products = store.products.read()?;
+    let categories = store.categories.read()?;
+    let posts = store.posts.read()?;
+    let published: Vec<_> = posts.values().filter(|p| p.is_published()).collect();
+
+    let home = store.home_page();
+    let mut urls = vec![
+        SitemapUrl { path: "/".to_string(), lastmod: home.as_ref().map(|p| p.updated_at) },
+        SitemapUrl { path: "/shop".to_string(), lastmod: products.values().map(|p| p.updated_at).max() },
+        SitemapUrl { path: "/blog".to_string(), lastmod: published.iter().map(|p| p.updated_at).max() },
+    ];
+
+    let mut categories_vec: Vec<_> = categories.values().collect();
+    categories_vec.sort_by_key(|c| c.created_at);
+    for category in categories_vec {
+        // A category page changes whenever one of its products does
+        let newest_product = products.values().filter(|p| p.category_id == category.id).map(|p| p.updated_at).max();
+        urls.push(SitemapUrl {
+            path: format!("/shop/category/{}", category.id),
+            lastmod: newest_product.max(Some(category.created_at)),
+        });
+    }
+
+    let mut products_vec: Vec<_> = products.values().collect();
+    products_vec.sort_by_key(|p| p.created_at);
+    for product in products_vec {
+        urls.push(SitemapUrl { path: format!("/shop/{}", product.id), lastmod: Some(product.updated_at) });
+    }
+
+    for (_, page) in store.page_tree() {
+        if page.is_published() && home.as_ref().map(|h| h.id != page.id).unwrap_or(true) {
+            urls.push(SitemapUrl { path: format!("/{}", page.slug), lastmod: Some(page.updated_at) });
+        }
+    }
+
+    let mut posts_vec = published;
+    posts_vec.sort_by_key(|p| p.date());
+    for post in posts_vec {
+        urls.push(SitemapUrl { path: format!("/blog/{}", post.id), lastmod: Some(post.updated_at) });
+    }
+    Ok(urls)
+}
+
+/// Renders a `<urlset>` sitemap
+fn urlset(urls: &[SitemapUrl]) -> String {
+    let site = site_url();
+    let mut entries = String::new();
+    for url in urls {
+        entries.push_str(&format!(
+            "\n  <url>\n    <loc>{}{}</loc>{}\n  </url>",
+            site,
+            escape_html(&url.path),
+            url.lastmod
+                .map(|at| format!("\n    <lastmod>{}</lastmod>", at.to_rfc3339_opts(SecondsFormat::Secs, true)))
+                .unwrap_or_default()
+        ));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{}
+</urlset>
+"#,
+        entries
+    )
+}
+
+/// Sitemap of the site, or a sitemap index when there are too many pages for one file
+pub async fn sitemap(State(store): State<Store>) -> AppResult<impl IntoResponse> {
+    let urls = sitemap_urls(&store)?;
+    if urls.len() <= SITEMAP_LIMIT {
+        return Ok(([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], urlset(&urls)));
+    }
+
+    let site = site_url();
+    let mut entries = String::new();
+    for (index, chunk) in urls.chunks(SITEMAP_LIMIT).enumerate() {
+        entries.push_str(&format!(
+            "\n  <sitemap>\n    <loc>{}/sitemaps/{}.xml</loc>{}\n  </sitemap>",
+            site,
+            index + 1,
+            chunk
+                .iter()
+                .filter_map(|url| url.lastmod)
+                .max()
+                .map(|at| format!("\n    <lastmod>{}</lastmod>", at.to_rfc3339_opts(SecondsFormat::Secs, true)))
+                .unwrap_or_default()
+        ));
+    }
+    let body = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{}
+</sitemapindex>
+"#,
+        entries
+    );
+    Ok(([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], body))
+}
+
+/// One part of a split sitemap, `file` is like `2.xml`
+pub async fn sitemap_part(State(store): State<Store>, Path(file): Path<String>) -> AppResult<impl IntoResponse> {
+    let page = file.strip_suffix(".xml").and_then(|n| n.parse::<usize>().ok()).filter(|n| *n > 0);
+    let urls = sitemap_urls(&store)?;
+    let chunk = page.and_then(|n| urls.chunks(SITEMAP_LIMIT).nth(n - 1)).ok_or(AppError::NotFound)?;
+    Ok(([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], urlset(chunk)))
+}
+
+/// Rules for crawlers as set in the admin
+/// The sitemap location is added unless the rules name one themselves
+pub async fn robots_txt(State(store): State<Store>) -> AppResult<impl IntoResponse> {
+    let mut body = store.robots_txt.read()?.trim_end().to_string();
+    if !body.to_lowercase().contains("sitemap:") {
+        body.push_str(&format!("\n\nSitemap: {}/sitemap.xml", site_url()));
+    }
+    body.push('\n');
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], body))
+}
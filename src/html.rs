@@ -0,0 +1,9 @@
+//! HTML helpers shared by the pages and the emails
+
+/// Escapes text for display inside HTML, including attribute values
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
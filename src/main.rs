@@ -1,17 +1,23 @@
 mod models;
 mod store;
 mod handlers;
+mod email;
+mod media;
+mod scheduler;
+mod html;
 
 use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{get, post},
     Router,
 };
-use tower_http::services::ServeDir;
+use tower_http::{catch_panic::CatchPanicLayer, services::ServeDir};
 use std::net::SocketAddr;
-use tracing_subscriber;
 
 use store::Store;
 use handlers::*;
+use media::MAX_REQUEST_SIZE;
 
 /// Main application entry point
 /// Initializes the web server and routes
@@ -21,8 +27,14 @@ async fn main() {
     tracing_subscriber::fmt::init();
 
     // Create and initialize the data store
-    let store = Store::new();
+    let store = Store::new(
+        email::EmailQueue::start(email::transport_from_env()),
+        media::MediaStorage::from_env(),
+        models::SiteSettings::file_from_env(),
+    );
+    let media_dir = store.media.dir().to_path_buf();
     store.init_with_sample_data();
+    scheduler::start(store.clone());
 
     // Build the application routes
     // Admin routes for managment interface
@@ -31,29 +43,176 @@ async fn main() {
         .route("/admin/categories", get(admin_list_categories))
         .route("/admin/categories/create", post(admin_create_category))
         .route("/admin/categories/delete/:id", post(admin_delete_category))
+        .route("/admin/categories/edit/:id", get(admin_edit_category))
+        .route("/admin/categories/seo/:id", post(admin_update_category_seo))
+        .route("/admin/categories/attributes/create/:id", post(admin_create_category_attribute))
+        .route("/admin/categories/attributes/delete/:id/:attribute_id", post(admin_delete_category_attribute))
         .route("/admin/products", get(admin_list_products))
         .route("/admin/products/create", post(admin_create_product))
+        .route("/admin/products/edit/:id", get(admin_edit_product).post(admin_update_product))
         .route("/admin/products/delete/:id", post(admin_delete_product))
+        .route(
+            "/admin/products/images/add/:id",
+            post(admin_add_product_image).layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE)),
+        )
+        .route("/admin/products/images/reorder/:id", post(admin_reorder_product_images))
+        .route("/admin/products/images/alt/:id/:image_id", post(admin_update_product_image_alt))
+        .route("/admin/products/images/primary/:id/:image_id", post(admin_set_primary_product_image))
+        .route("/admin/products/images/delete/:id/:image_id", post(admin_delete_product_image))
+        .route("/admin/products/options/create/:id", post(admin_create_product_option))
+        .route("/admin/products/options/delete/:id/:index", post(admin_delete_product_option))
+        .route("/admin/products/variants/generate/:id", post(admin_generate_variants))
+        .route("/admin/products/variants/update/:id/:variant_id", post(admin_update_variant))
+        .route("/admin/products/variants/delete/:id/:variant_id", post(admin_delete_variant))
+        .route("/admin/inventory", get(admin_inventory))
+        .route("/admin/inventory/default-threshold", post(admin_set_default_threshold))
+        .route("/admin/inventory/:id", get(admin_product_inventory))
+        .route("/admin/inventory/adjust/:id", post(admin_adjust_stock))
+        .route("/admin/inventory/threshold/:id", post(admin_set_product_threshold))
+        .route("/admin/orders", get(admin_list_orders))
+        .route("/admin/orders/:id", get(admin_view_order))
+        .route("/admin/orders/status/:id", post(admin_update_order_status))
+        .route("/admin/customers", get(admin_list_customers))
+        .route("/admin/coupons", get(admin_list_coupons))
+        .route("/admin/coupons/create", post(admin_create_coupon))
+        .route("/admin/coupons/toggle/:id", post(admin_toggle_coupon))
+        .route("/admin/coupons/delete/:id", post(admin_delete_coupon))
+        .route("/admin/taxes", get(admin_list_taxes))
+        .route("/admin/taxes/settings", post(admin_update_tax_settings))
+        .route("/admin/taxes/create", post(admin_create_tax_rate))
+        .route("/admin/taxes/delete/:region", post(admin_delete_tax_rate))
+        .route("/admin/shipping", get(admin_list_shipping))
+        .route("/admin/shipping/create", post(admin_create_shipping_zone))
+        .route("/admin/shipping/delete/:id", post(admin_delete_shipping_zone))
+        .route("/admin/shipping/methods/create/:id", post(admin_create_shipping_method))
+        .route("/admin/shipping/methods/delete/:id/:method_id", post(admin_delete_shipping_method))
+        .route("/admin/emails", get(admin_list_emails))
         .route("/admin/blog-categories", get(admin_list_blog_categories))
         .route("/admin/blog-categories/create", post(admin_create_blog_category))
         .route("/admin/blog-categories/delete/:id", post(admin_delete_blog_category))
+        .route("/admin/posts/comments-enabled/:id", post(admin_toggle_post_comments))
+        .route("/admin/posts/feed-settings", post(admin_update_feed_settings))
+        .route("/admin/comments", get(admin_list_comments))
+        .route("/admin/comments/status/:id", post(admin_update_comment_status))
+        .route("/admin/comments/edit/:id", get(admin_edit_comment).post(admin_update_comment))
+        .route("/admin/comments/delete/:id", post(admin_delete_comment))
+        .route("/admin/comments/keywords", post(admin_update_spam_keywords))
+        .route("/admin/seo", get(admin_seo))
+        .route("/admin/seo/robots", post(admin_update_robots_txt))
+        .route("/admin/settings", get(admin_settings).post(admin_update_settings))
+        .route(
+            "/admin/settings/logo",
+            post(admin_set_logo).layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE)),
+        )
+        .route("/admin/pages", get(admin_list_pages))
+        .route("/admin/pages/create", post(admin_create_page))
+        .route("/admin/pages/edit/:id", get(admin_edit_page).post(admin_update_page))
+        .route("/admin/pages/delete/:id", post(admin_delete_page))
+        .route("/admin/pages/homepage", post(admin_set_home_page))
+        .route("/admin/menus", get(admin_list_menus))
+        .route("/admin/menus/create", post(admin_create_menu_item))
+        .route("/admin/menus/edit/:id", get(admin_edit_menu_item).post(admin_update_menu_item))
+        .route("/admin/menus/move/:id/:direction", post(admin_move_menu_item))
+        .route("/admin/menus/delete/:id", post(admin_delete_menu_item))
+        .route("/admin/tags", get(admin_list_tags))
+        .route("/admin/tags/create", post(admin_create_tags))
+        .route("/admin/tags/edit/:id", post(admin_update_tag))
+        .route("/admin/tags/delete/:id", post(admin_delete_tag))
         .route("/admin/posts", get(admin_list_posts))
         .route("/admin/posts/create", post(admin_create_post))
-        .route("/admin/posts/toggle/:id", post(admin_toggle_post))
-        .route("/admin/posts/delete/:id", post(admin_delete_post));
+        .route("/admin/posts/status/:id", post(admin_update_post_status))
+        .route("/admin/posts/reviewer/:id", post(admin_assign_post_reviewer))
+        .route("/admin/posts/comments/create/:id", post(admin_create_review_comment))
+        .route("/admin/posts/autosave/:id", post(admin_autosave_post))
+        .route("/admin/posts/autosave/discard/:id", post(admin_discard_post_autosave))
+        .route("/admin/posts/delete/:id", post(admin_delete_post))
+        .route("/admin/posts/edit/:id", get(admin_edit_post).post(admin_update_post))
+        .route("/admin/posts/schedule/:id", post(admin_schedule_post))
+        .route("/admin/posts/revisions/:id", get(admin_post_revisions))
+        .route("/admin/posts/revisions/:id/:revision_id", get(admin_post_revision_diff))
+        .route("/admin/posts/revisions/restore/:id/:revision_id", post(admin_restore_post_revision))
+        .route(
+            "/admin/posts/image/:id",
+            post(admin_set_post_image).layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE)),
+        )
+        .route("/admin/authors", get(admin_list_authors))
+        .route("/admin/authors/create", post(admin_create_author))
+        .route("/admin/authors/edit/:id", get(admin_edit_author).post(admin_update_author))
+        .route(
+            "/admin/authors/avatar/:id",
+            post(admin_set_author_avatar).layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE)),
+        )
+        .route("/admin/authors/delete/:id", post(admin_delete_author))
+        .route("/admin/media", get(admin_media_library))
+        .route(
+            "/admin/media/upload",
+            post(admin_upload_media).layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE)),
+        )
+        .route("/admin/media/delete/:id", post(admin_delete_media))
+        .route("/admin/currencies", get(admin_list_currencies))
+        .route("/admin/currencies/create", post(admin_create_currency))
+        .route("/admin/currencies/rate/:code", post(admin_update_currency_rate))
+        .route("/admin/currencies/base/:code", post(admin_set_base_currency))
+        .route("/admin/currencies/delete/:code", post(admin_delete_currency));
 
     // Public routes for customer-facing pages
     let public_routes = Router::new()
         .route("/", get(public_home))
+        .route("/:slug", get(public_static_page))
         .route("/shop", get(public_shop))
+        .route("/shop/:id", get(public_product))
+        .route("/shop/category/:id", get(public_shop_category))
+        .route("/shop/tag/:slug", get(public_shop_tag))
         .route("/blog", get(public_blog))
-        .route("/blog/:id", get(public_blog_post));
+        .route("/blog/feed.rss", get(blog_rss))
+        .route("/blog/feed.atom", get(blog_atom))
+        .route("/blog/category/:id/feed.rss", get(blog_category_rss))
+        .route("/blog/category/:id/feed.atom", get(blog_category_atom))
+        .route("/blog/tag/:slug/feed.rss", get(blog_tag_rss))
+        .route("/blog/tag/:slug/feed.atom", get(blog_tag_atom))
+        .route("/blog/:id", get(public_blog_post))
+        .route("/blog/:id/comments", post(public_create_comment))
+        .route("/blog/author/:slug", get(public_blog_author))
+        .route("/blog/tag/:slug", get(public_blog_tag))
+        .route("/sitemap.xml", get(sitemap))
+        .route("/sitemaps/:file", get(sitemap_part))
+        .route("/robots.txt", get(robots_txt))
+        .route("/currency", get(public_set_currency))
+        .route("/cart", get(public_cart))
+        .route("/cart/add", post(public_add_to_cart))
+        .route("/cart/update", post(public_update_cart))
+        .route("/cart/coupon", post(public_apply_coupon))
+        .route("/cart/coupon/remove", post(public_remove_coupon))
+        .route("/checkout", get(public_checkout).post(public_place_order))
+        .route("/checkout/region", post(public_set_region))
+        .route("/checkout/shipping", post(public_set_shipping))
+        .route("/order/:id", get(public_order))
+        .route("/account", get(account_overview))
+        .route("/account/signup", get(account_signup).post(account_create))
+        .route("/account/login", get(account_login).post(account_authenticate))
+        .route("/account/logout", post(account_logout))
+        .route("/account/forgot", get(account_forgot_password).post(account_request_reset))
+        .route("/account/reset/:token", get(account_reset_form).post(account_reset_password))
+        .route("/account/verify", post(account_resend_verification))
+        .route("/account/verify/:token", get(account_verify_email))
+        .route("/account/orders/:id", get(account_order))
+        .route("/account/addresses", get(account_addresses))
+        .route("/account/addresses/create", post(account_create_address))
+        .route("/account/addresses/delete/:id", post(account_delete_address));
 
     // Combine all routes and add static file serving
     let app = Router::new()
         .merge(admin_routes)
         .merge(public_routes)
         .nest_service("/static", ServeDir::new("static"))
+        .nest_service("/media", ServeDir::new(media_dir))
+        .fallback(not_found)
+        .layer(middleware::map_response_with_state(store.clone(), render_error_pages))
+        .layer(CatchPanicLayer::custom({
+            // A panicking handler gets the error page instead of a dropped connection
+            let store = store.clone();
+            move |_| panic_response(&store)
+        }))
         .with_state(store);
 
     // Start the server
@@ -63,6 +222,9 @@ async fn main() {
     println!("Public site: http://{}/", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // Visitor addresses are used by the comment rate limit
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 }
 
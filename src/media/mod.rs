@@ -0,0 +1,90 @@
+//! Media storage
+//! Uploaded images are resized, then kept on local disk and served under `/media`
+
+mod processing;
+
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use crate::models::MediaAsset;
+use processing::process_image;
+
+/// Largest accepted upload in bytes
+pub const MAX_UPLOAD_SIZE: usize = 10 * 1024 * 1024;
+
+/// Largest accepted upload request, room for a few files at once
+pub const MAX_REQUEST_SIZE: usize = 5 * MAX_UPLOAD_SIZE;
+
+/// Detects supported image formats from the file contents
+/// Returns the content type, the uploaded name and type are not trusted
+fn sniff_image(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Directory holding uploaded files
+#[derive(Clone)]
+pub struct MediaStorage {
+    dir: PathBuf,
+}
+
+impl MediaStorage {
+    /// Creates storage in the given directory, created on first upload
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Storage in the `MEDIA_DIR` directory, `media` by default
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("MEDIA_DIR").unwrap_or_else(|_| "media".to_string()).into())
+    }
+
+    /// Directory served under `/media`
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Resizes an uploaded image and stores every variant
+    /// Rejects files that aren't JPEG, PNG, GIF or WebP images
+    /// Decoding and encoding is slow, call this from a blocking task
+    pub fn save(&self, original_name: &str, bytes: &[u8]) -> Result<MediaAsset, String> {
+        if bytes.len() > MAX_UPLOAD_SIZE {
+            return Err(format!("{} is larger than {} MB", original_name, MAX_UPLOAD_SIZE / 1024 / 1024));
+        }
+        let content_type = sniff_image(bytes)
+            .ok_or_else(|| format!("{} is not a JPEG, PNG, GIF or WebP image", original_name))?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let processed = process_image(&id, bytes).map_err(|e| format!("{}: {}", original_name, e))?;
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let mut variants = Vec::new();
+        for (variant, contents) in processed.variants {
+            std::fs::write(self.dir.join(&variant.file_name), contents).map_err(|e| e.to_string())?;
+            variants.push(variant);
+        }
+
+        Ok(MediaAsset {
+            id,
+            original_name: original_name.to_string(),
+            content_type: content_type.to_string(),
+            size: bytes.len() as u64,
+            width: processed.width,
+            height: processed.height,
+            variants,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Removes the stored files of an asset
+    pub fn delete(&self, asset: &MediaAsset) {
+        for variant in &asset.variants {
+            if let Err(error) = std::fs::remove_file(self.dir.join(&variant.file_name)) {
+                tracing::warn!("Could not delete {}: {}", variant.file_name, error);
+            }
+        }
+    }
+}
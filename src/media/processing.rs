@@ -0,0 +1,94 @@
+//! Image processing
+//! Uploads are decoded, turned upright and re-encoded in every size and format;
+//! re-encoding also drops EXIF and other metadata from the files we serve
+
+use std::io::Cursor;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use crate::models::{ImageSize, ImageVariant};
+
+/// JPEG quality of the fallback files
+const JPEG_QUALITY: u8 = 85;
+
+/// WebP quality of the preferred files
+const WEBP_QUALITY: f32 = 80.0;
+
+/// Image decoded and encoded in every size
+pub struct ProcessedImage {
+    /// Width and height of the upright original
+    pub width: u32,
+    pub height: u32,
+    /// Every variant with its encoded contents, named after `id`
+    pub variants: Vec<(ImageVariant, Vec<u8>)>,
+}
+
+/// Decodes an image, applies its EXIF orientation and encodes every size as WebP
+/// plus JPEG (or PNG for images with transparency) for browsers without WebP support
+/// Animated GIFs keep their first frame only
+pub fn process_image(id: &str, bytes: &[u8]) -> Result<ProcessedImage, String> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .into_decoder()
+        .map_err(|e| format!("Could not read the image: {}", e))?;
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| format!("Could not read the image: {}", e))?;
+    image.apply_orientation(orientation);
+
+    let (width, height) = (image.width(), image.height());
+    let has_alpha = image.color().has_alpha();
+    let mut variants = Vec::new();
+    // Largest size first so every smaller size is scaled from the previous one
+    let mut resized = image;
+    for size in ImageSize::ALL.into_iter().rev() {
+        let max = size.max_dimension();
+        // Images are only ever scaled down
+        if resized.width() > max || resized.height() > max {
+            resized = resized.resize(max, max, FilterType::Lanczos3);
+        }
+
+        variants.push(encode_webp(id, size, &resized));
+        variants.push(if has_alpha {
+            encode_png(id, size, &resized)?
+        } else {
+            encode_jpeg(id, size, &resized)?
+        });
+    }
+
+    Ok(ProcessedImage { width, height, variants })
+}
+
+/// Describes an encoded variant
+fn variant(id: &str, size: ImageSize, image: &DynamicImage, extension: &str, content_type: &str, bytes: Vec<u8>) -> (ImageVariant, Vec<u8>) {
+    let variant = ImageVariant {
+        size,
+        file_name: format!("{}-{}.{}", id, size.label().to_lowercase(), extension),
+        content_type: content_type.to_string(),
+        width: image.width(),
+        height: image.height(),
+        bytes: bytes.len() as u64,
+    };
+    (variant, bytes)
+}
+
+fn encode_webp(id: &str, size: ImageSize, image: &DynamicImage) -> (ImageVariant, Vec<u8>) {
+    let rgba = image.to_rgba8();
+    let encoded = webp::Encoder::from_rgba(&rgba, image.width(), image.height()).encode(WEBP_QUALITY);
+    variant(id, size, image, "webp", "image/webp", encoded.to_vec())
+}
+
+fn encode_jpeg(id: &str, size: ImageSize, image: &DynamicImage) -> Result<(ImageVariant, Vec<u8>), String> {
+    let mut bytes = Vec::new();
+    image
+        .to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))
+        .map_err(|e| e.to_string())?;
+    Ok(variant(id, size, image, "jpg", "image/jpeg", bytes))
+}
+
+fn encode_png(id: &str, size: ImageSize, image: &DynamicImage) -> Result<(ImageVariant, Vec<u8>), String> {
+    let mut bytes = Cursor::new(Vec::new());
+    image.to_rgba8().write_to(&mut bytes, ImageFormat::Png).map_err(|e| e.to_string())?;
+    Ok(variant(id, size, image, "png", "image/png", bytes.into_inner()))
+}
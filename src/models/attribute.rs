@@ -0,0 +1,137 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Kind of value an attribute holds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AttributeType {
+    Text,
+    Number,
+    Boolean,
+    /// One value from a fixed list of choices
+    Select,
+}
+
+impl AttributeType {
+    /// Parses the type from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(Self::Text),
+            "number" => Some(Self::Number),
+            "boolean" => Some(Self::Boolean),
+            "select" => Some(Self::Select),
+            _ => None,
+        }
+    }
+
+    /// Human readable name of the type
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Text => "Text",
+            Self::Number => "Number",
+            Self::Boolean => "Yes/No",
+            Self::Select => "Select",
+        }
+    }
+}
+
+/// Attribute definition belonging to a product category
+/// e.g. "Screen Size" for electronics or "ISBN" for books
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    pub id: String,
+    pub name: String,
+    pub attribute_type: AttributeType,
+    /// Allowed values for select attributes
+    pub choices: Vec<String>,
+    /// Unit shown after number values, e.g. "in" or "GB"
+    pub unit: Option<String>,
+    /// Whether the attribute is offered as a filter on the category page
+    pub filterable: bool,
+}
+
+impl AttributeDefinition {
+    /// Creates a new attribute definition
+    /// Choices are given as a comma separated list and only kept for select attributes
+    pub fn new(name: String, attribute_type: AttributeType, choices: &str, unit: Option<String>, filterable: bool) -> Self {
+        let choices = if attribute_type == AttributeType::Select {
+            choices
+                .split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            attribute_type,
+            choices,
+            unit: unit.map(|u| u.trim().to_string()).filter(|u| !u.is_empty()),
+            filterable,
+        }
+    }
+
+    /// Parses a raw form value into a typed value
+    /// Returns None for empty or invalid input
+    pub fn parse(&self, raw: &str) -> Option<AttributeValue> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        match self.attribute_type {
+            AttributeType::Text => Some(AttributeValue::Text(raw.to_string())),
+            AttributeType::Number => raw.parse::<f64>().ok().map(AttributeValue::Number),
+            AttributeType::Boolean => match raw {
+                "true" | "yes" | "on" | "1" => Some(AttributeValue::Boolean(true)),
+                "false" | "no" | "off" | "0" => Some(AttributeValue::Boolean(false)),
+                _ => None,
+            },
+            AttributeType::Select => self
+                .choices
+                .iter()
+                .find(|c| c.as_str() == raw)
+                .map(|c| AttributeValue::Text(c.clone())),
+        }
+    }
+
+    /// Formats a value for display, adding the unit if any
+    pub fn display(&self, value: &AttributeValue) -> String {
+        match (&self.unit, value) {
+            (Some(unit), AttributeValue::Number(_)) => format!("{} {}", value, unit),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Typed attribute value stored on a product
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AttributeValue {
+    Text(String),
+    Number(f64),
+    Boolean(bool),
+}
+
+impl AttributeValue {
+    /// Raw value as used in forms and filter query strings
+    pub fn raw(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Number(number) => number.to_string(),
+            Self::Boolean(flag) => flag.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(text) => write!(f, "{}", text),
+            Self::Number(number) => write!(f, "{}", number),
+            Self::Boolean(true) => write!(f, "Yes"),
+            Self::Boolean(false) => write!(f, "No"),
+        }
+    }
+}
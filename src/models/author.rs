@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Author profile shown in post bylines and on the author archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Author {
+    pub id: String,
+    pub name: String,
+    /// Unique part of the archive URL, `/blog/author/<slug>`
+    pub slug: String,
+    pub bio: String,
+    pub avatar_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Author {
+    /// Creates a new author profile
+    /// The slug has to be unique, see `Store::create_author`
+    pub fn new(name: String, slug: String, bio: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            slug,
+            bio,
+            avatar_url: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
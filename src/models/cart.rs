@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::{Coupon, ShippingQuote, TaxLine};
+
+/// Most units of one product or variant a cart can hold
+pub const MAX_LINE_QUANTITY: i32 = 999;
+
+/// Shopping cart of a visitor
+/// Identified by a cookie, items are priced when the cart is shown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cart {
+    pub id: String,
+    pub items: Vec<CartItem>,
+    pub coupon_code: Option<String>,
+    /// Region the order ships to, used for tax
+    /// `None` uses the store default region
+    pub region: Option<String>,
+    /// Shipping method picked at checkout
+    pub shipping_method_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A product or variant in the cart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CartItem {
+    pub product_id: String,
+    pub variant_id: Option<String>,
+    pub quantity: i32,
+}
+
+impl Cart {
+    /// Creates a new empty cart
+    pub fn new() -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            items: Vec::new(),
+            coupon_code: None,
+            region: None,
+            shipping_method_id: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Adds an item, increasing the quantity if it is already in the cart
+    /// Quantities are capped at `MAX_LINE_QUANTITY`
+    pub fn add(&mut self, product_id: String, variant_id: Option<String>, quantity: i32) {
+        match self
+            .items
+            .iter_mut()
+            .find(|i| i.product_id == product_id && i.variant_id == variant_id)
+        {
+            Some(item) => item.quantity = item.quantity.saturating_add(quantity).min(MAX_LINE_QUANTITY),
+            None => self.items.push(CartItem { product_id, variant_id, quantity: quantity.min(MAX_LINE_QUANTITY) }),
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Sets the quantity of a product or variant, removing it when the quantity is zero
+    /// Quantities are capped at `MAX_LINE_QUANTITY`
+    pub fn set_quantity(&mut self, product_id: &str, variant_id: Option<&str>, quantity: i32) {
+        if quantity <= 0 {
+            self.items.retain(|i| !(i.product_id == product_id && i.variant_id.as_deref() == variant_id));
+        } else if let Some(item) = self
+            .items
+            .iter_mut()
+            .find(|i| i.product_id == product_id && i.variant_id.as_deref() == variant_id)
+        {
+            item.quantity = quantity.min(MAX_LINE_QUANTITY);
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Total number of units in the cart
+    pub fn item_count(&self) -> i32 {
+        self.items.iter().map(|i| i.quantity).sum()
+    }
+}
+
+impl Default for Cart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A priced cart line
+#[derive(Debug, Clone)]
+pub struct CartLine {
+    pub product_id: String,
+    pub category_id: String,
+    pub variant_id: Option<String>,
+    pub name: String,
+    pub sku: Option<String>,
+    /// Price before any sale
+    pub regular_price: f64,
+    pub unit_price: f64,
+    pub quantity: i32,
+    pub total: f64,
+    /// Units currently in stock, used to warn about unavailable items
+    pub available: i32,
+    /// Tax percentage for the cart region
+    pub tax_rate: f64,
+    /// Shipping weight of one unit in kilograms
+    pub weight: f64,
+}
+
+impl CartLine {
+    /// Adds this line's tax to a net amount
+    pub fn with_tax(&self, amount: f64) -> f64 {
+        amount * (1.0 + self.tax_rate / 100.0)
+    }
+}
+
+/// Cart with all prices, discounts and taxes calculated in one currency
+/// Line prices, subtotal and discount are net amounts
+#[derive(Debug, Clone)]
+pub struct CartSummary {
+    pub lines: Vec<CartLine>,
+    pub region: String,
+    /// Whether prices are shown including tax
+    pub prices_include_tax: bool,
+    pub subtotal: f64,
+    pub coupon: Option<Coupon>,
+    /// Why the entered coupon was not applied
+    pub coupon_error: Option<String>,
+    pub discount: f64,
+    pub free_shipping: bool,
+    /// Shipping methods available for the cart region
+    pub shipping_options: Vec<ShippingQuote>,
+    /// Picked shipping method, `None` until the customer chooses one
+    pub shipping: Option<ShippingQuote>,
+    pub tax_lines: Vec<TaxLine>,
+    pub tax: f64,
+    pub total: f64,
+}
+
+impl CartSummary {
+    /// Checks that every line can be fulfilled from current stock
+    pub fn in_stock(&self) -> bool {
+        self.lines.iter().all(|l| l.quantity <= l.available)
+    }
+
+    /// Total shipping weight in kilograms
+    pub fn weight(&self) -> f64 {
+        self.lines.iter().map(|l| l.weight * l.quantity as f64).sum()
+    }
+
+    /// Cost of the picked shipping method
+    pub fn shipping_cost(&self) -> f64 {
+        self.shipping.as_ref().map(|s| s.cost).unwrap_or(0.0)
+    }
+
+    /// Subtotal including the tax of every line
+    pub fn subtotal_with_tax(&self) -> f64 {
+        self.lines.iter().map(|l| l.with_tax(l.total)).sum()
+    }
+
+    /// Discount including the tax it saves
+    pub fn discount_with_tax(&self) -> f64 {
+        if self.discount > 0.0 {
+            self.subtotal_with_tax() - (self.subtotal - self.discount + self.tax)
+        } else {
+            0.0
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use super::{AttributeDefinition, SeoMeta};
 
 /// Product category model
 /// Used to organize products into diffrent categories
@@ -8,6 +9,10 @@ pub struct Category {
     pub id: String,
     pub name: String,
     pub description: String,
+    /// Attributes that products in this category can have
+    pub attributes: Vec<AttributeDefinition>,
+    /// Meta title and description overrides
+    pub seo: SeoMeta,
     pub created_at: DateTime<Utc>,
 }
 
@@ -19,8 +24,9 @@ impl Category {
             id: uuid::Uuid::new_v4().to_string(),
             name,
             description,
+            attributes: Vec::new(),
+            seo: SeoMeta::default(),
             created_at: Utc::now(),
         }
     }
 }
-
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Most comments a visitor can post within `COMMENT_RATE_WINDOW_MINUTES`
+pub const COMMENT_RATE_LIMIT: usize = 3;
+
+/// Window of the comment rate limit in minutes
+pub const COMMENT_RATE_WINDOW_MINUTES: i64 = 10;
+
+/// Moderation state of a reader comment
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum CommentStatus {
+    /// Waiting in the moderation queue
+    #[default]
+    Pending,
+    /// Shown under the post
+    Approved,
+    Rejected,
+    /// Flagged by the spam filter or a moderator
+    Spam,
+}
+
+impl CommentStatus {
+    pub const ALL: [CommentStatus; 4] = [Self::Pending, Self::Approved, Self::Rejected, Self::Spam];
+
+    /// Parses the status from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.label().eq_ignore_ascii_case(value))
+    }
+
+    /// Human readable name of the status
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Approved => "Approved",
+            Self::Rejected => "Rejected",
+            Self::Spam => "Spam",
+        }
+    }
+}
+
+/// Reader comment on a blog post
+/// Replies point to the comment they answer, which makes threads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: String,
+    pub post_id: String,
+    /// Comment this one replies to, `None` for top level comments
+    pub parent_id: Option<String>,
+    pub author_name: String,
+    /// Never shown publicly, used for reply notifications
+    pub author_email: String,
+    pub body: String,
+    pub status: CommentStatus,
+    /// Whether the author wants an email when a reply is approved
+    pub notify_replies: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Comment {
+    /// Creates a new comment waiting for moderation
+    pub fn new(
+        post_id: String,
+        parent_id: Option<String>,
+        author_name: String,
+        author_email: String,
+        body: String,
+        notify_replies: bool,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            post_id,
+            parent_id,
+            author_name,
+            author_email: author_email.trim().to_lowercase(),
+            body,
+            status: CommentStatus::Pending,
+            notify_replies,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Checks the comment against spam keywords, ignoring case
+    pub fn matches_keyword(&self, keywords: &[String]) -> Option<String> {
+        let text = format!("{} {} {}", self.author_name, self.author_email, self.body).to_lowercase();
+        keywords
+            .iter()
+            .find(|keyword| !keyword.is_empty() && text.contains(&keyword.to_lowercase()))
+            .cloned()
+    }
+}
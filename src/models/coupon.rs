@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::Currency;
+
+/// How a coupon reduces the order total
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DiscountType {
+    /// Percentage off the eligible items
+    Percentage,
+    /// Fixed amount in the base currency off the eligible items
+    FixedAmount,
+    FreeShipping,
+}
+
+impl DiscountType {
+    /// Parses the type from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        match value {
+            "percentage" => Some(Self::Percentage),
+            "fixed" => Some(Self::FixedAmount),
+            "free_shipping" => Some(Self::FreeShipping),
+            _ => None,
+        }
+    }
+}
+
+/// Discount code customers can enter in the cart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Coupon {
+    pub id: String,
+    pub code: String,
+    pub discount_type: DiscountType,
+    /// Percentage or base currency amount, unused for free shipping
+    pub value: f64,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    /// Maximum number of orders that can use the coupon
+    pub usage_limit: Option<u32>,
+    pub times_used: u32,
+    /// Minimum cart subtotal in the base currency
+    pub minimum_order: Option<f64>,
+    /// When set, only items in these categories are discounted
+    pub category_ids: Vec<String>,
+    /// When set, only these products are discounted
+    pub product_ids: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Coupon {
+    /// Creates a new active coupon without restrictions
+    /// The code is normalized to upper case
+    pub fn new(code: String, discount_type: DiscountType, value: f64) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            code: code.trim().to_uppercase(),
+            discount_type,
+            value,
+            starts_at: None,
+            ends_at: None,
+            usage_limit: None,
+            times_used: 0,
+            minimum_order: None,
+            category_ids: Vec::new(),
+            product_ids: Vec::new(),
+            active: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Checks whether the coupon can currently be used
+    /// Returns the reason shown to the customer if it can't
+    pub fn check_usable(&self, now: DateTime<Utc>) -> Result<(), &'static str> {
+        if !self.active {
+            return Err("This coupon is no longer active");
+        }
+        if self.starts_at.map(|start| now < start).unwrap_or(false) {
+            return Err("This coupon is not valid yet");
+        }
+        if self.ends_at.map(|end| now > end).unwrap_or(false) {
+            return Err("This coupon has expired");
+        }
+        if self.usage_limit.map(|limit| self.times_used >= limit).unwrap_or(false) {
+            return Err("This coupon has reached its usage limit");
+        }
+        Ok(())
+    }
+
+    /// Checks if an item is eligible for the discount
+    /// Coupons without restrictions apply to every item
+    pub fn applies_to(&self, product_id: &str, category_id: &str) -> bool {
+        if self.category_ids.is_empty() && self.product_ids.is_empty() {
+            return true;
+        }
+        self.product_ids.iter().any(|id| id == product_id)
+            || self.category_ids.iter().any(|id| id == category_id)
+    }
+
+    /// Short description like "10% off", "$5.00 off" or "Free shipping"
+    /// Fixed amounts are converted into and formatted in the given currency
+    pub fn describe(&self, currency: &Currency) -> String {
+        match self.discount_type {
+            DiscountType::Percentage => format!("{}% off", self.value),
+            DiscountType::FixedAmount => format!("{} off", currency.format(currency.convert(self.value))),
+            DiscountType::FreeShipping => "Free shipping".to_string(),
+        }
+    }
+}
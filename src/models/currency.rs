@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Currency model
+/// Prices are stored in the base currency and converted using the exchange rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Currency {
+    pub code: String,
+    pub name: String,
+    pub symbol: String,
+    /// Units of this currency per one unit of the base currency
+    pub rate: f64,
+    /// Locale used for number formatting, e.g. "en-US" or "de-DE"
+    pub locale: String,
+    pub decimals: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Currency {
+    /// Creates a new currency
+    /// The code is normalized to upper case
+    pub fn new(code: String, name: String, symbol: String, rate: f64, locale: String, decimals: usize) -> Self {
+        Self {
+            code: code.trim().to_uppercase(),
+            name,
+            symbol,
+            rate,
+            locale,
+            decimals,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Converts an amount in the base currency into this currency
+    pub fn convert(&self, base_amount: f64) -> f64 {
+        base_amount * self.rate
+    }
+
+    /// Rounds an amount to the number of decimals of this currency
+    pub fn round(&self, amount: f64) -> f64 {
+        let factor = 10f64.powi(self.decimals as i32);
+        (amount * factor).round() / factor
+    }
+
+    /// Formats an amount of this currency using the locale conventions
+    /// e.g. "$1,299.99" for en-US and "1.299,99 €" for de-DE
+    pub fn format(&self, amount: f64) -> String {
+        let (thousands, decimal, symbol_after) = match self.locale.as_str() {
+            "de-DE" | "es-ES" | "it-IT" | "nl-NL" | "pt-BR" | "da-DK" => (".", ",", true),
+            "fr-FR" | "sv-SE" | "pl-PL" | "cs-CZ" | "nb-NO" => ("\u{a0}", ",", true),
+            "de-CH" => ("'", ".", false),
+            _ => (",", ".", false),
+        };
+
+        let fixed = format!("{:.*}", self.decimals, amount.abs());
+        let (int_part, frac_part) = match fixed.split_once('.') {
+            Some((i, f)) => (i.to_string(), Some(f.to_string())),
+            None => (fixed.clone(), None),
+        };
+
+        let mut grouped = String::new();
+        for (i, ch) in int_part.chars().enumerate() {
+            if i > 0 && (int_part.len() - i) % 3 == 0 {
+                grouped.push_str(thousands);
+            }
+            grouped.push(ch);
+        }
+        if let Some(frac) = frac_part {
+            grouped.push_str(decimal);
+            grouped.push_str(&frac);
+        }
+
+        let sign = if amount < 0.0 { "-" } else { "" };
+        if symbol_after {
+            format!("{}{}\u{a0}{}", sign, grouped, self.symbol)
+        } else {
+            format!("{}{}{}", sign, self.symbol, grouped)
+        }
+    }
+}
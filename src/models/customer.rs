@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+/// Shop customer account
+/// Separate from the admin panel, customers can only see their own orders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Customer {
+    pub id: String,
+    pub name: String,
+    /// Lower case email, used to log in and to link guest orders
+    pub email: String,
+    /// Argon2 hash in PHC string format
+    pub password_hash: String,
+    /// Whether the customer proved they own the email, guest orders are only linked after that
+    #[serde(default)]
+    pub email_verified: bool,
+    pub addresses: Vec<Address>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Saved shipping address of a customer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Address {
+    pub id: String,
+    /// Short name like "Home" or "Office"
+    pub label: String,
+    pub recipient: String,
+    pub address: String,
+    pub region: String,
+}
+
+impl Address {
+    /// Creates a new address
+    /// The region code is normalized to upper case
+    pub fn new(label: String, recipient: String, address: String, region: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            label,
+            recipient,
+            address,
+            region: region.trim().to_uppercase(),
+        }
+    }
+}
+
+/// Minimum password length for customer accounts
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+impl Customer {
+    /// Creates a new customer with a hashed password
+    pub fn new(name: String, email: String, password: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            email: normalize_email(&email),
+            password_hash: hash_password(password),
+            email_verified: false,
+            addresses: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Checks a password against the stored hash
+    pub fn verify_password(&self, password: &str) -> bool {
+        PasswordHash::new(&self.password_hash)
+            .map(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+            .unwrap_or(false)
+    }
+
+    /// Replaces the password
+    pub fn set_password(&mut self, password: &str) {
+        self.password_hash = hash_password(password);
+        self.updated_at = Utc::now();
+    }
+}
+
+/// Hashes a password with a random salt
+fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("argon2 hashing with default parameters can't fail")
+        .to_string()
+}
+
+/// Normalizes an email for comparisons
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Checks that mail can be sent to an address
+pub fn is_valid_email(email: &str) -> bool {
+    email.trim().parse::<lettre::Address>().is_ok()
+}
+
+/// Logged in customer session, identified by a random token in a cookie
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomerSession {
+    pub customer_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl CustomerSession {
+    /// Creates a session valid for 30 days
+    pub fn new(customer_id: String) -> Self {
+        Self {
+            customer_id,
+            expires_at: Utc::now() + Duration::days(30),
+        }
+    }
+}
+
+/// Single use password reset token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordReset {
+    pub customer_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PasswordReset {
+    /// Creates a reset valid for one hour
+    pub fn new(customer_id: String) -> Self {
+        Self {
+            customer_id,
+            expires_at: Utc::now() + Duration::hours(1),
+        }
+    }
+}
+
+/// Single use link proving a customer owns their email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailVerification {
+    pub customer_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl EmailVerification {
+    /// Creates a verification valid for one day
+    pub fn new(customer_id: String) -> Self {
+        Self {
+            customer_id,
+            expires_at: Utc::now() + Duration::days(1),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Sizes every uploaded image is stored in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ImageSize {
+    /// Small preview for admin pickers
+    Thumbnail,
+    /// Product and blog cards
+    Card,
+    /// Detail pages
+    Full,
+}
+
+impl ImageSize {
+    pub const ALL: [ImageSize; 3] = [Self::Thumbnail, Self::Card, Self::Full];
+
+    /// Human readable name of the size
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Thumbnail => "Thumbnail",
+            Self::Card => "Card",
+            Self::Full => "Full",
+        }
+    }
+
+    /// Longest side in pixels, smaller images are not scaled up
+    pub fn max_dimension(&self) -> u32 {
+        match self {
+            Self::Thumbnail => 200,
+            Self::Card => 600,
+            Self::Full => 1600,
+        }
+    }
+}
+
+/// One encoded size and format of an uploaded image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageVariant {
+    pub size: ImageSize,
+    /// Name of the stored file inside the media directory
+    pub file_name: String,
+    pub content_type: String,
+    pub width: u32,
+    pub height: u32,
+    /// Size in bytes
+    pub bytes: u64,
+}
+
+impl ImageVariant {
+    /// Public URL of the file
+    pub fn url(&self) -> String {
+        format!("/media/{}", self.file_name)
+    }
+
+    /// Checks if the variant is the WebP version rather than the fallback
+    pub fn is_webp(&self) -> bool {
+        self.content_type == "image/webp"
+    }
+}
+
+/// Uploaded image in the media library
+/// The original file isn't kept, only its resized variants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaAsset {
+    pub id: String,
+    /// Name of the file as uploaded
+    pub original_name: String,
+    /// Type of the uploaded file
+    pub content_type: String,
+    /// Size of the uploaded file in bytes
+    pub size: u64,
+    /// Width and height after orientation was applied
+    pub width: u32,
+    pub height: u32,
+    pub variants: Vec<ImageVariant>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MediaAsset {
+    /// Fallback format variant of a size
+    pub fn variant(&self, size: ImageSize) -> Option<&ImageVariant> {
+        self.variants.iter().find(|v| v.size == size && !v.is_webp())
+    }
+
+    /// URL of the fallback format file of a size
+    pub fn url_for(&self, size: ImageSize) -> String {
+        self.variant(size).map(|v| v.url()).unwrap_or_default()
+    }
+
+    /// URL stored on products and posts using the image
+    pub fn url(&self) -> String {
+        self.url_for(ImageSize::Full)
+    }
+
+    /// `srcset` value listing every size of one format, e.g. "/media/a-card.webp 600w, ..."
+    /// Sizes that came out the same width as a smaller one are left out
+    pub fn srcset(&self, webp: bool) -> String {
+        let mut entries: Vec<String> = Vec::new();
+        let mut last_width = 0;
+        for size in ImageSize::ALL {
+            if let Some(variant) = self.variants.iter().find(|v| v.size == size && v.is_webp() == webp) {
+                if variant.width > last_width {
+                    entries.push(format!("{} {}w", variant.url(), variant.width));
+                    last_width = variant.width;
+                }
+            }
+        }
+        entries.join(", ")
+    }
+
+    /// Size formatted for display, e.g. "245.3 KB"
+    pub fn display_size(&self) -> String {
+        format_bytes(self.size)
+    }
+}
+
+/// Formats a byte count for display
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        bytes if bytes >= 1024 * 1024 => format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0)),
+        bytes if bytes >= 1024 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        bytes => format!("{} B", bytes),
+    }
+}
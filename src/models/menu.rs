@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Place of the site layout a menu is shown in
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum MenuLocation {
+    #[default]
+    Header,
+    Footer,
+}
+
+impl MenuLocation {
+    pub const ALL: [MenuLocation; 2] = [Self::Header, Self::Footer];
+
+    /// Parses the location from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.label().eq_ignore_ascii_case(value))
+    }
+
+    /// Human readable name of the location
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Header => "Header",
+            Self::Footer => "Footer",
+        }
+    }
+}
+
+/// Target of a menu item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MenuLink {
+    /// Static page by id, follows slug changes
+    Page(String),
+    /// Product category by id
+    Category(String),
+    /// Blog post by id
+    Post(String),
+    /// Any site path or external address
+    Url(String),
+}
+
+impl MenuLink {
+    /// Parses the target select of the menu form, `url` is used for `url` targets
+    /// Targets look like `page:<id>`, `category:<id>`, `post:<id>` or `url`
+    pub fn from_form(target: &str, url: &str) -> Option<Self> {
+        let url = url.trim();
+        match target.split_once(':') {
+            Some(("page", id)) => Some(Self::Page(id.to_string())),
+            Some(("category", id)) => Some(Self::Category(id.to_string())),
+            Some(("post", id)) => Some(Self::Post(id.to_string())),
+            _ if target == "url" && !url.is_empty() => Some(Self::Url(url.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Value of the target select for this link
+    pub fn form_value(&self) -> String {
+        match self {
+            Self::Page(id) => format!("page:{}", id),
+            Self::Category(id) => format!("category:{}", id),
+            Self::Post(id) => format!("post:{}", id),
+            Self::Url(_) => "url".to_string(),
+        }
+    }
+
+    /// Kind of target shown in the admin
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Page(_) => "Page",
+            Self::Category(_) => "Category",
+            Self::Post(_) => "Post",
+            Self::Url(_) => "URL",
+        }
+    }
+}
+
+/// Entry of the header or footer menu
+/// Items with a parent are shown nested below it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuItem {
+    pub id: String,
+    pub location: MenuLocation,
+    pub label: String,
+    pub link: MenuLink,
+    /// Item this one is nested under, `None` for top level items
+    pub parent_id: Option<String>,
+    /// Order among the items with the same parent, lowest first
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MenuItem {
+    /// Creates a new menu item
+    /// The position is set by `Store::add_menu_item`
+    pub fn new(location: MenuLocation, label: String, link: MenuLink, parent_id: Option<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            location,
+            label,
+            link,
+            parent_id,
+            position: 0,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Menu item ready for rendering, with its resolved address and visible children
+#[derive(Debug, Clone)]
+pub struct MenuEntry {
+    pub label: String,
+    pub href: String,
+    pub children: Vec<MenuEntry>,
+}
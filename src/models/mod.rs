@@ -2,8 +2,48 @@ pub mod product;
 pub mod category;
 pub mod post;
 pub mod blog_category;
+pub mod currency;
+pub mod variant;
+pub mod attribute;
+pub mod stock_movement;
+pub mod coupon;
+pub mod cart;
+pub mod order;
+pub mod tax;
+pub mod shipping;
+pub mod customer;
+pub mod media;
+pub mod author;
+pub mod revision;
+pub mod slug;
+pub mod tag;
+pub mod comment;
+pub mod seo;
+pub mod page;
+pub mod menu;
+pub mod settings;
 
-pub use product::Product;
+pub use product::{Dimensions, Product, ProductImage};
 pub use category::Category;
-pub use post::Post;
+pub use post::{Post, PostStatus, ReviewComment};
+pub use author::Author;
+pub use revision::PostRevision;
+pub use slug::{slugify, unique_slug};
+pub use tag::Tag;
+pub use seo::SeoMeta;
+pub use page::{Page, PageStatus, RESERVED_PAGE_SLUGS};
+pub use menu::{MenuEntry, MenuItem, MenuLink, MenuLocation};
+pub use settings::{parse_timezone, timezone_choices, SiteSettings, MAX_POSTS_PER_PAGE};
+pub use comment::{Comment, CommentStatus, COMMENT_RATE_LIMIT, COMMENT_RATE_WINDOW_MINUTES};
 pub use blog_category::BlogCategory;
+pub use currency::Currency;
+pub use variant::{ProductOption, ProductVariant};
+pub use attribute::{AttributeDefinition, AttributeType, AttributeValue};
+pub use stock_movement::{StockMovement, StockMovementKind};
+pub use coupon::{Coupon, DiscountType};
+pub use cart::{Cart, CartLine, CartSummary, MAX_LINE_QUANTITY};
+pub use order::{Order, OrderItem, OrderStatus};
+pub use tax::{TaxClass, TaxLine, TaxRate};
+pub use shipping::{ShippingMethod, ShippingQuote, ShippingRateType, ShippingZone};
+pub use media::{ImageSize, ImageVariant, MediaAsset};
+pub use customer::{is_valid_email, normalize_email, Address, Customer, CustomerSession, EmailVerification, PasswordReset, MIN_PASSWORD_LENGTH};
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::TaxLine;
+
+/// Fulfillment state of an order
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OrderStatus {
+    Pending,
+    Paid,
+    Shipped,
+    Completed,
+    Cancelled,
+}
+
+impl OrderStatus {
+    pub const ALL: [OrderStatus; 5] = [
+        Self::Pending,
+        Self::Paid,
+        Self::Shipped,
+        Self::Completed,
+        Self::Cancelled,
+    ];
+
+    /// Parses the status from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.label().eq_ignore_ascii_case(value))
+    }
+
+    /// Human readable name of the status
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Paid => "Paid",
+            Self::Shipped => "Shipped",
+            Self::Completed => "Completed",
+            Self::Cancelled => "Cancelled",
+        }
+    }
+}
+
+/// Product line of a placed order
+/// Names and prices are copied so later product changes don't alter the order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderItem {
+    pub product_id: String,
+    pub variant_id: Option<String>,
+    pub name: String,
+    pub sku: Option<String>,
+    pub unit_price: f64,
+    pub quantity: i32,
+    pub total: f64,
+    pub tax_rate: f64,
+}
+
+/// A placed order
+/// All amounts are in the currency the customer checked out with
+/// Item prices and the subtotal are net, taxes are listed separately
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Order {
+    pub id: String,
+    pub number: u32,
+    /// Account that placed the order, `None` for guest orders not yet linked
+    pub customer_id: Option<String>,
+    pub customer_name: String,
+    pub email: String,
+    pub address: String,
+    /// Region used for tax
+    pub region: String,
+    pub currency_code: String,
+    pub items: Vec<OrderItem>,
+    pub subtotal: f64,
+    pub coupon_code: Option<String>,
+    pub discount: f64,
+    pub shipping_method: Option<String>,
+    pub shipping: f64,
+    pub tax_lines: Vec<TaxLine>,
+    pub tax: f64,
+    pub total: f64,
+    pub status: OrderStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Order {
+    /// Creates a new pending order
+    pub fn new(
+        number: u32,
+        customer_name: String,
+        email: String,
+        address: String,
+        region: String,
+        currency_code: String,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            number,
+            customer_id: None,
+            customer_name,
+            email,
+            address,
+            region,
+            currency_code,
+            items: Vec::new(),
+            subtotal: 0.0,
+            coupon_code: None,
+            discount: 0.0,
+            shipping_method: None,
+            shipping: 0.0,
+            tax_lines: Vec::new(),
+            tax: 0.0,
+            total: 0.0,
+            status: OrderStatus::Pending,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Changes the order status
+    pub fn set_status(&mut self, status: OrderStatus) {
+        self.status = status;
+        self.updated_at = Utc::now();
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::SeoMeta;
+
+/// Slugs used by other parts of the site, pages can't take them
+pub const RESERVED_PAGE_SLUGS: [&str; 11] = [
+    "admin", "shop", "blog", "cart", "checkout", "account", "order", "static", "media", "sitemaps", "currency",
+];
+
+/// Whether a page is visible on the site
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum PageStatus {
+    #[default]
+    Draft,
+    Published,
+}
+
+impl PageStatus {
+    pub const ALL: [PageStatus; 2] = [Self::Draft, Self::Published];
+
+    /// Parses the status from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.label().eq_ignore_ascii_case(value))
+    }
+
+    /// Human readable name of the status
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Draft => "Draft",
+            Self::Published => "Published",
+        }
+    }
+}
+
+/// Static content page like About or Terms, served at `/<slug>`
+/// Pages can be nested under a parent page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page {
+    pub id: String,
+    pub title: String,
+    /// Unique part of the URL, see `Store::create_page`
+    pub slug: String,
+    /// HTML content of the page
+    pub body: String,
+    /// Page this one is shown under, `None` for top level pages
+    pub parent_id: Option<String>,
+    pub status: PageStatus,
+    /// Meta title and description overrides
+    pub seo: SeoMeta,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Page {
+    /// Creates a new draft page
+    pub fn new(title: String, slug: String, body: String, parent_id: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            title,
+            slug,
+            body,
+            parent_id,
+            status: PageStatus::default(),
+            seo: SeoMeta::default(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.status == PageStatus::Published
+    }
+}
@@ -1,5 +1,92 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use super::SeoMeta;
+
+/// Average reading speed used for reading times
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Editorial state of a post
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum PostStatus {
+    #[default]
+    Draft,
+    /// Waiting for the reviewer
+    InReview,
+    /// Ready to be published or scheduled
+    Approved,
+    Published,
+    /// Taken off the blog but kept
+    Archived,
+}
+
+impl PostStatus {
+    pub const ALL: [PostStatus; 5] = [
+        Self::Draft,
+        Self::InReview,
+        Self::Approved,
+        Self::Published,
+        Self::Archived,
+    ];
+
+    /// Parses the status from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.label().eq_ignore_ascii_case(value))
+    }
+
+    /// Human readable name of the status
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Draft => "Draft",
+            Self::InReview => "In review",
+            Self::Approved => "Approved",
+            Self::Published => "Published",
+            Self::Archived => "Archived",
+        }
+    }
+
+    /// States a post can move to from this one, with the action label
+    pub fn transitions(&self) -> &'static [(PostStatus, &'static str)] {
+        match self {
+            Self::Draft => &[(Self::InReview, "Submit for Review")],
+            Self::InReview => &[(Self::Approved, "Approve"), (Self::Draft, "Request Changes")],
+            Self::Approved => &[(Self::Published, "Publish"), (Self::Draft, "Back to Draft")],
+            Self::Published => &[(Self::Archived, "Archive"), (Self::Draft, "Unpublish")],
+            Self::Archived => &[(Self::Draft, "Restore as Draft")],
+        }
+    }
+}
+
+/// Comment left on a post during review, only shown in the admin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub id: String,
+    /// Author profile of the commenter
+    pub author_id: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ReviewComment {
+    /// Creates a new review comment
+    pub fn new(author_id: String, body: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            author_id,
+            body,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Unsaved edits kept by the editor's autosave
+/// They don't change the post until it is saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostAutosave {
+    pub title: String,
+    pub excerpt: String,
+    pub content: String,
+    pub saved_at: DateTime<Utc>,
+}
 
 /// Blog post model
 /// Represents individual blog posts with content and metadata
@@ -10,8 +97,25 @@ pub struct Post {
     pub content: String,
     pub excerpt: String,
     pub category_id: String,
-    pub author: String,
-    pub published: bool,
+    /// Ids of the tags, see `Store::resolve_tags`
+    pub tag_ids: Vec<String>,
+    /// Meta title and description overrides
+    pub seo: SeoMeta,
+    /// Id of the author profile
+    pub author_id: String,
+    /// URL of the featured image shown on the blog list and above the post
+    pub image_url: Option<String>,
+    pub status: PostStatus,
+    /// Author profile asked to review the post
+    pub reviewer_id: Option<String>,
+    pub review_comments: Vec<ReviewComment>,
+    pub autosave: Option<PostAutosave>,
+    /// Whether readers can leave comments
+    pub comments_enabled: bool,
+    /// When a scheduled post goes live, cleared once it is published
+    pub publish_at: Option<DateTime<Utc>>,
+    /// When the post was last published, shown as the post date
+    pub published_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -19,7 +123,7 @@ pub struct Post {
 impl Post {
     /// Creates a new blog post
     /// Posts can be published or saved as drafts
-    pub fn new(title: String, content: String, excerpt: String, category_id: String, author: String) -> Self {
+    pub fn new(title: String, content: String, excerpt: String, category_id: String, author_id: String) -> Self {
         let now = Utc::now();
         Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -27,32 +131,117 @@ impl Post {
             content,
             excerpt,
             category_id,
-            author,
-            published: false,
+            tag_ids: Vec::new(),
+            seo: SeoMeta::default(),
+            author_id,
+            image_url: None,
+            status: PostStatus::Draft,
+            reviewer_id: None,
+            review_comments: Vec::new(),
+            autosave: None,
+            comments_enabled: true,
+            publish_at: None,
+            published_at: None,
             created_at: now,
             updated_at: now,
         }
     }
 
     /// Updates post content and metadata
-    /// Automaticaly updates the timestamp
+    /// Automaticaly updates the timestamp and drops the autosave it replaces
     pub fn update(&mut self, title: String, content: String, excerpt: String) {
         self.title = title;
         self.content = content;
         self.excerpt = excerpt;
+        self.autosave = None;
         self.updated_at = Utc::now();
     }
 
+    /// Keeps unsaved edits from the editor
+    pub fn autosave(&mut self, title: String, content: String, excerpt: String) {
+        self.autosave = Some(PostAutosave { title, excerpt, content, saved_at: Utc::now() });
+    }
+
+    /// Checks if the post is visible on the blog
+    pub fn is_published(&self) -> bool {
+        self.status == PostStatus::Published
+    }
+
+    /// Moves the post along the editorial workflow
+    /// Only the transitions listed by `PostStatus::transitions` are allowed
+    pub fn set_status(&mut self, status: PostStatus) -> Result<(), String> {
+        if !self.status.transitions().iter().any(|(to, _)| *to == status) {
+            return Err(format!("A post can't go from {} to {}", self.status.label(), status.label()));
+        }
+        match status {
+            PostStatus::Published => self.publish(),
+            PostStatus::Draft | PostStatus::Archived => {
+                self.status = status;
+                self.publish_at = None;
+                self.updated_at = Utc::now();
+            }
+            PostStatus::InReview | PostStatus::Approved => {
+                self.status = status;
+                self.updated_at = Utc::now();
+            }
+        }
+        Ok(())
+    }
+
+    /// Estimated minutes needed to read the content, at least one
+    pub fn reading_time(&self) -> usize {
+        self.content.split_whitespace().count().div_ceil(WORDS_PER_MINUTE).max(1)
+    }
+
     /// Publishes the post making it visible to users
+    /// Republished posts keep their first publication date
     pub fn publish(&mut self) {
-        self.published = true;
+        let now = Utc::now();
+        self.status = PostStatus::Published;
+        self.publish_at = None;
+        self.published_at.get_or_insert(now);
+        self.updated_at = now;
+    }
+
+    /// Hides the post until it is published at the given time
+    /// Published posts go back to approved until then
+    pub fn schedule(&mut self, publish_at: DateTime<Utc>) {
+        if self.status == PostStatus::Published {
+            self.status = PostStatus::Approved;
+        }
+        self.publish_at = Some(publish_at);
         self.updated_at = Utc::now();
     }
 
-    /// Unpublishes the post hiding it from users
-    pub fn unpublish(&mut self) {
-        self.published = false;
+    /// Cancels a scheduled publication, the status stays as it is
+    pub fn cancel_schedule(&mut self) {
+        self.publish_at = None;
         self.updated_at = Utc::now();
     }
+
+    /// Checks if the post waits for a scheduled publication
+    pub fn is_scheduled(&self) -> bool {
+        self.publish_at.is_some() && !matches!(self.status, PostStatus::Published | PostStatus::Archived)
+    }
+
+    /// Checks if a scheduled publication time has passed
+    /// Only approved posts go live, others wait for their review
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == PostStatus::Approved && self.publish_at.is_some_and(|at| at <= now)
+    }
+
+    /// Date shown on the post, when it was published or else created
+    pub fn date(&self) -> DateTime<Utc> {
+        self.published_at.unwrap_or(self.created_at)
+    }
+
+    /// Status for admin lists, approved posts with a schedule show as scheduled
+    pub fn status_label(&self) -> &'static str {
+        if self.status == PostStatus::Approved && self.is_scheduled() {
+            "Scheduled"
+        } else {
+            self.status.label()
+        }
+    }
 }
 
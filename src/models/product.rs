@@ -1,5 +1,42 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use super::{AttributeValue, Currency, ProductOption, ProductVariant, SeoMeta, TaxClass};
+use super::variant::sku_for;
+
+/// Package size of a product in centimeters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Dimensions {
+    pub length: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl std::fmt::Display for Dimensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} × {} × {} cm", self.length, self.width, self.height)
+    }
+}
+
+/// Image in a product gallery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductImage {
+    pub id: String,
+    pub url: String,
+    /// Description for screen readers, the product name is used when empty
+    pub alt: String,
+}
+
+impl ProductImage {
+    /// Creates a new gallery image
+    pub fn new(url: String, alt: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            url,
+            alt: alt.trim().to_string(),
+        }
+    }
+}
 
 /// Represents a product in the e-commerce system
 /// Contains all necesary information about products
@@ -10,8 +47,34 @@ pub struct Product {
     pub description: String,
     pub price: f64,
     pub category_id: String,
-    pub image_url: Option<String>,
+    /// Ids of the tags, see `Store::resolve_tags`
+    pub tag_ids: Vec<String>,
+    /// Meta title and description overrides
+    pub seo: SeoMeta,
+    /// Ordered gallery, the first image is the primary image shown on cards
+    pub images: Vec<ProductImage>,
     pub stock: i32,
+    /// Fixed prices for specific currencies, keyed by currency code
+    /// Used instead of the exchange rate conversion when present
+    pub price_overrides: HashMap<String, f64>,
+    /// Option types like size or color, empty for simple products
+    pub options: Vec<ProductOption>,
+    /// Purchasable combinations of option values
+    /// When present, their stock replaces the product level stock
+    pub variants: Vec<ProductVariant>,
+    /// Values for the category attributes, keyed by attribute definition id
+    pub attributes: HashMap<String, AttributeValue>,
+    /// Stock level at which the product shows up in low stock alerts
+    /// `None` uses the store wide default
+    pub low_stock_threshold: Option<i32>,
+    /// Sale price in the base currency, active between the optional start and end
+    pub sale_price: Option<f64>,
+    pub sale_starts_at: Option<DateTime<Utc>>,
+    pub sale_ends_at: Option<DateTime<Utc>>,
+    pub tax_class: TaxClass,
+    /// Shipping weight in kilograms
+    pub weight: f64,
+    pub dimensions: Option<Dimensions>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -27,8 +90,21 @@ impl Product {
             description,
             price,
             category_id,
-            image_url: None,
+            tag_ids: Vec::new(),
+            seo: SeoMeta::default(),
+            images: Vec::new(),
             stock,
+            price_overrides: HashMap::new(),
+            options: Vec::new(),
+            variants: Vec::new(),
+            attributes: HashMap::new(),
+            low_stock_threshold: None,
+            sale_price: None,
+            sale_starts_at: None,
+            sale_ends_at: None,
+            tax_class: TaxClass::default(),
+            weight: 0.0,
+            dimensions: None,
             created_at: now,
             updated_at: now,
         }
@@ -36,16 +112,180 @@ impl Product {
 
     /// Updates the product information
     /// Automaticaly sets the updated_at timestamp
-    pub fn update(&mut self, name: String, description: String, price: f64, stock: i32) {
+    /// Stock is changed through stock movements instead
+    pub fn update(&mut self, name: String, description: String, price: f64) {
         self.name = name;
         self.description = description;
         self.price = price;
-        self.stock = stock;
         self.updated_at = Utc::now();
     }
 
+    /// Returns the product price in the given currency
+    /// Uses the per-currency override if set, otherwise converts the base price
+    pub fn price_in(&self, currency: &Currency) -> f64 {
+        self.price_overrides
+            .get(&currency.code)
+            .copied()
+            .unwrap_or_else(|| currency.convert(self.price))
+    }
+
+    /// Returns the price of a variant in the given currency
+    /// Variants without their own price use the product price
+    pub fn variant_price_in(&self, variant: &ProductVariant, currency: &Currency) -> f64 {
+        match variant.price {
+            Some(price) => currency.convert(price),
+            None => self.price_in(currency),
+        }
+    }
+
+    /// Returns the lowest and highest price in the given currency
+    /// Both are the same for products without variants
+    pub fn price_range_in(&self, currency: &Currency) -> (f64, f64) {
+        if self.variants.is_empty() {
+            let price = self.price_in(currency);
+            return (price, price);
+        }
+        self.variants
+            .iter()
+            .map(|v| self.variant_price_in(v, currency))
+            .fold((f64::MAX, f64::MIN), |(min, max), p| (min.min(p), max.max(p)))
+    }
+
+    /// Checks if a sale is running right now
+    pub fn is_on_sale(&self) -> bool {
+        let now = Utc::now();
+        match self.sale_price {
+            Some(sale_price) => {
+                sale_price < self.price
+                    && self.sale_starts_at.map(|start| now >= start).unwrap_or(true)
+                    && self.sale_ends_at.map(|end| now <= end).unwrap_or(true)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the share of the regular price paid during a running sale
+    /// The same reduction is applied to variant prices and currency overrides
+    fn sale_factor(&self) -> f64 {
+        match self.sale_price {
+            Some(sale_price) if self.is_on_sale() && self.price > 0.0 => sale_price / self.price,
+            _ => 1.0,
+        }
+    }
+
+    /// Returns the regular price of the product or one of its variants
+    pub fn regular_price_for(&self, variant: Option<&ProductVariant>, currency: &Currency) -> f64 {
+        match variant {
+            Some(variant) => self.variant_price_in(variant, currency),
+            None => self.price_in(currency),
+        }
+    }
+
+    /// Returns the price customers pay right now, with any running sale applied
+    pub fn current_price_for(&self, variant: Option<&ProductVariant>, currency: &Currency) -> f64 {
+        self.regular_price_for(variant, currency) * self.sale_factor()
+    }
+
+    /// Returns the current lowest and highest price with any running sale applied
+    pub fn current_price_range_in(&self, currency: &Currency) -> (f64, f64) {
+        let (min, max) = self.price_range_in(currency);
+        let factor = self.sale_factor();
+        (min * factor, max * factor)
+    }
+
+    /// Primary image of the gallery
+    pub fn primary_image(&self) -> Option<&ProductImage> {
+        self.images.first()
+    }
+
+    /// Alt text of a gallery image, falling back to the product name
+    pub fn image_alt<'a>(&'a self, image: &'a ProductImage) -> &'a str {
+        if image.alt.is_empty() { &self.name } else { &image.alt }
+    }
+
+    /// Puts the gallery in the order of the given image ids
+    /// Unknown ids are ignored and images missing from the list keep their place at the end
+    pub fn reorder_images(&mut self, ids: &[&str]) {
+        let mut remaining = std::mem::take(&mut self.images);
+        for id in ids {
+            if let Some(index) = remaining.iter().position(|image| image.id == *id) {
+                self.images.push(remaining.remove(index));
+            }
+        }
+        self.images.append(&mut remaining);
+        self.updated_at = Utc::now();
+    }
+
+    /// Adds an option type
+    /// Existing variants are removed since they no longer cover every option
+    pub fn add_option(&mut self, option: ProductOption) {
+        self.options.retain(|o| o.name != option.name);
+        self.options.push(option);
+        self.variants.clear();
+        self.updated_at = Utc::now();
+    }
+
+    /// Removes an option type together with all variants
+    pub fn remove_option(&mut self, name: &str) {
+        self.options.retain(|o| o.name != name);
+        self.variants.clear();
+        self.updated_at = Utc::now();
+    }
+
+    /// Creates a variant for every combination of option values that
+    /// doesn't have one yet. New variants start with no stock
+    pub fn generate_variants(&mut self) {
+        if self.options.is_empty() || self.options.iter().any(|o| o.values.is_empty()) {
+            return;
+        }
+
+        let mut combinations: Vec<Vec<&str>> = vec![Vec::new()];
+        for option in &self.options {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combo| {
+                    option.values.iter().map(move |value| {
+                        let mut next = combo.clone();
+                        next.push(value.as_str());
+                        next
+                    })
+                })
+                .collect();
+        }
+
+        let mut new_variants = Vec::new();
+        for combo in combinations {
+            let options: HashMap<String, String> = self
+                .options
+                .iter()
+                .zip(&combo)
+                .map(|(option, value)| (option.name.clone(), value.to_string()))
+                .collect();
+            if self.variants.iter().any(|v| v.options == options) {
+                continue;
+            }
+            new_variants.push(ProductVariant::new(sku_for(&self.name, &combo), options, None, 0));
+        }
+        self.variants.extend(new_variants);
+        self.updated_at = Utc::now();
+    }
+
+    /// Returns the stock across all variants, or the product stock
+    pub fn total_stock(&self) -> i32 {
+        if self.variants.is_empty() {
+            self.stock
+        } else {
+            self.variants.iter().map(|v| v.stock).sum()
+        }
+    }
+
     /// Checks if product is availabe in stock
+    /// Products with variants are available when any variant is in stock
     pub fn is_available(&self) -> bool {
-        self.stock > 0
+        if self.variants.is_empty() {
+            self.stock > 0
+        } else {
+            self.variants.iter().any(|v| v.is_available())
+        }
     }
 }
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::Post;
+
+/// Saved version of a blog post
+/// A revision is recorded every time a post is created, edited or restored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostRevision {
+    pub id: String,
+    pub post_id: String,
+    pub title: String,
+    pub excerpt: String,
+    pub content: String,
+    /// Author profile of the person who saved the revision
+    pub author_id: String,
+    /// Revision whose text was brought back, for restores
+    pub restored_from: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PostRevision {
+    /// Captures the current text of a post
+    pub fn new(post: &Post, author_id: String, restored_from: Option<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            post_id: post.id.clone(),
+            title: post.title.clone(),
+            excerpt: post.excerpt.clone(),
+            content: post.content.clone(),
+            author_id,
+            restored_from,
+            created_at: Utc::now(),
+        }
+    }
+}
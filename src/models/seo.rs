@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Search engine overrides of a product, post or category page
+/// Empty fields fall back to the name and description of the item
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeoMeta {
+    pub title: String,
+    pub description: String,
+}
+
+impl SeoMeta {
+    /// Creates overrides from form values, trimming whitespace
+    pub fn new(title: &str, description: &str) -> Self {
+        Self {
+            title: title.trim().to_string(),
+            description: description.trim().to_string(),
+        }
+    }
+
+    /// Meta title, or `fallback` without an override
+    pub fn title_or(&self, fallback: &str) -> String {
+        if self.title.is_empty() { fallback.to_string() } else { self.title.clone() }
+    }
+
+    /// Meta description, or `fallback` without an override
+    pub fn description_or(&self, fallback: &str) -> String {
+        if self.description.is_empty() { fallback.to_string() } else { self.description.clone() }
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
+
+/// Most posts a blog listing page can show
+pub const MAX_POSTS_PER_PAGE: usize = 100;
+
+/// UTC offsets offered as site timezones, in minutes
+const TIMEZONE_OFFSETS: [i32; 38] = [
+    -720, -660, -600, -570, -540, -480, -420, -360, -300, -240, -210, -180, -120, -60, 0, 60, 120, 180, 210,
+    240, 270, 300, 330, 345, 360, 390, 420, 480, 525, 540, 570, 600, 630, 660, 720, 765, 780, 840,
+];
+
+/// Site wide settings edited at `/admin/settings`
+/// Saved as JSON so they survive restarts, see `SiteSettings::load`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SiteSettings {
+    pub site_name: String,
+    /// Short line shown below the site name on the homepage
+    pub tagline: String,
+    /// Logo shown in the header instead of the site name
+    pub logo_url: Option<String>,
+    /// Line at the bottom of every page, `{year}` is replaced with the current year
+    pub footer_text: String,
+    /// Address shown in the footer and in emails for customer questions
+    pub contact_email: String,
+    /// Currency shown to visitors who haven't picked one
+    pub default_currency: String,
+    /// UTC offset dates are shown in, like `UTC` or `UTC+02:00`
+    pub timezone: String,
+    /// Posts per page of the blog listings
+    pub posts_per_page: usize,
+}
+
+impl Default for SiteSettings {
+    fn default() -> Self {
+        Self {
+            site_name: "MyStore".to_string(),
+            tagline: "Discover amazing products and read our latest articles".to_string(),
+            logo_url: None,
+            footer_text: "© {year} MyStore. All rights reserved.".to_string(),
+            contact_email: "shop@localhost".to_string(),
+            default_currency: "USD".to_string(),
+            timezone: "UTC".to_string(),
+            posts_per_page: 10,
+        }
+    }
+}
+
+impl SiteSettings {
+    /// Path of the settings file from `SETTINGS_FILE`, `settings.json` by default
+    pub fn file_from_env() -> PathBuf {
+        std::env::var("SETTINGS_FILE").unwrap_or_else(|_| "settings.json".to_string()).into()
+    }
+
+    /// Reads the settings file, using the defaults if it is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid settings file {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Writes the settings file
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("Couldn't save {}: {}", path.display(), e))
+    }
+
+    /// Footer line with the placeholders filled in
+    pub fn footer_line(&self) -> String {
+        self.footer_text.replace("{year}", &Utc::now().year().to_string())
+    }
+
+    /// Offset of the site timezone
+    pub fn utc_offset(&self) -> FixedOffset {
+        parse_timezone(&self.timezone).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// A point in time as shown on the site
+    pub fn local_time(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        at.with_timezone(&self.utc_offset())
+    }
+}
+
+/// Parses a timezone like `UTC`, `UTC+02:00` or `UTC-05:30`
+pub fn parse_timezone(value: &str) -> Option<FixedOffset> {
+    let offset = value.trim().strip_prefix("UTC")?;
+    if offset.is_empty() {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, offset) = match offset.split_at(1) {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours, minutes) = offset.split_once(':')?;
+    let minutes = hours.parse::<i32>().ok()? * 60 + minutes.parse::<i32>().ok()?;
+    FixedOffset::east_opt(sign * minutes * 60)
+}
+
+/// Timezones offered in the settings form
+pub fn timezone_choices() -> Vec<String> {
+    TIMEZONE_OFFSETS
+        .iter()
+        .map(|&minutes| match minutes {
+            0 => "UTC".to_string(),
+            _ => format!(
+                "UTC{}{:02}:{:02}",
+                if minutes < 0 { '-' } else { '+' },
+                minutes.abs() / 60,
+                minutes.abs() % 60
+            ),
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::Currency;
+
+/// How a shipping method calculates its cost
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ShippingRateType {
+    /// The same cost for every order
+    FlatRate,
+    /// Base cost plus a cost per kilogram
+    WeightBased,
+    /// Flat cost that drops to zero above an order amount
+    FreeOverThreshold,
+    /// Collected by the customer, never charged
+    LocalPickup,
+}
+
+impl ShippingRateType {
+    pub const ALL: [ShippingRateType; 4] = [
+        Self::FlatRate,
+        Self::WeightBased,
+        Self::FreeOverThreshold,
+        Self::LocalPickup,
+    ];
+
+    /// Parses the rate type from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.label().eq_ignore_ascii_case(value))
+    }
+
+    /// Human readable name of the rate type
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::FlatRate => "Flat rate",
+            Self::WeightBased => "Weight based",
+            Self::FreeOverThreshold => "Free over threshold",
+            Self::LocalPickup => "Local pickup",
+        }
+    }
+}
+
+/// Shipping method offered in a zone
+/// Amounts are in the base currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShippingMethod {
+    pub id: String,
+    pub name: String,
+    pub rate_type: ShippingRateType,
+    pub cost: f64,
+    /// Cost per kilogram for weight based rates
+    pub per_kg: f64,
+    /// Order amount from which free over threshold rates cost nothing
+    pub threshold: f64,
+}
+
+impl ShippingMethod {
+    /// Creates a new shipping method
+    pub fn new(name: String, rate_type: ShippingRateType, cost: f64, per_kg: f64, threshold: f64) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            rate_type,
+            cost,
+            per_kg,
+            threshold,
+        }
+    }
+
+    /// Calculates the cost in the given currency
+    /// The weight is in kilograms and the order amount in the given currency
+    pub fn cost_for(&self, weight: f64, order_amount: f64, currency: &Currency) -> f64 {
+        let cost = match self.rate_type {
+            ShippingRateType::FlatRate => self.cost,
+            ShippingRateType::WeightBased => self.cost + self.per_kg * weight,
+            ShippingRateType::FreeOverThreshold if order_amount >= currency.convert(self.threshold) => 0.0,
+            ShippingRateType::FreeOverThreshold => self.cost,
+            ShippingRateType::LocalPickup => 0.0,
+        };
+        currency.round(currency.convert(cost))
+    }
+
+    /// Short description like "9.99, free over 100.00"
+    pub fn describe(&self) -> String {
+        match self.rate_type {
+            ShippingRateType::FlatRate => format!("{:.2}", self.cost),
+            ShippingRateType::WeightBased => format!("{:.2} + {:.2} per kg", self.cost, self.per_kg),
+            ShippingRateType::FreeOverThreshold => format!("{:.2}, free over {:.2}", self.cost, self.threshold),
+            ShippingRateType::LocalPickup => "Free".to_string(),
+        }
+    }
+}
+
+/// Group of regions sharing the same shipping methods
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShippingZone {
+    pub id: String,
+    pub name: String,
+    /// Country codes like "DE", subdivisions like "US-CA" or "*" for everywhere else
+    pub regions: Vec<String>,
+    pub methods: Vec<ShippingMethod>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ShippingZone {
+    /// Creates a new zone from a comma separated list of region codes
+    pub fn new(name: String, regions: &str) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            regions: regions
+                .split(',')
+                .map(|r| r.trim().to_uppercase())
+                .filter(|r| !r.is_empty())
+                .collect(),
+            methods: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Checks if the zone lists the region itself or its country
+    pub fn covers(&self, region: &str) -> bool {
+        let country = region.split_once('-').map(|(country, _)| country).unwrap_or(region);
+        self.regions.iter().any(|r| r == region || r == country)
+    }
+
+    /// Checks if the zone is the catch-all for regions no other zone covers
+    pub fn is_fallback(&self) -> bool {
+        self.regions.iter().any(|r| r == "*")
+    }
+}
+
+/// Price of a shipping method for a cart
+#[derive(Debug, Clone)]
+pub struct ShippingQuote {
+    pub method_id: String,
+    pub name: String,
+    pub cost: f64,
+}
@@ -0,0 +1,28 @@
+/// Turns a name into a URL slug, e.g. "Jane O'Neil" becomes "jane-o-neil"
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.trim().to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Makes a slug unique by appending a number, e.g. "news-2"
+/// `taken` reports whether a candidate is already used
+pub fn unique_slug(text: &str, taken: impl Fn(&str) -> bool) -> String {
+    let base = match slugify(text) {
+        slug if slug.is_empty() => "item".to_string(),
+        slug => slug,
+    };
+    let mut slug = base.clone();
+    let mut counter = 2;
+    while taken(&slug) {
+        slug = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    slug
+}
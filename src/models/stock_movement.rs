@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Reason category of a stock change
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StockMovementKind {
+    /// Manual correction, e.g. after counting or damage
+    Adjustment,
+    Sale,
+    Return,
+    /// Goods received or initial stock
+    Import,
+}
+
+impl StockMovementKind {
+    /// Parses the kind from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        match value {
+            "adjustment" => Some(Self::Adjustment),
+            "sale" => Some(Self::Sale),
+            "return" => Some(Self::Return),
+            "import" => Some(Self::Import),
+            _ => None,
+        }
+    }
+
+    /// Human readable name of the kind
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Adjustment => "Adjustment",
+            Self::Sale => "Sale",
+            Self::Return => "Return",
+            Self::Import => "Import",
+        }
+    }
+
+    /// Applies the direction of the kind to a quantity
+    /// Sales always remove stock, returns and imports always add it,
+    /// adjustments keep the sign they were given
+    /// None if the quantity has no positive counterpart, i.e. `i32::MIN`
+    pub fn signed(&self, quantity: i32) -> Option<i32> {
+        match self {
+            Self::Adjustment => Some(quantity),
+            Self::Sale => quantity.checked_abs().map(|q| -q),
+            Self::Return | Self::Import => quantity.checked_abs(),
+        }
+    }
+}
+
+/// A single entry in the stock ledger
+/// Product and variant stock only ever change through these
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockMovement {
+    pub id: String,
+    pub product_id: String,
+    /// Set when the movement is for a specific variant
+    pub variant_id: Option<String>,
+    pub kind: StockMovementKind,
+    /// Change in stock, negative when stock was removed
+    pub quantity: i32,
+    /// Stock level right after this movement
+    pub stock_after: i32,
+    pub reason: String,
+    pub user: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl StockMovement {
+    /// Creates a new stock movement
+    pub fn new(
+        product_id: String,
+        variant_id: Option<String>,
+        kind: StockMovementKind,
+        quantity: i32,
+        stock_after: i32,
+        reason: String,
+        user: String,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            product_id,
+            variant_id,
+            kind,
+            quantity,
+            stock_after,
+            reason,
+            user,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Tag shared by blog posts and products
+/// Unlike categories an item can have any number of tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    /// Unique part of the archive URLs, `/blog/tag/<slug>` and `/shop/tag/<slug>`
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Tag {
+    /// Creates a new tag
+    /// The slug has to be unique, see `Store::resolve_tags`
+    pub fn new(name: String, slug: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            slug,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Tax class of a product, selecting which regional rate applies
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum TaxClass {
+    #[default]
+    Standard,
+    /// Lower rate for goods like books or food
+    Reduced,
+    Exempt,
+}
+
+impl TaxClass {
+    pub const ALL: [TaxClass; 3] = [Self::Standard, Self::Reduced, Self::Exempt];
+
+    /// Parses the tax class from a form value
+    pub fn from_form(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.label().eq_ignore_ascii_case(value))
+    }
+
+    /// Human readable name of the tax class
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::Reduced => "Reduced",
+            Self::Exempt => "Exempt",
+        }
+    }
+}
+
+/// Tax rates of a region
+/// Regions are country codes like "DE" or subdivisions like "US-CA"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxRate {
+    pub region: String,
+    pub region_name: String,
+    /// Name shown on tax lines, e.g. "VAT" or "Sales Tax"
+    pub name: String,
+    /// Percentage for standard rated products
+    pub standard_rate: f64,
+    /// Percentage for reduced rate products
+    pub reduced_rate: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaxRate {
+    /// Creates new regional tax rates
+    /// The region code is normalized to upper case
+    pub fn new(region: String, region_name: String, name: String, standard_rate: f64, reduced_rate: f64) -> Self {
+        Self {
+            region: region.trim().to_uppercase(),
+            region_name,
+            name,
+            standard_rate,
+            reduced_rate,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Percentage charged for a tax class
+    pub fn rate_for(&self, class: TaxClass) -> f64 {
+        match class {
+            TaxClass::Standard => self.standard_rate,
+            TaxClass::Reduced => self.reduced_rate,
+            TaxClass::Exempt => 0.0,
+        }
+    }
+}
+
+/// Tax charged at one rate, shown as a breakdown line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxLine {
+    pub name: String,
+    pub rate: f64,
+    pub amount: f64,
+}
+
+impl TaxLine {
+    /// Label like "VAT 19%"
+    pub fn label(&self) -> String {
+        format!("{} {}%", self.name, self.rate)
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Option type of a product, e.g. "Size" with values S, M, L
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductOption {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+impl ProductOption {
+    /// Creates a new option type from a comma separated list of values
+    /// Empty and duplicate values are skipped
+    pub fn new(name: String, values: &str) -> Self {
+        let mut parsed: Vec<String> = Vec::new();
+        for value in values.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            if !parsed.iter().any(|v| v == value) {
+                parsed.push(value.to_string());
+            }
+        }
+        Self {
+            name: name.trim().to_string(),
+            values: parsed,
+        }
+    }
+}
+
+/// A purchasable variant of a product
+/// Each variant is one combination of option values with its own SKU and stock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductVariant {
+    pub id: String,
+    pub sku: String,
+    /// Selected value for every option type, keyed by option name
+    pub options: HashMap<String, String>,
+    /// Price in the base currency, `None` uses the product price
+    pub price: Option<f64>,
+    pub stock: i32,
+}
+
+impl ProductVariant {
+    /// Creates a new variant
+    pub fn new(sku: String, options: HashMap<String, String>, price: Option<f64>, stock: i32) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            sku,
+            options,
+            price,
+            stock,
+        }
+    }
+
+    /// Checks if this variant can be bought
+    pub fn is_available(&self) -> bool {
+        self.stock > 0
+    }
+
+    /// Builds a label like "Black / M" following the order of the option types
+    pub fn title(&self, option_types: &[ProductOption]) -> String {
+        option_types
+            .iter()
+            .filter_map(|option| self.options.get(&option.name))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+/// Builds a SKU from a product name and option values
+/// e.g. "Cotton T-Shirt" + ["Black", "M"] gives "COTTON-T-SHIRT-BLACK-M"
+pub fn sku_for(name: &str, values: &[&str]) -> String {
+    std::iter::once(name)
+        .chain(values.iter().copied())
+        .flat_map(|part| part.split(|c: char| !c.is_ascii_alphanumeric()))
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_uppercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
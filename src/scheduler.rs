@@ -0,0 +1,19 @@
+//! Background jobs
+//! Runs periodic store maintenance on the tokio runtime
+
+use std::time::Duration;
+use crate::store::Store;
+
+/// How often scheduled posts are checked
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Starts the task that publishes scheduled posts once they are due
+pub fn start(store: Store) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PUBLISH_INTERVAL);
+        loop {
+            interval.tick().await;
+            store.publish_due_posts();
+        }
+    });
+}
@@ -1,6 +1,27 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
-use crate::models::{Product, Category, Post, BlogCategory};
+use crate::models::{
+    Product, Category, Post, PostRevision, PostStatus, Page, PageStatus, RESERVED_PAGE_SLUGS, MenuEntry, MenuItem, MenuLink, MenuLocation, Tag, Comment, CommentStatus, COMMENT_RATE_LIMIT, COMMENT_RATE_WINDOW_MINUTES, Author, BlogCategory, Currency, ProductOption,
+    AttributeDefinition, AttributeType, AttributeValue, StockMovement, StockMovementKind,
+    Coupon, DiscountType, Cart, CartLine, CartSummary, Order, OrderItem, OrderStatus,
+    TaxClass, TaxLine, TaxRate, Dimensions, Customer, CustomerSession, EmailVerification, PasswordReset,
+    is_valid_email, normalize_email, slugify, unique_slug, MIN_PASSWORD_LENGTH, MediaAsset, ShippingMethod, ShippingQuote, ShippingRateType, ShippingZone,
+    parse_timezone, SiteSettings, MAX_POSTS_PER_PAGE,
+};
+use chrono::{DateTime, Duration, Utc};
+use crate::email::{templates, EmailQueue};
+use crate::media::MediaStorage;
+use crate::html::escape_html;
+
+/// Product or variant whose stock is at or below its low stock threshold
+pub struct LowStockItem {
+    pub product_id: String,
+    pub name: String,
+    pub sku: Option<String>,
+    pub stock: i32,
+    pub threshold: i32,
+}
 
 /// In-memory data store for the CMS
 /// This provides a simple storage solution without needing a database
@@ -10,38 +31,1569 @@ pub struct Store {
     pub products: Arc<RwLock<HashMap<String, Product>>>,
     pub categories: Arc<RwLock<HashMap<String, Category>>>,
     pub posts: Arc<RwLock<HashMap<String, Post>>>,
+    pub authors: Arc<RwLock<HashMap<String, Author>>>,
+    /// Saved versions of posts, keyed by revision id
+    pub post_revisions: Arc<RwLock<HashMap<String, PostRevision>>>,
     pub blog_categories: Arc<RwLock<HashMap<String, BlogCategory>>>,
+    /// Reader comments on posts, keyed by comment id
+    pub comments: Arc<RwLock<HashMap<String, Comment>>>,
+    /// Recent comment times per visitor address, for the rate limit
+    pub comment_submissions: Arc<RwLock<HashMap<String, Vec<DateTime<Utc>>>>>,
+    /// Comments containing one of these words are marked as spam
+    pub spam_keywords: Arc<RwLock<Vec<String>>>,
+    /// Whether blog feeds carry the full post instead of the excerpt
+    pub feed_full_content: Arc<RwLock<bool>>,
+    /// Rules served at `/robots.txt`
+    pub robots_txt: Arc<RwLock<String>>,
+    /// Static content pages, keyed by page id
+    pub pages: Arc<RwLock<HashMap<String, Page>>>,
+    /// Page shown at `/`, the built in homepage when `None`
+    pub home_page_id: Arc<RwLock<Option<String>>>,
+    /// Items of the header and footer menus, keyed by item id
+    pub menu_items: Arc<RwLock<HashMap<String, MenuItem>>>,
+    /// Tags shared by posts and products, keyed by tag id
+    pub tags: Arc<RwLock<HashMap<String, Tag>>>,
+    pub currencies: Arc<RwLock<HashMap<String, Currency>>>,
+    /// Code of the currency product prices are entered in
+    pub base_currency: Arc<RwLock<String>>,
+    pub stock_movements: Arc<RwLock<HashMap<String, StockMovement>>>,
+    /// Default low stock threshold for products without their own
+    pub low_stock_threshold: Arc<RwLock<i32>>,
+    pub coupons: Arc<RwLock<HashMap<String, Coupon>>>,
+    pub carts: Arc<RwLock<HashMap<String, Cart>>>,
+    pub orders: Arc<RwLock<HashMap<String, Order>>>,
+    /// Regional tax rates, keyed by region code
+    pub tax_rates: Arc<RwLock<HashMap<String, TaxRate>>>,
+    /// Whether shop prices are displayed including tax
+    pub prices_include_tax: Arc<RwLock<bool>>,
+    /// Region used for tax until the customer picks one at checkout
+    pub default_region: Arc<RwLock<String>>,
+    pub shipping_zones: Arc<RwLock<HashMap<String, ShippingZone>>>,
+    pub customers: Arc<RwLock<HashMap<String, Customer>>>,
+    /// Logged in customers, keyed by session token
+    pub customer_sessions: Arc<RwLock<HashMap<String, CustomerSession>>>,
+    /// Pending password resets, keyed by token
+    pub password_resets: Arc<RwLock<HashMap<String, PasswordReset>>>,
+    /// Pending email verifications, keyed by token
+    pub email_verifications: Arc<RwLock<HashMap<String, EmailVerification>>>,
+    /// Queue for outgoing emails
+    pub mailer: EmailQueue,
+    /// Uploaded images, keyed by asset id
+    pub media_assets: Arc<RwLock<HashMap<String, MediaAsset>>>,
+    /// Where uploaded files are stored
+    pub media: MediaStorage,
+    /// Site name, contact details and display settings
+    pub settings: Arc<RwLock<SiteSettings>>,
+    /// File the settings are saved to
+    pub settings_file: PathBuf,
 }
 
 impl Store {
     /// Creates a new empty store instance
-    pub fn new() -> Self {
+    /// Emails are sent through the given queue and uploads stored in `media`
+    /// Settings are loaded from `settings_file` and saved back to it when changed
+    pub fn new(mailer: EmailQueue, media: MediaStorage, settings_file: PathBuf) -> Self {
         Self {
             products: Arc::new(RwLock::new(HashMap::new())),
             categories: Arc::new(RwLock::new(HashMap::new())),
             posts: Arc::new(RwLock::new(HashMap::new())),
+            authors: Arc::new(RwLock::new(HashMap::new())),
+            tags: Arc::new(RwLock::new(HashMap::new())),
+            feed_full_content: Arc::new(RwLock::new(false)),
+            robots_txt: Arc::new(RwLock::new(DEFAULT_ROBOTS_TXT.to_string())),
+            pages: Arc::new(RwLock::new(HashMap::new())),
+            home_page_id: Arc::new(RwLock::new(None)),
+            menu_items: Arc::new(RwLock::new(HashMap::new())),
+            comments: Arc::new(RwLock::new(HashMap::new())),
+            comment_submissions: Arc::new(RwLock::new(HashMap::new())),
+            spam_keywords: Arc::new(RwLock::new(Vec::new())),
+            post_revisions: Arc::new(RwLock::new(HashMap::new())),
             blog_categories: Arc::new(RwLock::new(HashMap::new())),
+            currencies: Arc::new(RwLock::new(HashMap::new())),
+            base_currency: Arc::new(RwLock::new("USD".to_string())),
+            stock_movements: Arc::new(RwLock::new(HashMap::new())),
+            low_stock_threshold: Arc::new(RwLock::new(5)),
+            coupons: Arc::new(RwLock::new(HashMap::new())),
+            carts: Arc::new(RwLock::new(HashMap::new())),
+            orders: Arc::new(RwLock::new(HashMap::new())),
+            tax_rates: Arc::new(RwLock::new(HashMap::new())),
+            prices_include_tax: Arc::new(RwLock::new(false)),
+            default_region: Arc::new(RwLock::new("US-CA".to_string())),
+            shipping_zones: Arc::new(RwLock::new(HashMap::new())),
+            customers: Arc::new(RwLock::new(HashMap::new())),
+            customer_sessions: Arc::new(RwLock::new(HashMap::new())),
+            password_resets: Arc::new(RwLock::new(HashMap::new())),
+            email_verifications: Arc::new(RwLock::new(HashMap::new())),
+            mailer,
+            media_assets: Arc::new(RwLock::new(HashMap::new())),
+            media,
+            settings: Arc::new(RwLock::new(SiteSettings::load(&settings_file))),
+            settings_file,
+        }
+    }
+
+    /// Marks every lock usable again after a request panicked while holding one
+    /// The data is kept as the panicking request left it
+    pub fn clear_poison(&self) {
+        self.products.clear_poison();
+        self.categories.clear_poison();
+        self.posts.clear_poison();
+        self.authors.clear_poison();
+        self.post_revisions.clear_poison();
+        self.blog_categories.clear_poison();
+        self.comments.clear_poison();
+        self.comment_submissions.clear_poison();
+        self.spam_keywords.clear_poison();
+        self.feed_full_content.clear_poison();
+        self.robots_txt.clear_poison();
+        self.pages.clear_poison();
+        self.home_page_id.clear_poison();
+        self.menu_items.clear_poison();
+        self.tags.clear_poison();
+        self.currencies.clear_poison();
+        self.base_currency.clear_poison();
+        self.stock_movements.clear_poison();
+        self.low_stock_threshold.clear_poison();
+        self.coupons.clear_poison();
+        self.carts.clear_poison();
+        self.orders.clear_poison();
+        self.tax_rates.clear_poison();
+        self.prices_include_tax.clear_poison();
+        self.default_region.clear_poison();
+        self.shipping_zones.clear_poison();
+        self.customers.clear_poison();
+        self.customer_sessions.clear_poison();
+        self.password_resets.clear_poison();
+        self.email_verifications.clear_poison();
+        self.media_assets.clear_poison();
+        self.settings.clear_poison();
+    }
+
+    /// Returns a copy of the site settings
+    pub fn settings(&self) -> SiteSettings {
+        self.settings.read().unwrap().clone()
+    }
+
+    /// Name of the site used in titles, feeds and emails
+    pub fn site_name(&self) -> String {
+        self.settings.read().unwrap().site_name.clone()
+    }
+
+    /// Validates and saves new site settings
+    /// The settings stay unchanged if the file can't be written
+    pub fn update_settings(&self, settings: SiteSettings) -> Result<(), String> {
+        if settings.site_name.trim().is_empty() {
+            return Err("Site name is required".to_string());
+        }
+        if !settings.contact_email.is_empty() && !settings.contact_email.contains('@') {
+            return Err(format!("{} is not an email address", settings.contact_email));
+        }
+        if !self.currencies.read().unwrap().contains_key(&settings.default_currency) {
+            return Err(format!("Unknown currency {}", settings.default_currency));
+        }
+        if parse_timezone(&settings.timezone).is_none() {
+            return Err(format!("Unknown timezone {}", settings.timezone));
+        }
+        if settings.posts_per_page == 0 || settings.posts_per_page > MAX_POSTS_PER_PAGE {
+            return Err(format!("Posts per page must be between 1 and {}", MAX_POSTS_PER_PAGE));
+        }
+        let mut current = self.settings.write().unwrap();
+        settings.save(&self.settings_file)?;
+        *current = settings;
+        Ok(())
+    }
+
+    /// Returns the base currency
+    /// Falls back to a plain USD definition if the configured code is missing
+    pub fn base_currency(&self) -> Currency {
+        let code = self.base_currency.read().unwrap().clone();
+        self.currencies
+            .read()
+            .unwrap()
+            .get(&code)
+            .cloned()
+            .unwrap_or_else(|| Currency::new(code, "US Dollar".to_string(), "$".to_string(), 1.0, "en-US".to_string(), 2))
+    }
+
+    /// Looks up a currency by code, falling back to the base currency
+    pub fn currency_or_base(&self, code: Option<&str>) -> Currency {
+        code.and_then(|code| self.currencies.read().unwrap().get(code).cloned())
+            .unwrap_or_else(|| self.base_currency())
+    }
+
+    /// Makes another currency the base currency
+    /// All exchange rates are rescaled so the new base has a rate of 1.0
+    /// Product, variant and sale prices, coupon amounts and shipping rates are converted so they keep their value
+    pub fn set_base_currency(&self, code: &str) {
+        // The currencies guard is released before products are locked,
+        // storefront pages read currencies while holding the products lock
+        let factor = {
+            let mut currencies = self.currencies.write().unwrap();
+            let factor = match currencies.get(code) {
+                Some(currency) if currency.rate > 0.0 => currency.rate,
+                _ => return,
+            };
+            for currency in currencies.values_mut() {
+                currency.rate /= factor;
+            }
+            factor
+        };
+
+        for product in self.products.write().unwrap().values_mut() {
+            product.price = product.price_overrides.remove(code).unwrap_or(product.price * factor);
+            for variant in &mut product.variants {
+                variant.price = variant.price.map(|price| price * factor);
+            }
+            product.sale_price = product.sale_price.map(|price| price * factor);
+        }
+        for coupon in self.coupons.write().unwrap().values_mut() {
+            if coupon.discount_type == DiscountType::FixedAmount {
+                coupon.value *= factor;
+            }
+            coupon.minimum_order = coupon.minimum_order.map(|amount| amount * factor);
+        }
+        for zone in self.shipping_zones.write().unwrap().values_mut() {
+            for method in &mut zone.methods {
+                method.cost *= factor;
+                method.per_kg *= factor;
+                method.threshold *= factor;
+            }
+        }
+        *self.base_currency.write().unwrap() = code.to_string();
+    }
+
+    /// Changes the stock of a product or one of its variants and records it in the ledger
+    /// Returns None if the product or variant doesn't exist, the quantity is zero or stock would go negative
+    pub fn record_stock_movement(
+        &self,
+        product_id: &str,
+        variant_id: Option<&str>,
+        kind: StockMovementKind,
+        quantity: i32,
+        reason: String,
+        user: String,
+    ) -> Option<StockMovement> {
+        self.record_stock_movements(&[(product_id, variant_id, quantity)], kind, &reason, &user)
+            .map(|mut movements| movements.remove(0))
+    }
+
+    /// Changes the stock of several products or variants at once, given as (product id, variant id, quantity)
+    /// Every change is checked before any is made, so either all are recorded or none
+    /// Returns None if a product or variant doesn't exist, a quantity is zero
+    /// or a stock level would go negative or overflow
+    pub fn record_stock_movements(
+        &self,
+        changes: &[(&str, Option<&str>, i32)],
+        kind: StockMovementKind,
+        reason: &str,
+        user: &str,
+    ) -> Option<Vec<StockMovement>> {
+        let mut products = self.products.write().unwrap();
+
+        // Stock levels after the changes, lines for the same item add up
+        let mut levels: HashMap<(&str, Option<&str>), i32> = HashMap::new();
+        for &(product_id, variant_id, quantity) in changes {
+            let current = match levels.get(&(product_id, variant_id)) {
+                Some(level) => *level,
+                None => *stock_of(products.get_mut(product_id)?, variant_id)?,
+            };
+            let change = kind.signed(quantity).filter(|change| *change != 0)?;
+            let level = current.checked_add(change)?;
+            if level < 0 {
+                return None;
+            }
+            levels.insert((product_id, variant_id), level);
+        }
+
+        let mut movements = Vec::new();
+        for &(product_id, variant_id, quantity) in changes {
+            let stock = stock_of(products.get_mut(product_id)?, variant_id)?;
+            // Checked in the first pass
+            let quantity = kind.signed(quantity)?;
+            *stock += quantity;
+            movements.push(StockMovement::new(
+                product_id.to_string(),
+                variant_id.map(str::to_string),
+                kind,
+                quantity,
+                *stock,
+                reason.to_string(),
+                user.to_string(),
+            ));
+        }
+        let mut ledger = self.stock_movements.write().unwrap();
+        for movement in &movements {
+            ledger.insert(movement.id.clone(), movement.clone());
+        }
+        Some(movements)
+    }
+
+    /// Records the stock a product starts with as import movements
+    /// Used when a product is created with stock already set
+    pub fn record_initial_stock(&self, product: &Product, user: &str) {
+        let mut movements = self.stock_movements.write().unwrap();
+        let mut record = |variant_id: Option<String>, stock: i32| {
+            if stock > 0 {
+                let movement = StockMovement::new(
+                    product.id.clone(),
+                    variant_id,
+                    StockMovementKind::Import,
+                    stock,
+                    stock,
+                    "Initial stock".to_string(),
+                    user.to_string(),
+                );
+                movements.insert(movement.id.clone(), movement);
+            }
+        };
+
+        if product.variants.is_empty() {
+            record(None, product.stock);
+        } else {
+            for variant in &product.variants {
+                record(Some(variant.id.clone()), variant.stock);
+            }
+        }
+    }
+
+    /// Lists products and variants at or below their low stock threshold
+    /// Sorted with the lowest stock first
+    pub fn low_stock_items(&self) -> Vec<LowStockItem> {
+        let default_threshold = *self.low_stock_threshold.read().unwrap();
+        let products = self.products.read().unwrap();
+
+        let mut items = Vec::new();
+        for product in products.values() {
+            let threshold = product.low_stock_threshold.unwrap_or(default_threshold);
+            if product.variants.is_empty() {
+                if product.stock <= threshold {
+                    items.push(LowStockItem {
+                        product_id: product.id.clone(),
+                        name: product.name.clone(),
+                        sku: None,
+                        stock: product.stock,
+                        threshold,
+                    });
+                }
+            } else {
+                for variant in product.variants.iter().filter(|v| v.stock <= threshold) {
+                    items.push(LowStockItem {
+                        product_id: product.id.clone(),
+                        name: format!("{} ({})", product.name, variant.title(&product.options)),
+                        sku: Some(variant.sku.clone()),
+                        stock: variant.stock,
+                        threshold,
+                    });
+                }
+            }
+        }
+        items.sort_by(|a, b| a.stock.cmp(&b.stock).then_with(|| a.name.cmp(&b.name)));
+        items
+    }
+
+    /// Returns the tax rates of a region
+    /// Subdivisions like "US-CA" fall back to their country when they have no rates of their own
+    pub fn tax_rate_for(&self, region: &str) -> Option<TaxRate> {
+        let tax_rates = self.tax_rates.read().unwrap();
+        tax_rates
+            .get(region)
+            .or_else(|| region.split_once('-').and_then(|(country, _)| tax_rates.get(country)))
+            .cloned()
+    }
+
+    /// Regions customers can choose at checkout as (code, name) pairs, sorted by name
+    pub fn regions(&self) -> Vec<(String, String)> {
+        let mut regions: Vec<(String, String)> = self
+            .tax_rates
+            .read()
+            .unwrap()
+            .values()
+            .map(|rate| (rate.region.clone(), rate.region_name.clone()))
+            .collect();
+        for zone in self.shipping_zones.read().unwrap().values() {
+            for region in &zone.regions {
+                if region != "*" && !regions.iter().any(|(code, _)| code == region) {
+                    regions.push((region.clone(), region.clone()));
+                }
+            }
+        }
+        regions.sort_by(|a, b| a.1.cmp(&b.1));
+        regions
+    }
+
+    /// Returns the shipping zone of a region
+    /// Falls back to the catch-all zone when no zone lists the region
+    pub fn shipping_zone_for(&self, region: &str) -> Option<ShippingZone> {
+        let zones = self.shipping_zones.read().unwrap();
+        let mut zones_vec: Vec<_> = zones.values().collect();
+        zones_vec.sort_by_key(|z| z.created_at);
+        zones_vec
+            .iter()
+            .find(|z| z.covers(region))
+            .or_else(|| zones_vec.iter().find(|z| z.is_fallback()))
+            .map(|z| (*z).clone())
+    }
+
+    /// Tax percentage to add to catalog prices of a tax class
+    /// Zero unless prices are displayed including tax, uses the default region
+    pub fn display_tax_rate(&self, class: TaxClass) -> f64 {
+        if !*self.prices_include_tax.read().unwrap() {
+            return 0.0;
+        }
+        let region = self.default_region.read().unwrap().clone();
+        self.tax_rate_for(&region).map(|rate| rate.rate_for(class)).unwrap_or(0.0)
+    }
+
+    /// Prices a cart in the given currency
+    /// Applies running sales, the cart coupon, shipping and the taxes of the cart region
+    /// Items that no longer exist are skipped
+    pub fn summarize_cart(&self, cart: &Cart, currency: &Currency) -> CartSummary {
+        let region = cart
+            .region
+            .clone()
+            .unwrap_or_else(|| self.default_region.read().unwrap().clone());
+        let tax_rate = self.tax_rate_for(&region);
+        let prices_include_tax = *self.prices_include_tax.read().unwrap();
+        let products = self.products.read().unwrap();
+
+        let mut lines = Vec::new();
+        for item in &cart.items {
+            let product = match products.get(&item.product_id) {
+                Some(product) => product,
+                None => continue,
+            };
+            let variant = match &item.variant_id {
+                Some(variant_id) => match product.variants.iter().find(|v| &v.id == variant_id) {
+                    Some(variant) => Some(variant),
+                    None => continue,
+                },
+                // Products that got variants after being added need a variant picked
+                None if !product.variants.is_empty() => continue,
+                None => None,
+            };
+
+            let unit_price = currency.round(product.current_price_for(variant, currency));
+            lines.push(CartLine {
+                product_id: product.id.clone(),
+                category_id: product.category_id.clone(),
+                variant_id: variant.map(|v| v.id.clone()),
+                name: match variant {
+                    Some(variant) => format!("{} ({})", product.name, variant.title(&product.options)),
+                    None => product.name.clone(),
+                },
+                sku: variant.map(|v| v.sku.clone()),
+                regular_price: currency.round(product.regular_price_for(variant, currency)),
+                unit_price,
+                quantity: item.quantity,
+                total: currency.round(unit_price * item.quantity as f64),
+                available: variant.map(|v| v.stock).unwrap_or(product.stock),
+                tax_rate: tax_rate.as_ref().map(|rate| rate.rate_for(product.tax_class)).unwrap_or(0.0),
+                weight: product.weight,
+            });
+        }
+
+        let subtotal = currency.round(lines.iter().map(|l| l.total).sum());
+        let mut summary = CartSummary {
+            lines,
+            region,
+            prices_include_tax,
+            subtotal,
+            coupon: None,
+            coupon_error: None,
+            discount: 0.0,
+            free_shipping: false,
+            shipping_options: Vec::new(),
+            shipping: None,
+            tax_lines: Vec::new(),
+            tax: 0.0,
+            total: subtotal,
+        };
+
+        if let Some(code) = &cart.coupon_code {
+            let coupons = self.coupons.read().unwrap();
+            match coupons.values().find(|c| &c.code == code) {
+                None => summary.coupon_error = Some("Unknown coupon code".to_string()),
+                Some(coupon) => match self.coupon_discount(coupon, &summary, currency) {
+                    Ok(discount) => {
+                        summary.discount = discount;
+                        summary.free_shipping = coupon.discount_type == DiscountType::FreeShipping;
+                        summary.coupon = Some(coupon.clone());
+                    }
+                    Err(error) => summary.coupon_error = Some(error),
+                },
+            }
+        }
+
+        // Spread the discount over the eligible lines so tax is charged on what is paid
+        let eligible: f64 = summary
+            .lines
+            .iter()
+            .filter(|l| summary.coupon.as_ref().map(|c| c.applies_to(&l.product_id, &l.category_id)).unwrap_or(false))
+            .map(|l| l.total)
+            .sum();
+        let tax_name = tax_rate.map(|rate| rate.name).unwrap_or_default();
+        let mut tax_lines: Vec<TaxLine> = Vec::new();
+        for line in summary.lines.iter().filter(|l| l.tax_rate > 0.0) {
+            let applies = summary.coupon.as_ref().map(|c| c.applies_to(&line.product_id, &line.category_id)).unwrap_or(false);
+            let share = if applies && eligible > 0.0 { summary.discount * line.total / eligible } else { 0.0 };
+            let amount = (line.total - share) * line.tax_rate / 100.0;
+            match tax_lines.iter_mut().find(|t| t.rate == line.tax_rate) {
+                Some(tax_line) => tax_line.amount += amount,
+                None => tax_lines.push(TaxLine { name: tax_name.clone(), rate: line.tax_rate, amount }),
+            }
+        }
+        for tax_line in &mut tax_lines {
+            tax_line.amount = currency.round(tax_line.amount);
+        }
+        tax_lines.sort_by(|a, b| b.rate.total_cmp(&a.rate));
+
+        summary.tax = currency.round(tax_lines.iter().map(|t| t.amount).sum());
+        summary.tax_lines = tax_lines;
+
+        if let Some(zone) = self.shipping_zone_for(&summary.region) {
+            let weight = summary.weight();
+            let order_amount = summary.subtotal - summary.discount;
+            summary.shipping_options = zone
+                .methods
+                .iter()
+                .map(|method| ShippingQuote {
+                    method_id: method.id.clone(),
+                    name: method.name.clone(),
+                    cost: if summary.free_shipping { 0.0 } else { method.cost_for(weight, order_amount, currency) },
+                })
+                .collect();
+        }
+        summary.shipping = cart
+            .shipping_method_id
+            .as_ref()
+            .and_then(|id| summary.shipping_options.iter().find(|q| &q.method_id == id))
+            .cloned();
+
+        summary.total = currency.round(summary.subtotal - summary.discount + summary.tax + summary.shipping_cost());
+        summary
+    }
+
+    /// Calculates the discount a coupon gives on a priced cart
+    fn coupon_discount(&self, coupon: &Coupon, summary: &CartSummary, currency: &Currency) -> Result<f64, String> {
+        coupon.check_usable(Utc::now()).map_err(str::to_string)?;
+
+        if let Some(minimum) = coupon.minimum_order.map(|m| currency.convert(m)) {
+            if summary.subtotal < minimum {
+                return Err(format!("Spend at least {} to use this coupon", currency.format(minimum)));
+            }
+        }
+
+        let eligible: f64 = summary
+            .lines
+            .iter()
+            .filter(|l| coupon.applies_to(&l.product_id, &l.category_id))
+            .map(|l| l.total)
+            .sum();
+        if eligible <= 0.0 && coupon.discount_type != DiscountType::FreeShipping {
+            return Err("This coupon doesn't apply to any items in your cart".to_string());
+        }
+
+        Ok(currency.round(match coupon.discount_type {
+            DiscountType::Percentage => eligible * coupon.value.min(100.0) / 100.0,
+            DiscountType::FixedAmount => currency.convert(coupon.value).min(eligible),
+            DiscountType::FreeShipping => 0.0,
+        }))
+    }
+
+    /// Turns a cart into an order, the name, email and address are required
+    /// Removes the ordered items from stock, counts the coupon use, deletes the cart
+    /// and emails a confirmation
+    pub fn place_order(
+        &self,
+        cart_id: &str,
+        currency: &Currency,
+        customer_id: Option<String>,
+        customer_name: String,
+        email: String,
+        address: String,
+    ) -> Result<Order, String> {
+        if customer_name.trim().is_empty() {
+            return Err("Please enter your name".to_string());
+        }
+        if !is_valid_email(&email) {
+            return Err("Please enter a valid email address".to_string());
+        }
+        if address.trim().is_empty() {
+            return Err("Please enter your address".to_string());
+        }
+
+        let cart = self
+            .carts
+            .read()
+            .unwrap()
+            .get(cart_id)
+            .cloned()
+            .ok_or_else(|| "Your cart is empty".to_string())?;
+
+        let summary = self.summarize_cart(&cart, currency);
+        if summary.lines.is_empty() {
+            return Err("Your cart is empty".to_string());
+        }
+        if !summary.in_stock() {
+            return Err("Some items in your cart are no longer in stock".to_string());
+        }
+        if summary.shipping_options.is_empty() {
+            return Err("We don't ship to this region yet".to_string());
+        }
+        if summary.shipping.is_none() {
+            return Err("Please choose a shipping method".to_string());
+        }
+
+        // Holding the orders lock keeps concurrent checkouts from taking the same stock or coupon use
+        let mut orders = self.orders.write().unwrap();
+        if let Some(coupon) = &summary.coupon {
+            if let Some(coupon) = self.coupons.read().unwrap().get(&coupon.id) {
+                coupon.check_usable(Utc::now()).map_err(str::to_string)?;
+            }
+        }
+
+        let number = 1001 + orders.len() as u32;
+        let sold: Vec<_> = summary
+            .lines
+            .iter()
+            .map(|line| (line.product_id.as_str(), line.variant_id.as_deref(), line.quantity))
+            .collect();
+        self.record_stock_movements(&sold, StockMovementKind::Sale, &format!("Order #{}", number), &email)
+            .ok_or_else(|| "Some items in your cart are no longer in stock".to_string())?;
+        if let Some(coupon) = &summary.coupon {
+            if let Some(coupon) = self.coupons.write().unwrap().get_mut(&coupon.id) {
+                coupon.times_used += 1;
+            }
+        }
+
+        let mut order = Order::new(number, customer_name, email, address, summary.region.clone(), currency.code.clone());
+        order.customer_id = customer_id;
+        for line in &summary.lines {
+            order.items.push(OrderItem {
+                product_id: line.product_id.clone(),
+                variant_id: line.variant_id.clone(),
+                name: line.name.clone(),
+                sku: line.sku.clone(),
+                unit_price: line.unit_price,
+                quantity: line.quantity,
+                total: line.total,
+                tax_rate: line.tax_rate,
+            });
+        }
+        order.subtotal = summary.subtotal;
+        order.coupon_code = summary.coupon.as_ref().map(|c| c.code.clone());
+        order.discount = summary.discount;
+        order.shipping_method = summary.shipping.as_ref().map(|s| s.name.clone());
+        order.shipping = summary.shipping_cost();
+        order.tax_lines = summary.tax_lines.clone();
+        order.tax = summary.tax;
+        order.total = summary.total;
+
+        orders.insert(order.id.clone(), order.clone());
+        self.carts.write().unwrap().remove(cart_id);
+        self.mailer.send(templates::order_confirmation(&self.settings(), &order, currency));
+        Ok(order)
+    }
+
+    /// Changes the status of an order
+    /// Cancelling an order puts its items back into stock, reopening it takes them out again,
+    /// shipping it emails the customer
+    /// Fails when a cancelled order is reopened and its items are no longer in stock
+    pub fn set_order_status(&self, order_id: &str, status: OrderStatus) -> Result<(), String> {
+        let mut orders = self.orders.write().unwrap();
+        let order = orders.get_mut(order_id).ok_or_else(|| "Order not found".to_string())?;
+        if order.status == status {
+            return Ok(());
+        }
+
+        let items: Vec<_> = order
+            .items
+            .iter()
+            .map(|item| (item.product_id.as_str(), item.variant_id.as_deref(), item.quantity))
+            .collect();
+        if status == OrderStatus::Cancelled {
+            // Items of deleted products can't go back into stock, the others still do
+            for &item in &items {
+                self.record_stock_movements(
+                    &[item],
+                    StockMovementKind::Return,
+                    &format!("Order #{} cancelled", order.number),
+                    "Admin",
+                );
+            }
+        } else if order.status == OrderStatus::Cancelled {
+            self.record_stock_movements(
+                &items,
+                StockMovementKind::Sale,
+                &format!("Order #{} reopened", order.number),
+                "Admin",
+            )
+            .ok_or_else(|| "The items of this order are no longer in stock".to_string())?;
+        }
+        order.set_status(status);
+        if status == OrderStatus::Shipped {
+            self.mailer.send(templates::shipping_notification(&self.settings(), order));
+        }
+        Ok(())
+    }
+
+    /// Finds a customer by email
+    pub fn customer_by_email(&self, email: &str) -> Option<Customer> {
+        let email = normalize_email(email);
+        self.customers.read().unwrap().values().find(|c| c.email == email).cloned()
+    }
+
+    /// Creates a customer account
+    /// Guest orders placed with the same email are linked to the new account
+    pub fn register_customer(&self, name: String, email: String, password: &str) -> Result<Customer, String> {
+        if name.trim().is_empty() {
+            return Err("Please enter your name".to_string());
+        }
+        if !is_valid_email(&email) {
+            return Err("Please enter a valid email address".to_string());
+        }
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(format!("Passwords need at least {} characters", MIN_PASSWORD_LENGTH));
+        }
+
+        // Hashing is slow, so it happens before the check and insert under one lock
+        let customer = Customer::new(name.trim().to_string(), email, password);
+        {
+            let mut customers = self.customers.write().unwrap();
+            if customers.values().any(|c| c.email == customer.email) {
+                return Err("An account with this email already exists".to_string());
+            }
+            customers.insert(customer.id.clone(), customer.clone());
+        }
+        self.send_email_verification(&customer);
+        Ok(customer)
+    }
+
+    /// Checks login details, returning the customer when they match
+    /// Guest orders placed since the last login are linked to verified accounts
+    pub fn authenticate_customer(&self, email: &str, password: &str) -> Option<Customer> {
+        let customer = self.customer_by_email(email).filter(|c| c.verify_password(password))?;
+        self.link_guest_orders(&customer);
+        Some(customer)
+    }
+
+    /// Emails a link the customer opens to verify their email
+    pub fn send_email_verification(&self, customer: &Customer) {
+        let token = random_token();
+        self.email_verifications
+            .write()
+            .unwrap()
+            .insert(token.clone(), EmailVerification::new(customer.id.clone()));
+        self.mailer.send(templates::email_verification(&self.settings(), customer, &token));
+    }
+
+    /// Marks the email of a customer as verified using a verification token
+    /// The token is used up and guest orders with the email are linked to the account
+    pub fn verify_email(&self, token: &str) -> Result<Customer, String> {
+        let verification = self
+            .email_verifications
+            .write()
+            .unwrap()
+            .remove(token)
+            .filter(|verification| verification.expires_at > Utc::now())
+            .ok_or_else(|| "This verification link is invalid or has expired".to_string())?;
+        self.mark_email_verified(&verification.customer_id)
+            .ok_or_else(|| "This account no longer exists".to_string())
+    }
+
+    /// Marks the email of a customer as verified and links their guest orders
+    fn mark_email_verified(&self, customer_id: &str) -> Option<Customer> {
+        let customer = {
+            let mut customers = self.customers.write().unwrap();
+            let customer = customers.get_mut(customer_id)?;
+            customer.email_verified = true;
+            customer.clone()
+        };
+        self.link_guest_orders(&customer);
+        Some(customer)
+    }
+
+    /// Links guest orders with the customer's email to the account
+    /// Does nothing until the customer has verified the email, so nobody can claim orders by signing up with someone else's address
+    pub fn link_guest_orders(&self, customer: &Customer) {
+        if !customer.email_verified {
+            return;
+        }
+        for order in self.orders.write().unwrap().values_mut() {
+            if order.customer_id.is_none() && normalize_email(&order.email) == customer.email {
+                order.customer_id = Some(customer.id.clone());
+            }
+        }
+    }
+
+    /// Orders of a customer, newest first
+    pub fn customer_orders(&self, customer_id: &str) -> Vec<Order> {
+        let mut orders: Vec<Order> = self
+            .orders
+            .read()
+            .unwrap()
+            .values()
+            .filter(|o| o.customer_id.as_deref() == Some(customer_id))
+            .cloned()
+            .collect();
+        orders.sort_by_key(|o| std::cmp::Reverse(o.created_at));
+        orders
+    }
+
+    /// Starts a session for a customer and returns its token
+    pub fn create_session(&self, customer_id: &str) -> String {
+        let token = random_token();
+        self.customer_sessions
+            .write()
+            .unwrap()
+            .insert(token.clone(), CustomerSession::new(customer_id.to_string()));
+        token
+    }
+
+    /// Returns the customer of an unexpired session
+    pub fn session_customer(&self, token: &str) -> Option<Customer> {
+        let customer_id = self
+            .customer_sessions
+            .read()
+            .unwrap()
+            .get(token)
+            .filter(|session| session.expires_at > Utc::now())
+            .map(|session| session.customer_id.clone())?;
+        self.customers.read().unwrap().get(&customer_id).cloned()
+    }
+
+    /// Ends a session
+    pub fn end_session(&self, token: &str) {
+        self.customer_sessions.write().unwrap().remove(token);
+    }
+
+    /// Emails a password reset link to the account with this email
+    /// Does nothing when there is no such account
+    pub fn send_password_reset(&self, email: &str) {
+        let customer = match self.customer_by_email(email) {
+            Some(customer) => customer,
+            None => return,
+        };
+        let token = random_token();
+        self.password_resets
+            .write()
+            .unwrap()
+            .insert(token.clone(), PasswordReset::new(customer.id.clone()));
+        self.mailer.send(templates::password_reset(&self.settings(), &customer, &token));
+    }
+
+    /// Checks that a password reset token exists and hasn't expired
+    pub fn password_reset_valid(&self, token: &str) -> bool {
+        self.password_resets
+            .read()
+            .unwrap()
+            .get(token)
+            .map(|reset| reset.expires_at > Utc::now())
+            .unwrap_or(false)
+    }
+
+    /// Sets a new password using a reset token
+    /// The token is used up and all sessions of the customer are ended
+    pub fn reset_password(&self, token: &str, password: &str) -> Result<(), String> {
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(format!("Passwords need at least {} characters", MIN_PASSWORD_LENGTH));
+        }
+        let reset = self
+            .password_resets
+            .write()
+            .unwrap()
+            .remove(token)
+            .filter(|reset| reset.expires_at > Utc::now())
+            .ok_or_else(|| "This reset link is invalid or has expired".to_string())?;
+
+        if let Some(customer) = self.customers.write().unwrap().get_mut(&reset.customer_id) {
+            customer.set_password(password);
+        }
+        // The reset link went to the customer's email, which proves they own it
+        self.mark_email_verified(&reset.customer_id);
+        self.customer_sessions
+            .write()
+            .unwrap()
+            .retain(|_, session| session.customer_id != reset.customer_id);
+        Ok(())
+    }
+
+    /// Creates an author profile with a unique slug derived from the name
+    pub fn create_author(&self, name: String, bio: String) -> Author {
+        let mut authors = self.authors.write().unwrap();
+        let slug = unique_slug(&name, |slug| authors.values().any(|a| a.slug == slug));
+        let author = Author::new(name, slug, bio);
+        authors.insert(author.id.clone(), author.clone());
+        author
+    }
+
+    /// Updates an author profile
+    /// Fails when another author already uses the slug
+    pub fn update_author(&self, id: &str, name: String, slug: &str, bio: String) -> Result<(), String> {
+        let mut authors = self.authors.write().unwrap();
+        let slug = match slugify(slug) {
+            slug if slug.is_empty() => slugify(&name),
+            slug => slug,
+        };
+        if authors.values().any(|a| a.slug == slug && a.id != id) {
+            return Err(format!("Another author already uses the slug \"{}\"", slug));
+        }
+        let author = authors.get_mut(id).ok_or_else(|| "Author not found".to_string())?;
+        author.name = name;
+        author.slug = slug;
+        author.bio = bio;
+        author.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Deletes an author profile
+    /// Authors with posts can't be deleted, their posts need a new author first
+    pub fn delete_author(&self, id: &str) -> Result<(), String> {
+        let post_count = self.posts.read().unwrap().values().filter(|p| p.author_id == id).count();
+        match post_count {
+            0 => {}
+            1 => return Err("The author still has a post".to_string()),
+            n => return Err(format!("The author still has {} posts", n)),
+        }
+        self.authors.write().unwrap().remove(id);
+        Ok(())
+    }
+
+    /// Finds an author by the slug of their archive page
+    pub fn author_by_slug(&self, slug: &str) -> Option<Author> {
+        self.authors.read().unwrap().values().find(|a| a.slug == slug).cloned()
+    }
+
+    /// Turns a comma separated list of tag names into tag ids
+    /// Names are matched case-insensitively, unknown names create new tags
+    pub fn resolve_tags(&self, names: &str) -> Vec<String> {
+        let mut tags = self.tags.write().unwrap();
+        let mut ids: Vec<String> = Vec::new();
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let existing = tags.values().find(|t| t.name.eq_ignore_ascii_case(name) || t.slug == slugify(name));
+            let id = match existing {
+                Some(tag) => tag.id.clone(),
+                None => {
+                    let slug = unique_slug(name, |slug| tags.values().any(|t| t.slug == slug));
+                    let tag = Tag::new(name.to_string(), slug);
+                    let id = tag.id.clone();
+                    tags.insert(id.clone(), tag);
+                    id
+                }
+            };
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
         }
+        ids
+    }
+
+    /// Tags with the given ids sorted by name, unknown ids are skipped
+    pub fn tags_for(&self, ids: &[String]) -> Vec<Tag> {
+        let tags = self.tags.read().unwrap();
+        let mut found: Vec<_> = ids.iter().filter_map(|id| tags.get(id)).cloned().collect();
+        found.sort_by_key(|t| t.name.to_lowercase());
+        found
+    }
+
+    /// Finds a tag by the slug of its archive pages
+    pub fn tag_by_slug(&self, slug: &str) -> Option<Tag> {
+        self.tags.read().unwrap().values().find(|t| t.slug == slug).cloned()
+    }
+
+    /// Renames a tag
+    /// Fails when another tag already uses the name or slug
+    pub fn update_tag(&self, id: &str, name: String, slug: &str) -> Result<(), String> {
+        let mut tags = self.tags.write().unwrap();
+        let name = name.trim().to_string();
+        let slug = match slugify(slug) {
+            slug if slug.is_empty() => slugify(&name),
+            slug => slug,
+        };
+        if name.is_empty() || slug.is_empty() {
+            return Err("The tag needs a name".to_string());
+        }
+        if let Some(other) = tags.values().find(|t| t.id != id && (t.slug == slug || t.name.eq_ignore_ascii_case(&name))) {
+            return Err(format!("The tag \"{}\" already uses this name or slug", other.name));
+        }
+        let tag = tags.get_mut(id).ok_or_else(|| "Tag not found".to_string())?;
+        tag.name = name;
+        tag.slug = slug;
+        Ok(())
+    }
+
+    /// Deletes a tag and removes it from every post and product
+    pub fn delete_tag(&self, id: &str) {
+        for product in self.products.write().unwrap().values_mut() {
+            product.tag_ids.retain(|t| t != id);
+        }
+        for post in self.posts.write().unwrap().values_mut() {
+            post.tag_ids.retain(|t| t != id);
+        }
+        self.tags.write().unwrap().remove(id);
+    }
+
+    /// Creates a draft page with a unique slug derived from the title
+    pub fn create_page(&self, title: String, body: String, parent_id: Option<String>) -> Page {
+        let mut pages = self.pages.write().unwrap();
+        let parent_id = parent_id.filter(|id| pages.contains_key(id));
+        let slug = unique_slug(&title, |slug| {
+            RESERVED_PAGE_SLUGS.contains(&slug) || pages.values().any(|p| p.slug == slug)
+        });
+        let page = Page::new(title, slug, body, parent_id);
+        pages.insert(page.id.clone(), page.clone());
+        page
+    }
+
+    /// Updates the text and place of a page
+    /// Fails when the slug is taken or the parent is the page itself or one of its subpages
+    pub fn update_page(&self, id: &str, title: String, slug: &str, body: String, parent_id: Option<String>) -> Result<(), String> {
+        let mut pages = self.pages.write().unwrap();
+        if title.is_empty() {
+            return Err("The page needs a title".to_string());
+        }
+        let slug = match slugify(slug) {
+            slug if slug.is_empty() => slugify(&title),
+            slug => slug,
+        };
+        if RESERVED_PAGE_SLUGS.contains(&slug.as_str()) {
+            return Err(format!("The slug \"{}\" is used by another part of the site", slug));
+        }
+        if let Some(other) = pages.values().find(|p| p.slug == slug && p.id != id) {
+            return Err(format!("The page \"{}\" already uses the slug \"{}\"", other.title, slug));
+        }
+        // Walk up from the new parent, reaching this page would make a loop
+        let mut ancestor = parent_id.clone();
+        while let Some(ancestor_id) = ancestor {
+            if ancestor_id == id {
+                return Err("A page can't be placed under itself or one of its subpages".to_string());
+            }
+            ancestor = pages
+                .get(&ancestor_id)
+                .ok_or_else(|| "The parent page doesn't exist".to_string())?
+                .parent_id
+                .clone();
+        }
+        let page = pages.get_mut(id).ok_or_else(|| "Page not found".to_string())?;
+        page.title = title;
+        page.slug = slug;
+        page.body = body;
+        page.parent_id = parent_id;
+        page.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Deletes a page and its menu items, its subpages move up to its parent
+    pub fn delete_page(&self, id: &str) {
+        let mut pages = self.pages.write().unwrap();
+        if let Some(page) = pages.remove(id) {
+            for child in pages.values_mut().filter(|p| p.parent_id.as_deref() == Some(id)) {
+                child.parent_id = page.parent_id.clone();
+            }
+        }
+        drop(pages);
+        self.remove_menu_links(&MenuLink::Page(id.to_string()));
+        let mut home_page_id = self.home_page_id.write().unwrap();
+        if home_page_id.as_deref() == Some(id) {
+            *home_page_id = None;
+        }
+    }
+
+    /// Finds a page by its URL slug
+    pub fn page_by_slug(&self, slug: &str) -> Option<Page> {
+        self.pages.read().unwrap().values().find(|p| p.slug == slug).cloned()
+    }
+
+    /// Parents of a page, starting with the top level page
+    pub fn page_ancestors(&self, page: &Page) -> Vec<Page> {
+        let pages = self.pages.read().unwrap();
+        let mut ancestors = Vec::new();
+        let mut parent_id = page.parent_id.clone();
+        while let Some(parent) = parent_id.and_then(|id| pages.get(&id)) {
+            ancestors.insert(0, parent.clone());
+            parent_id = parent.parent_id.clone();
+        }
+        ancestors
+    }
+
+    /// Pages in tree order with their depth, children sorted by title under their parent
+    pub fn page_tree(&self) -> Vec<(usize, Page)> {
+        fn add_children(pages: &[Page], parent_id: Option<&str>, depth: usize, tree: &mut Vec<(usize, Page)>) {
+            for page in pages.iter().filter(|p| p.parent_id.as_deref() == parent_id) {
+                tree.push((depth, page.clone()));
+                add_children(pages, Some(&page.id), depth + 1, tree);
+            }
+        }
+        let mut pages: Vec<_> = self.pages.read().unwrap().values().cloned().collect();
+        pages.sort_by(|a, b| a.title.cmp(&b.title));
+        let mut tree = Vec::new();
+        add_children(&pages, None, 0, &mut tree);
+        tree
+    }
+
+    /// Published page shown at `/`, if one is set
+    pub fn home_page(&self) -> Option<Page> {
+        let id = self.home_page_id.read().unwrap().clone()?;
+        self.pages.read().unwrap().get(&id).filter(|p| p.is_published()).cloned()
+    }
+
+    /// Uses a page as the homepage, `None` restores the built in homepage
+    pub fn set_home_page(&self, id: Option<String>) -> Result<(), String> {
+        if let Some(id) = &id {
+            match self.pages.read().unwrap().get(id) {
+                Some(page) if page.is_published() => {}
+                Some(_) => return Err("Only published pages can be the homepage".to_string()),
+                None => return Err("Page not found".to_string()),
+            }
+        }
+        *self.home_page_id.write().unwrap() = id;
+        Ok(())
+    }
+
+    /// Checks that `parent_id` can hold the menu item `id` of `location`
+    /// The parent has to be in the same menu and can't be the item itself or one of its children
+    fn check_menu_parent(items: &HashMap<String, MenuItem>, id: &str, location: MenuLocation, parent_id: Option<&str>) -> Result<(), String> {
+        let mut ancestor = parent_id;
+        while let Some(ancestor_id) = ancestor {
+            if ancestor_id == id {
+                return Err("A menu item can't be nested under itself or one of its children".to_string());
+            }
+            let parent = items.get(ancestor_id).ok_or_else(|| "The parent item doesn't exist".to_string())?;
+            if parent.location != location {
+                return Err(format!("The parent item is in the {} menu", parent.location.label().to_lowercase()));
+            }
+            ancestor = parent.parent_id.as_deref();
+        }
+        Ok(())
+    }
+
+    /// Position after the last item with the same parent
+    fn next_menu_position(items: &HashMap<String, MenuItem>, location: MenuLocation, parent_id: Option<&str>) -> i32 {
+        items
+            .values()
+            .filter(|i| i.location == location && i.parent_id.as_deref() == parent_id)
+            .map(|i| i.position + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Adds an item at the end of its menu or parent item
+    pub fn add_menu_item(&self, mut item: MenuItem) -> Result<(), String> {
+        let mut items = self.menu_items.write().unwrap();
+        if item.label.is_empty() {
+            return Err("The menu item needs a label".to_string());
+        }
+        Self::check_menu_parent(&items, &item.id, item.location, item.parent_id.as_deref())?;
+        item.position = Self::next_menu_position(&items, item.location, item.parent_id.as_deref());
+        items.insert(item.id.clone(), item);
+        Ok(())
+    }
+
+    /// Changes the label, target and parent of a menu item
+    /// An item moved to another parent goes to the end of it
+    pub fn update_menu_item(&self, id: &str, label: String, link: MenuLink, parent_id: Option<String>) -> Result<(), String> {
+        let mut items = self.menu_items.write().unwrap();
+        if label.is_empty() {
+            return Err("The menu item needs a label".to_string());
+        }
+        let location = items.get(id).ok_or_else(|| "Menu item not found".to_string())?.location;
+        Self::check_menu_parent(&items, id, location, parent_id.as_deref())?;
+        let position = Self::next_menu_position(&items, location, parent_id.as_deref());
+        let item = items.get_mut(id).ok_or_else(|| "Menu item not found".to_string())?;
+        if item.parent_id != parent_id {
+            item.position = position;
+        }
+        item.label = label;
+        item.link = link;
+        item.parent_id = parent_id;
+        Ok(())
+    }
+
+    /// Deletes a menu item, its children move up to its parent and take its place
+    pub fn delete_menu_item(&self, id: &str) {
+        let mut items = self.menu_items.write().unwrap();
+        if let Some(item) = items.remove(id) {
+            for child in items.values_mut().filter(|i| i.parent_id.as_deref() == Some(id)) {
+                child.parent_id = item.parent_id.clone();
+                child.position = item.position;
+            }
+        }
+    }
+
+    /// Swaps a menu item with the previous or next item of the same parent
+    pub fn move_menu_item(&self, id: &str, up: bool) {
+        let mut items = self.menu_items.write().unwrap();
+        let item = match items.get(id) {
+            Some(item) => item.clone(),
+            None => return,
+        };
+        let neighbour = items
+            .values()
+            .filter(|i| i.location == item.location && i.parent_id == item.parent_id && i.id != item.id)
+            .filter(|i| if up { i.position <= item.position } else { i.position >= item.position })
+            .min_by_key(|i| (i.position - item.position).abs())
+            .map(|i| (i.id.clone(), i.position));
+        if let Some((neighbour_id, neighbour_position)) = neighbour {
+            // Items that share a position are pushed apart so the swap always changes the order
+            let position = if neighbour_position == item.position {
+                if up { item.position + 1 } else { item.position - 1 }
+            } else {
+                item.position
+            };
+            if let Some(other) = items.get_mut(&neighbour_id) {
+                other.position = position;
+            }
+            if let Some(moved) = items.get_mut(id) {
+                moved.position = neighbour_position;
+            }
+        }
+    }
+
+    /// Removes the menu items pointing to a deleted page, category or post
+    /// Their children move up to their parent
+    pub fn remove_menu_links(&self, link: &MenuLink) {
+        let ids: Vec<_> = self
+            .menu_items
+            .read()
+            .unwrap()
+            .values()
+            .filter(|i| i.link == *link)
+            .map(|i| i.id.clone())
+            .collect();
+        for id in ids {
+            self.delete_menu_item(&id);
+        }
+    }
+
+    /// Items of a menu in display order with their depth
+    pub fn menu_tree(&self, location: MenuLocation) -> Vec<(usize, MenuItem)> {
+        fn add_children(items: &[MenuItem], parent_id: Option<&str>, depth: usize, tree: &mut Vec<(usize, MenuItem)>) {
+            for item in items.iter().filter(|i| i.parent_id.as_deref() == parent_id) {
+                tree.push((depth, item.clone()));
+                add_children(items, Some(&item.id), depth + 1, tree);
+            }
+        }
+        let mut items: Vec<_> = self
+            .menu_items
+            .read()
+            .unwrap()
+            .values()
+            .filter(|i| i.location == location)
+            .cloned()
+            .collect();
+        items.sort_by_key(|i| (i.position, i.created_at));
+        let mut tree = Vec::new();
+        add_children(&items, None, 0, &mut tree);
+        tree
+    }
+
+    /// Address of a menu link, `None` for unpublished or missing pages and posts
+    /// Only the page and post locks are taken, so menus can be rendered while other locks are held
+    pub fn menu_href(&self, link: &MenuLink) -> Option<String> {
+        match link {
+            MenuLink::Page(id) => {
+                let pages = self.pages.read().unwrap();
+                let page = pages.get(id).filter(|p| p.is_published())?;
+                if self.home_page_id.read().unwrap().as_deref() == Some(page.id.as_str()) {
+                    Some("/".to_string())
+                } else {
+                    Some(format!("/{}", page.slug))
+                }
+            }
+            MenuLink::Category(id) => Some(format!("/shop/category/{}", id)),
+            MenuLink::Post(id) => {
+                let posts = self.posts.read().unwrap();
+                let post = posts.get(id).filter(|p| p.is_published())?;
+                Some(format!("/blog/{}", post.id))
+            }
+            MenuLink::Url(url) => Some(url.clone()),
+        }
+    }
+
+    /// Visible entries of a menu, items whose link can't be resolved are left out with their children
+    pub fn menu(&self, location: MenuLocation) -> Vec<MenuEntry> {
+        fn entries(store: &Store, items: &[MenuItem], parent_id: Option<&str>) -> Vec<MenuEntry> {
+            items
+                .iter()
+                .filter(|i| i.parent_id.as_deref() == parent_id)
+                .filter_map(|item| {
+                    Some(MenuEntry {
+                        label: item.label.clone(),
+                        href: store.menu_href(&item.link)?,
+                        children: entries(store, items, Some(&item.id)),
+                    })
+                })
+                .collect()
+        }
+        let items: Vec<_> = self.menu_tree(location).into_iter().map(|(_, item)| item).collect();
+        entries(self, &items, None)
+    }
+
+    /// Adds a new post and records its first revision
+    pub fn add_post(&self, post: Post) {
+        let revision = PostRevision::new(&post, post.author_id.clone(), None);
+        self.posts.write().unwrap().insert(post.id.clone(), post);
+        self.post_revisions.write().unwrap().insert(revision.id.clone(), revision);
+    }
+
+    /// Changes the text of a post and records the result as a revision saved by `editor_id`
+    pub fn update_post(&self, id: &str, title: String, content: String, excerpt: String, editor_id: String) -> Result<(), String> {
+        let mut posts = self.posts.write().unwrap();
+        let post = posts.get_mut(id).ok_or_else(|| "Post not found".to_string())?;
+        post.update(title, content, excerpt);
+        let revision = PostRevision::new(post, editor_id, None);
+        self.post_revisions.write().unwrap().insert(revision.id.clone(), revision);
+        Ok(())
+    }
+
+    /// Deletes a post with its revisions and comments
+    pub fn delete_post(&self, id: &str) {
+        self.posts.write().unwrap().remove(id);
+        self.post_revisions.write().unwrap().retain(|_, r| r.post_id != id);
+        self.comments.write().unwrap().retain(|_, c| c.post_id != id);
+        self.remove_menu_links(&MenuLink::Post(id.to_string()));
+    }
+
+    /// Adds a reader comment to the moderation queue
+    /// `visitor` identifies the sender for the rate limit, comments matching a spam keyword go to spam
+    pub fn submit_comment(&self, mut comment: Comment, visitor: &str) -> Result<CommentStatus, String> {
+        match self.posts.read().unwrap().get(&comment.post_id) {
+            Some(post) if post.is_published() && post.comments_enabled => {}
+            Some(post) if post.is_published() => return Err("Comments are closed for this post".to_string()),
+            _ => return Err("Post not found".to_string()),
+        }
+        if comment.author_name.trim().is_empty() || comment.body.trim().is_empty() {
+            return Err("Please enter your name and a comment".to_string());
+        }
+
+        let mut comments = self.comments.write().unwrap();
+        if let Some(parent_id) = &comment.parent_id {
+            match comments.get(parent_id) {
+                Some(parent) if parent.post_id == comment.post_id && parent.status == CommentStatus::Approved => {}
+                _ => return Err("The comment you replied to is no longer available".to_string()),
+            }
+        }
+
+        // Only submissions within the window count towards the limit
+        let now = Utc::now();
+        let mut submissions = self.comment_submissions.write().unwrap();
+        let recent = submissions.entry(visitor.to_string()).or_default();
+        recent.retain(|at| *at > now - Duration::minutes(COMMENT_RATE_WINDOW_MINUTES));
+        if recent.len() >= COMMENT_RATE_LIMIT {
+            return Err(format!(
+                "You can post {} comments every {} minutes, please try again later",
+                COMMENT_RATE_LIMIT, COMMENT_RATE_WINDOW_MINUTES
+            ));
+        }
+        recent.push(now);
+
+        if let Some(keyword) = comment.matches_keyword(&self.spam_keywords.read().unwrap()) {
+            tracing::info!("Comment by {} marked as spam for \"{}\"", comment.author_email, keyword);
+            comment.status = CommentStatus::Spam;
+        }
+        let status = comment.status;
+        comments.insert(comment.id.clone(), comment);
+        Ok(status)
+    }
+
+    /// Moderates a comment
+    /// Approving a reply notifies the author of the parent comment if they asked for it
+    pub fn set_comment_status(&self, id: &str, status: CommentStatus) {
+        let (reply, parent) = {
+            let mut comments = self.comments.write().unwrap();
+            let comment = match comments.get_mut(id) {
+                Some(comment) if comment.status != status => comment,
+                _ => return,
+            };
+            comment.status = status;
+            comment.updated_at = Utc::now();
+            let reply = comment.clone();
+            let parent = reply.parent_id.as_ref().and_then(|parent_id| comments.get(parent_id)).cloned();
+            (reply, parent)
+        };
+
+        // The comments lock is released first, pages read posts before comments
+        let parent = parent.filter(|p| p.notify_replies && p.author_email != reply.author_email);
+        if let (CommentStatus::Approved, Some(parent)) = (status, parent) {
+            if let Some(post) = self.posts.read().unwrap().get(&reply.post_id) {
+                self.mailer.send(templates::comment_reply(&self.settings(), &parent, &reply, post));
+            }
+        }
+    }
+
+    /// Changes the name and text of a comment
+    pub fn update_comment(&self, id: &str, author_name: String, body: String) -> Result<(), String> {
+        if author_name.trim().is_empty() || body.trim().is_empty() {
+            return Err("A comment needs a name and a text".to_string());
+        }
+        let mut comments = self.comments.write().unwrap();
+        let comment = comments.get_mut(id).ok_or_else(|| "Comment not found".to_string())?;
+        comment.author_name = author_name;
+        comment.body = body;
+        comment.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Deletes a comment, its replies move up to the parent
+    pub fn delete_comment(&self, id: &str) {
+        let mut comments = self.comments.write().unwrap();
+        if let Some(removed) = comments.remove(id) {
+            for reply in comments.values_mut().filter(|c| c.parent_id.as_deref() == Some(id)) {
+                reply.parent_id = removed.parent_id.clone();
+            }
+        }
+    }
+
+    /// Revisions of a post, newest first
+    pub fn post_revisions(&self, post_id: &str) -> Vec<PostRevision> {
+        let mut revisions: Vec<_> = self
+            .post_revisions
+            .read()
+            .unwrap()
+            .values()
+            .filter(|r| r.post_id == post_id)
+            .cloned()
+            .collect();
+        revisions.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+        revisions
+    }
+
+    /// Brings back the text of an earlier revision
+    /// The restore is recorded as a new revision so no history is lost
+    pub fn restore_revision(&self, post_id: &str, revision_id: &str, editor_id: String) -> Result<(), String> {
+        let old = self
+            .post_revisions
+            .read()
+            .unwrap()
+            .get(revision_id)
+            .filter(|r| r.post_id == post_id)
+            .cloned()
+            .ok_or_else(|| "Revision not found".to_string())?;
+
+        let mut posts = self.posts.write().unwrap();
+        let post = posts.get_mut(post_id).ok_or_else(|| "Post not found".to_string())?;
+        post.update(old.title, old.content, old.excerpt);
+        let revision = PostRevision::new(post, editor_id, Some(old.id));
+        self.post_revisions.write().unwrap().insert(revision.id.clone(), revision);
+        Ok(())
+    }
+
+    /// Moves a post along the editorial workflow
+    /// Posts need a reviewer before they can be submitted for review
+    pub fn set_post_status(&self, id: &str, status: PostStatus) -> Result<(), String> {
+        let mut posts = self.posts.write().unwrap();
+        let post = posts.get_mut(id).ok_or("Post not found")?;
+        if status == PostStatus::InReview && post.reviewer_id.is_none() {
+            return Err("Assign a reviewer before submitting the post for review".to_string());
+        }
+        post.set_status(status)
+    }
+
+    /// Publishes scheduled posts whose time has come
+    /// Returns the number of posts published
+    pub fn publish_due_posts(&self) -> usize {
+        let now = Utc::now();
+        let mut published = 0;
+        for post in self.posts.write().unwrap().values_mut().filter(|p| p.is_due(now)) {
+            post.publish();
+            tracing::info!("Published scheduled post \"{}\"", post.title);
+            published += 1;
+        }
+        published
+    }
+
+    /// Stores an uploaded image and adds it to the media library
+    pub fn upload_media(&self, original_name: &str, bytes: &[u8]) -> Result<MediaAsset, String> {
+        let asset = self.media.save(original_name, bytes)?;
+        self.media_assets.write().unwrap().insert(asset.id.clone(), asset.clone());
+        Ok(asset)
+    }
+
+    /// Finds the media asset an image URL points to
+    pub fn media_by_url(&self, url: &str) -> Option<MediaAsset> {
+        self.media_assets
+            .read()
+            .unwrap()
+            .values()
+            .find(|asset| asset.variants.iter().any(|v| v.url() == url))
+            .cloned()
+    }
+
+    /// Deletes a media asset and removes it from the products and posts using it
+    pub fn delete_media(&self, id: &str) {
+        let asset = match self.media_assets.write().unwrap().remove(id) {
+            Some(asset) => asset,
+            None => return,
+        };
+        let url = asset.url();
+        for product in self.products.write().unwrap().values_mut() {
+            product.images.retain(|image| image.url != url);
+        }
+        for post in self.posts.write().unwrap().values_mut() {
+            if post.image_url.as_deref() == Some(url.as_str()) {
+                post.image_url = None;
+            }
+        }
+        for author in self.authors.write().unwrap().values_mut() {
+            if author.avatar_url.as_deref() == Some(url.as_str()) {
+                author.avatar_url = None;
+            }
+        }
+        let mut settings = self.settings.write().unwrap();
+        if settings.logo_url.as_deref() == Some(url.as_str()) {
+            settings.logo_url = None;
+            if let Err(error) = settings.save(&self.settings_file) {
+                tracing::warn!("Logo removed but settings not saved: {}", error);
+            }
+        }
+        drop(settings);
+        self.media.delete(&asset);
     }
 
     /// Initializes the store with some sample data
     /// This is useful for demonstration and testing purposes
     /// Creates categories, products, blog posts with realistic content
     pub fn init_with_sample_data(&self) {
+        // Create sample currencies
+        let usd = Currency::new("USD".to_string(), "US Dollar".to_string(), "$".to_string(), 1.0, "en-US".to_string(), 2);
+        let eur = Currency::new("EUR".to_string(), "Euro".to_string(), "€".to_string(), 0.92, "de-DE".to_string(), 2);
+        let gbp = Currency::new("GBP".to_string(), "British Pound".to_string(), "£".to_string(), 0.79, "en-GB".to_string(), 2);
+
+        self.currencies.write().unwrap().insert(usd.code.clone(), usd);
+        self.currencies.write().unwrap().insert(eur.code.clone(), eur);
+        self.currencies.write().unwrap().insert(gbp.code.clone(), gbp);
+
         // Create sample categories
-        let electronics = Category::new(
+        let mut electronics = Category::new(
             "Electronics".to_string(),
             "Electronic devices and gadgets".to_string(),
         );
-        let clothing = Category::new(
+        let mut clothing = Category::new(
             "Clothing".to_string(),
             "Fashion and apparel".to_string(),
         );
-        let books = Category::new(
+        let mut books = Category::new(
             "Books".to_string(),
             "Books and educational materials".to_string(),
         );
 
+        // Category specific product attributes
+        let screen_size = AttributeDefinition::new("Screen Size".to_string(), AttributeType::Number, "", Some("in".to_string()), true);
+        let wireless = AttributeDefinition::new("Wireless".to_string(), AttributeType::Boolean, "", None, true);
+        let brand = AttributeDefinition::new("Brand".to_string(), AttributeType::Select, "Apex, Sonora, Voltix", None, true);
+        let (screen_size_id, wireless_id, brand_id) = (screen_size.id.clone(), wireless.id.clone(), brand.id.clone());
+        electronics.attributes.extend([screen_size, wireless, brand]);
+
+        let material = AttributeDefinition::new("Material".to_string(), AttributeType::Select, "Cotton, Polyester, Wool", None, true);
+        let material_id = material.id.clone();
+        clothing.attributes.push(material);
+
+        let author = AttributeDefinition::new("Author".to_string(), AttributeType::Text, "", None, true);
+        let isbn = AttributeDefinition::new("ISBN".to_string(), AttributeType::Text, "", None, false);
+        let format = AttributeDefinition::new("Format".to_string(), AttributeType::Select, "Paperback, Hardcover, E-book", None, true);
+        let (author_id, isbn_id, format_id) = (author.id.clone(), isbn.id.clone(), format.id.clone());
+        books.attributes.extend([author, isbn, format]);
+
         let electronics_id = electronics.id.clone();
         let clothing_id = clothing.id.clone();
         let books_id = books.id.clone();
@@ -51,28 +1603,40 @@ impl Store {
         self.categories.write().unwrap().insert(books.id.clone(), books);
 
         // Create sample products
-        let laptop = Product::new(
+        let mut laptop = Product::new(
             "Gaming Laptop".to_string(),
             "High-performance laptop for gaming and professional work".to_string(),
             1299.99,
             electronics_id.clone(),
             15,
         );
-        let headphones = Product::new(
+        laptop.price_overrides.insert("EUR".to_string(), 1199.00);
+        let mut headphones = Product::new(
             "Wireless Headphones".to_string(),
             "Noise-canceling wireless headphones with premium sound quality".to_string(),
             249.99,
             electronics_id.clone(),
             30,
         );
-        let tshirt = Product::new(
+        headphones.sale_price = Some(199.99);
+        headphones.sale_ends_at = Some(Utc::now() + Duration::days(14));
+        let mut tshirt = Product::new(
             "Cotton T-Shirt".to_string(),
             "Comfortable cotton t-shirt available in multiple colors".to_string(),
             29.99,
             clothing_id.clone(),
-            100,
+            0,
         );
-        let rust_book = Product::new(
+        tshirt.add_option(ProductOption::new("Color".to_string(), "White, Black, Navy"));
+        tshirt.add_option(ProductOption::new("Size".to_string(), "S, M, L, XL"));
+        tshirt.generate_variants();
+        for (i, variant) in tshirt.variants.iter_mut().enumerate() {
+            variant.stock = (i as i32 * 7) % 12;
+            if variant.options.get("Size").map(String::as_str) == Some("XL") {
+                variant.price = Some(32.99);
+            }
+        }
+        let mut rust_book = Product::new(
             "Rust Programming Book".to_string(),
             "Complete guide to Rust programming language".to_string(),
             49.99,
@@ -80,11 +1644,79 @@ impl Store {
             50,
         );
 
+        laptop.attributes.insert(screen_size_id.clone(), AttributeValue::Number(15.6));
+        laptop.attributes.insert(wireless_id.clone(), AttributeValue::Boolean(false));
+        laptop.attributes.insert(brand_id.clone(), AttributeValue::Text("Apex".to_string()));
+        headphones.attributes.insert(wireless_id, AttributeValue::Boolean(true));
+        headphones.attributes.insert(brand_id, AttributeValue::Text("Sonora".to_string()));
+        tshirt.attributes.insert(material_id, AttributeValue::Text("Cotton".to_string()));
+        rust_book.attributes.insert(author_id, AttributeValue::Text("Steve Klabnik".to_string()));
+        rust_book.attributes.insert(isbn_id, AttributeValue::Text("978-1718503106".to_string()));
+        rust_book.attributes.insert(format_id, AttributeValue::Text("Paperback".to_string()));
+        rust_book.tax_class = TaxClass::Reduced;
+
+        laptop.weight = 2.4;
+        laptop.dimensions = Some(Dimensions { length: 40.0, width: 30.0, height: 8.0 });
+        headphones.weight = 0.6;
+        headphones.dimensions = Some(Dimensions { length: 22.0, width: 20.0, height: 10.0 });
+        tshirt.weight = 0.2;
+        rust_book.weight = 1.1;
+        rust_book.dimensions = Some(Dimensions { length: 23.0, width: 18.0, height: 4.0 });
+
+        laptop.tag_ids = self.resolve_tags("Gaming, Bestseller");
+        headphones.tag_ids = self.resolve_tags("Audio, Wireless, Bestseller");
+        tshirt.tag_ids = self.resolve_tags("Cotton");
+        rust_book.tag_ids = self.resolve_tags("Rust, Programming");
+
+        for product in [&laptop, &headphones, &tshirt, &rust_book] {
+            self.record_initial_stock(product, "Admin");
+        }
+
         self.products.write().unwrap().insert(laptop.id.clone(), laptop);
         self.products.write().unwrap().insert(headphones.id.clone(), headphones);
         self.products.write().unwrap().insert(tshirt.id.clone(), tshirt);
         self.products.write().unwrap().insert(rust_book.id.clone(), rust_book);
 
+        // Create sample tax rates
+        let tax_rates = [
+            TaxRate::new("US-CA".to_string(), "California".to_string(), "Sales Tax".to_string(), 7.25, 0.0),
+            TaxRate::new("US-NY".to_string(), "New York".to_string(), "Sales Tax".to_string(), 8.875, 0.0),
+            TaxRate::new("DE".to_string(), "Germany".to_string(), "VAT".to_string(), 19.0, 7.0),
+            TaxRate::new("GB".to_string(), "United Kingdom".to_string(), "VAT".to_string(), 20.0, 0.0),
+        ];
+        for rate in tax_rates {
+            self.tax_rates.write().unwrap().insert(rate.region.clone(), rate);
+        }
+
+        // Create sample shipping zones
+        let mut domestic = ShippingZone::new("United States".to_string(), "US");
+        domestic.methods.push(ShippingMethod::new(
+            "Standard Shipping".to_string(), ShippingRateType::FreeOverThreshold, 7.99, 0.0, 100.0,
+        ));
+        domestic.methods.push(ShippingMethod::new(
+            "Express Shipping".to_string(), ShippingRateType::WeightBased, 14.99, 2.5, 0.0,
+        ));
+        domestic.methods.push(ShippingMethod::new(
+            "Local Pickup".to_string(), ShippingRateType::LocalPickup, 0.0, 0.0, 0.0,
+        ));
+        let mut europe = ShippingZone::new("Europe".to_string(), "DE, FR, GB");
+        europe.methods.push(ShippingMethod::new(
+            "International Shipping".to_string(), ShippingRateType::WeightBased, 19.99, 4.0, 0.0,
+        ));
+        self.shipping_zones.write().unwrap().insert(domestic.id.clone(), domestic);
+        self.shipping_zones.write().unwrap().insert(europe.id.clone(), europe);
+
+        // Create sample coupons
+        let welcome = Coupon::new("WELCOME10".to_string(), DiscountType::Percentage, 10.0);
+        let mut books_coupon = Coupon::new("BOOKS5".to_string(), DiscountType::FixedAmount, 5.0);
+        books_coupon.category_ids.push(books_id.clone());
+        let mut free_shipping = Coupon::new("FREESHIP".to_string(), DiscountType::FreeShipping, 0.0);
+        free_shipping.minimum_order = Some(50.0);
+
+        self.coupons.write().unwrap().insert(welcome.id.clone(), welcome);
+        self.coupons.write().unwrap().insert(books_coupon.id.clone(), books_coupon);
+        self.coupons.write().unwrap().insert(free_shipping.id.clone(), free_shipping);
+
         // Create sample blog categories
         let tech = BlogCategory::new(
             "Technology".to_string(),
@@ -101,14 +1733,25 @@ impl Store {
         self.blog_categories.write().unwrap().insert(tech.id.clone(), tech);
         self.blog_categories.write().unwrap().insert(lifestyle.id.clone(), lifestyle);
 
+        // Create sample authors
+        let sam = self.create_author(
+            "Sam Carter".to_string(),
+            "Sam writes about Rust, web development and the tools behind our shop.".to_string(),
+        );
+        let robin = self.create_author(
+            "Robin Lee".to_string(),
+            "Robin covers productivity and life as a developer.".to_string(),
+        );
+
         // Create sample blog posts
         let mut post1 = Post::new(
             "Getting Started with Rust".to_string(),
             "Rust is a systems programming language that runs blazingly fast, prevents segfaults, and guarantees thread safety. In this article, we'll explore the basics of Rust and why it's becoming increasingly popular among developers.".to_string(),
             "Learn the basics of Rust programming language".to_string(),
             tech_id.clone(),
-            "Admin".to_string(),
+            sam.id.clone(),
         );
+        post1.tag_ids = self.resolve_tags("Rust, Programming, Beginners");
         post1.publish();
 
         let mut post2 = Post::new(
@@ -116,8 +1759,9 @@ impl Store {
             "Web development with Rust has become much easier with modern frameworks like Axum, Actix-web, and Rocket. This post covers the fundamentals of building web applications using Rust and demonstrates best practices.".to_string(),
             "How to build modern web apps with Rust".to_string(),
             tech_id.clone(),
-            "Admin".to_string(),
+            sam.id.clone(),
         );
+        post2.tag_ids = self.resolve_tags("Rust, Web, Programming");
         post2.publish();
 
         let mut post3 = Post::new(
@@ -125,19 +1769,151 @@ impl Store {
             "As developers, we're always looking for ways to improve our productivity. Here are some proven strategies that can help you write better code faster and maintain a healthy work-life balance.".to_string(),
             "Improve your coding productivity".to_string(),
             lifestyle_id.clone(),
-            "Admin".to_string(),
+            robin.id.clone(),
         );
+        post3.tag_ids = self.resolve_tags("Productivity");
         post3.publish();
 
-        self.posts.write().unwrap().insert(post1.id.clone(), post1);
-        self.posts.write().unwrap().insert(post2.id.clone(), post2);
-        self.posts.write().unwrap().insert(post3.id.clone(), post3);
+        let post1_id = post1.id.clone();
+        self.add_post(post1);
+        self.add_post(post2);
+        self.add_post(post3);
+
+        // Create sample comments
+        *self.spam_keywords.write().unwrap() = ["casino", "viagra", "crypto giveaway", "cheap pills"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let mut question = Comment::new(
+            post1_id.clone(),
+            None,
+            "Alex".to_string(),
+            "alex@example.com".to_string(),
+            "Is the borrow checker as hard as everyone says?".to_string(),
+            true,
+        );
+        question.status = CommentStatus::Approved;
+        let mut answer = Comment::new(
+            post1_id,
+            Some(question.id.clone()),
+            "Sam Carter".to_string(),
+            "sam@example.com".to_string(),
+            "It takes a few weeks to get used to, after that it mostly helps.".to_string(),
+            false,
+        );
+        answer.status = CommentStatus::Approved;
+        self.comments.write().unwrap().insert(question.id.clone(), question);
+        self.comments.write().unwrap().insert(answer.id.clone(), answer);
+
+        // Create sample pages
+        let settings = self.settings();
+        let home = self.create_page("Home".to_string(), default_home_body(&settings), None);
+        let about = self.create_page(
+            "About Us".to_string(),
+            format!(
+                "<p>{} started as a small shop for developers and makers. Today we ship quality products worldwide and write about the things we love.</p>",
+                escape_html(&settings.site_name)
+            ),
+            None,
+        );
+        let contact = self.create_page(
+            "Contact".to_string(),
+            format!(
+                "<p>Questions about an order or a product? Write to us at <a href=\"mailto:{0}\">{0}</a> and we'll get back to you within one business day.</p>",
+                escape_html(&settings.contact_email)
+            ),
+            Some(about.id.clone()),
+        );
+        let terms = self.create_page(
+            "Terms of Service".to_string(),
+            "<p>By placing an order you agree to these terms. Prices include the taxes shown at checkout. Orders can be returned within 30 days of delivery.</p>".to_string(),
+            None,
+        );
+        let privacy = self.create_page(
+            "Privacy Policy".to_string(),
+            "<p>We only use your personal data to process your orders and, if you ask us to, to send you emails about new articles.</p>".to_string(),
+            Some(terms.id.clone()),
+        );
+        for page in [&home, &about, &contact, &terms, &privacy] {
+            if let Some(page) = self.pages.write().unwrap().get_mut(&page.id) {
+                page.status = PageStatus::Published;
+            }
+        }
+        *self.home_page_id.write().unwrap() = Some(home.id.clone());
+
+        // Create sample menus
+        let header = |label: &str, link: MenuLink, parent_id: Option<String>| {
+            let item = MenuItem::new(MenuLocation::Header, label.to_string(), link, parent_id);
+            let id = item.id.clone();
+            let _ = self.add_menu_item(item);
+            id
+        };
+        header("Home", MenuLink::Page(home.id.clone()), None);
+        let shop = header("Shop", MenuLink::Url("/shop".to_string()), None);
+        let mut categories_vec: Vec<_> = self.categories.read().unwrap().values().cloned().collect();
+        categories_vec.sort_by(|a, b| a.name.cmp(&b.name));
+        for category in categories_vec {
+            header(&category.name, MenuLink::Category(category.id), Some(shop.clone()));
+        }
+        header("Blog", MenuLink::Url("/blog".to_string()), None);
+        let about_item = header("About", MenuLink::Page(about.id.clone()), None);
+        header("Contact", MenuLink::Page(contact.id.clone()), Some(about_item));
+        for (label, page) in [("About Us", &about), ("Contact", &contact), ("Terms of Service", &terms), ("Privacy Policy", &privacy)] {
+            let _ = self.add_menu_item(MenuItem::new(MenuLocation::Footer, label.to_string(), MenuLink::Page(page.id.clone()), None));
+        }
     }
 }
 
-impl Default for Store {
-    fn default() -> Self {
-        Self::new()
+/// Homepage shown until a page is chosen as the homepage, welcoming visitors with the site name and tagline
+pub fn default_home_body(settings: &SiteSettings) -> String {
+    format!(
+        r#"<section class="hero">
+            <h1>Welcome to {}</h1>
+            <p>{}</p>
+            <div class="hero-buttons">
+                <a href="/shop" class="btn btn-primary">Browse Products</a>
+                <a href="/blog" class="btn btn-secondary">Read Blog</a>
+            </div>
+        </section>
+        <section class="features">
+            <div class="feature">
+                <h3>Quality Products</h3>
+                <p>We offer only the best products carefuly selected for you</p>
+            </div>
+            <div class="feature">
+                <h3>Fast Shipping</h3>
+                <p>Get your orders delivered quickly and securly</p>
+            </div>
+            <div class="feature">
+                <h3>Great Content</h3>
+                <p>Stay updated with our informative blog posts</p>
+            </div>
+        </section>"#,
+        escape_html(&settings.site_name),
+        escape_html(&settings.tagline)
+    )
+}
+
+/// Keeps crawlers out of the admin, carts and customer pages
+const DEFAULT_ROBOTS_TXT: &str = "User-agent: *
+Disallow: /admin
+Disallow: /cart
+Disallow: /checkout
+Disallow: /account
+Disallow: /order/
+";
+
+/// Stock level of a product or one of its variants
+/// Products with variants only track stock per variant
+fn stock_of<'a>(product: &'a mut Product, variant_id: Option<&str>) -> Option<&'a mut i32> {
+    match variant_id {
+        Some(variant_id) => Some(&mut product.variants.iter_mut().find(|v| v.id == variant_id)?.stock),
+        None if !product.variants.is_empty() => None,
+        None => Some(&mut product.stock),
     }
 }
 
+/// Generates an unguessable token for sessions and reset links
+fn random_token() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}